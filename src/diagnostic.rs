@@ -0,0 +1,205 @@
+use serde::Serialize;
+use std::fmt::Display;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+// Line and column (in chars) both start at 1 (0 when unknown)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct SourcePosition {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl SourcePosition {
+    pub fn new(line: usize, column: usize) -> Self {
+        SourcePosition { line, column }
+    }
+
+    // Position of the char at the given byte offset
+    pub fn from_offset(source: &str, offset: usize) -> Self {
+        let before = &source[..offset];
+        let line_start = before.rfind('\n').map(|pos| pos + 1).unwrap_or(0);
+
+        SourcePosition {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+
+    // Position right after the given text, if it starts at this position
+    pub fn advanced_by(&self, text: &str) -> Self {
+        match text.rfind('\n') {
+            Some(pos) => SourcePosition {
+                line: self.line + text.matches('\n').count(),
+                column: text[pos + 1..].chars().count() + 1,
+            },
+            None => SourcePosition {
+                line: self.line,
+                column: self.column + text.chars().count(),
+            },
+        }
+    }
+}
+
+// The end position is exclusive
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct SourceRange {
+    pub start: SourcePosition,
+    pub end: SourcePosition,
+}
+
+impl SourceRange {
+    pub fn new(start: SourcePosition, end: SourcePosition) -> Self {
+        SourceRange { start, end }
+    }
+}
+
+// A problem found in a source file, e.g.:
+//
+// error[P001]: expected ';' after method declaration
+//  --> IPotato.aidl:4:31
+//   |
+// 4 |     String method1(int a) = 3
+//   |                               ^
+//   = note: ...
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: String,
+    pub message: String,
+    pub path: Option<String>,
+    pub range: Option<SourceRange>,
+    pub source_line: Option<String>,
+    pub notes: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn new<S1: Into<String>, S2: Into<String>>(
+        severity: Severity,
+        code: S1,
+        message: S2,
+    ) -> Self {
+        Diagnostic {
+            severity,
+            code: code.into(),
+            message: message.into(),
+            path: None,
+            range: None,
+            source_line: None,
+            notes: Vec::new(),
+        }
+    }
+
+    // Set the range and the (first) line of the source it refers to
+    pub fn set_location(&mut self, source: &str, start_offset: usize, end_offset: usize) {
+        let start = SourcePosition::from_offset(source, start_offset);
+        let end = SourcePosition::from_offset(source, end_offset.max(start_offset));
+
+        self.range = Some(SourceRange::new(start, end));
+        self.source_line = source.lines().nth(start.line - 1).map(|l| l.to_string());
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}[{}]: {}", self.severity, self.code, self.message)?;
+
+        let gutter_width = self
+            .range
+            .map(|r| r.start.line.to_string().len())
+            .unwrap_or(0);
+        let gutter = " ".repeat(gutter_width);
+
+        match (&self.path, &self.range) {
+            (Some(path), Some(range)) => writeln!(
+                f,
+                "{}--> {}:{}:{}",
+                gutter, path, range.start.line, range.start.column
+            )?,
+            (Some(path), None) => writeln!(f, "{}--> {}", gutter, path)?,
+            (None, Some(range)) => writeln!(
+                f,
+                "{}--> {}:{}",
+                gutter, range.start.line, range.start.column
+            )?,
+            (None, None) => (),
+        }
+
+        if let (Some(range), Some(source_line)) = (&self.range, &self.source_line) {
+            // Underline up to the end of the range (or of the line)
+            let line_length = source_line.chars().count();
+            let caret_start = range.start.column.min(line_length + 1);
+            let caret_end = if range.end.line == range.start.line {
+                range.end.column.min(line_length + 1)
+            } else {
+                line_length + 1
+            };
+            let caret_count = caret_end.saturating_sub(caret_start).max(1);
+
+            writeln!(f, "{} |", gutter)?;
+            writeln!(f, "{} | {}", range.start.line, source_line)?;
+            writeln!(
+                f,
+                "{} | {}{}",
+                gutter,
+                " ".repeat(caret_start - 1),
+                "^".repeat(caret_count)
+            )?;
+        }
+
+        for note in &self.notes {
+            writeln!(f, "{} = note: {}", gutter, note)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for Diagnostic {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let source = "package a;\ninterface IFoo {\n    void f(int a) = 3\n}\n";
+        let offset = source.find(") = 3").unwrap() + 1;
+
+        let mut diagnostic = Diagnostic::new(Severity::Error, "P001", "expected ';'");
+        diagnostic.path = Some("IFoo.aidl".to_string());
+        diagnostic.set_location(source, offset, offset + " = 3".len());
+        diagnostic.notes.push("the note".to_string());
+        assert_eq!(
+            diagnostic.to_string(),
+            "error[P001]: expected ';'\n \
+             --> IFoo.aidl:3:18\n  \
+             |\n\
+             3 |     void f(int a) = 3\n  \
+             |                  ^^^^\n  \
+             = note: the note\n"
+        );
+
+        // Ranges over several lines are underlined up to the end of the line
+        diagnostic.set_location(source, offset, source.len());
+        assert!(diagnostic
+            .to_string()
+            .contains("3 |     void f(int a) = 3\n  |                  ^^^^\n"));
+
+        let diagnostic = Diagnostic::new(Severity::Warning, "M004", "unused import");
+        assert_eq!(diagnostic.to_string(), "warning[M004]: unused import\n");
+    }
+}
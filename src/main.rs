@@ -2,12 +2,17 @@
 
 #![recursion_limit = "10240"]
 
+mod diagnostic;
 mod model;
 mod parser;
 mod ui;
 mod ui_controller;
 mod ui_state;
 
+use std::path::PathBuf;
+use walkdir::WalkDir;
+
+use crate::diagnostic::Severity;
 use crate::parser::aidl;
 use crate::ui_controller::UiController;
 
@@ -23,6 +28,18 @@ use crate::ui_controller::UiController;
 // - check recursive usage of Rc
 
 pub fn main() -> jane_eyre::Result<(), Box<dyn std::error::Error>> {
+    // Headless commands, e.g.:
+    // - vuk --validate path/to/aidl
+    // - vuk fmt [--check] path/to/aidl path/to/IFoo.aidl
+    let args: Vec<String> = std::env::args().collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        [_, "--validate", path] => return validate(path),
+        [_, "fmt", "--check", paths @ ..] => return format_files(paths, true),
+        [_, "fmt", paths @ ..] => return format_files(paths, false),
+        _ => (),
+    }
+
     std::env::set_var("WINIT_UNIX_BACKEND", "x11");
     std::env::set_var("QT_QUICK_CONTROLS_STYLE", "Universal");
     std::env::set_var("QT_ENABLE_HIGHDPI_SCALING", "1");
@@ -30,15 +47,93 @@ pub fn main() -> jane_eyre::Result<(), Box<dyn std::error::Error>> {
     ui::run()
 }
 
+// The broken files and the problems are printed, the exit code is 1 if
+// there is any error
+fn validate(path: &str) -> jane_eyre::Result<(), Box<dyn std::error::Error>> {
+    let controller = UiController::open(&PathBuf::from(path))?;
+
+    controller
+        .broken_files
+        .iter()
+        .for_each(|f| println!("{}", f));
+    controller
+        .problems
+        .iter()
+        .for_each(|p| println!("{}", p.diagnostic));
+
+    let has_errors = !controller.broken_files.is_empty()
+        || controller
+            .problems
+            .iter()
+            .any(|p| p.diagnostic.severity == Severity::Error);
+    if has_errors {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+// The files are formatted in place, unless checking: then the files which
+// are not formatted are printed (the exit code is 1 if there is any, if a
+// file cannot be parsed, or if there is no file to format, e.g. an empty
+// glob in a pre-submit hook)
+fn format_files(paths: &[&str], check: bool) -> jane_eyre::Result<(), Box<dyn std::error::Error>> {
+    if paths.is_empty() {
+        eprintln!("usage: vuk fmt [--check] <path>...");
+        std::process::exit(1);
+    }
+
+    let aidl_file_entries = paths
+        .iter()
+        .flat_map(WalkDir::new)
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "aidl"));
+
+    let mut failed = false;
+    let mut file_count = 0;
+    for entry in aidl_file_entries {
+        file_count += 1;
+        let source = std::fs::read_to_string(entry.path())?;
+
+        match aidl::format(&source) {
+            Ok(formatted) if formatted == source => (),
+            Ok(_) if check => {
+                println!("{} is not formatted", entry.path().display());
+                failed = true;
+            }
+            Ok(formatted) => std::fs::write(entry.path(), formatted)?,
+            Err(mut diagnostic) => {
+                diagnostic.path = Some(entry.path().to_string_lossy().to_string());
+                println!("{}", diagnostic);
+                failed = true;
+            }
+        }
+    }
+
+    if file_count == 0 {
+        eprintln!("no .aidl file in {}", paths.join(" "));
+        failed = true;
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
 pub fn create_ui_controller(
     aidl: &'static str,
 ) -> Result<UiController, Box<dyn std::error::Error>> {
-    let file = aidl::parse(aidl)?;
+    // Unsized, the diagnostic can be downcast (see diagnostics_json)
+    let file = aidl::parse(aidl).map_err(|d| d as Box<dyn std::error::Error>)?;
 
     let mut model = aidl::create_model(vec![file]);
-    model.resolve_types();
+    let mut problems = model.resolve_types();
+    problems.append(&mut model.validate());
 
-    Ok(UiController::new(model))
+    Ok(UiController::new_with_problems(model, Vec::new(), problems))
 }
 
 pub const TEST_AIDL: &'static str = r#"
@@ -1,3 +1,8 @@
+mod check;
+mod expression;
+mod javadoc;
+mod validation;
+
 use once_cell::unsync::OnceCell;
 use std::{
     collections::{hash_map, HashMap, HashSet},
@@ -5,16 +10,65 @@ use std::{
     rc::Rc,
 };
 
+use crate::diagnostic::SourceRange;
+
+pub use check::ItemDiagnostic;
+pub use expression::{
+    BinaryOp, EvaluationError, Expression, IntegralType, Literal, UnaryOp, Value,
+};
+pub use javadoc::{Block, Inline, Javadoc, Tag, Text};
+
 pub type Key = String;
 
+// Key of an item declared in a package or nested in another item
+// (e.g.: a.b.Foo or a.b.Outer.Inner)
+pub fn item_key(pkg: &Package, parent: Option<&Key>, name: &str) -> Key {
+    match parent {
+        Some(parent) => format!("{}.{}", parent, name),
+        None => format!("{}.{}", pkg.path, name),
+    }
+}
+
+// Maximum depth of const references followed while evaluating an expression
+const MAX_EVALUATION_DEPTH: usize = 64;
+
 #[derive(Debug, Default)]
 pub struct Model {
     pub items: HashMap<Key, Rc<Item>>,
-    pub standard_types: HashMap<Key, Rc<StandardType>>,
+    // Standard types of each IDL (e.g.: string in AIDL and in OpenAPI)
+    pub standard_types: HashMap<Idl, HashMap<Key, Rc<StandardType>>>,
+    // Items replaced by another one with the same key
+    pub duplicate_items: Vec<Rc<Item>>,
 }
 
 impl Model {
-    pub fn resolve_types(&mut self) {
+    // An item declared twice (e.g.: in two files) replaces the previous one,
+    // which is kept aside for the validation
+    pub fn insert_item(&mut self, item: Item) -> Key {
+        let key = item.get_key().clone();
+
+        if let Some(previous_item) = self.items.insert(key.clone(), Rc::new(item)) {
+            self.duplicate_items.push(previous_item);
+        }
+
+        key
+    }
+
+    // Add the items and standard types of another model (e.g.: created from
+    // the files of another IDL), before resolving the types
+    pub fn merge(&mut self, other: Model) {
+        for (key, item) in other.items {
+            if let Some(previous_item) = self.items.insert(key, item) {
+                self.duplicate_items.push(previous_item);
+            }
+        }
+
+        self.standard_types.extend(other.standard_types);
+        self.duplicate_items.extend(other.duplicate_items);
+    }
+
+    // Returns the problems found once resolved (e.g.: unresolved types)
+    pub fn resolve_types(&mut self) -> Vec<ItemDiagnostic> {
         // Go through all interfaces/structs/enums and add type
         let resolved_items = self
             .items
@@ -23,12 +77,19 @@ impl Model {
                 Item::Interface(ref i) => Item::Interface(self.resolved_interface(&i)),
                 Item::Struct(ref s) => Item::Struct(self.resolved_struct(&s)),
                 Item::Enum(ref e) => Item::Enum(self.resolved_enum(&e)),
+                Item::Union(ref u) => Item::Union(self.resolved_union(&u)),
+                Item::OpaqueStruct(ref o) => Item::OpaqueStruct(OpaqueStruct {
+                    docu: self.resolved_javadoc(&o.docu, &[], o.get_key()),
+                    ..o.clone()
+                }),
             })
             .map(|item| (item.get_key().clone(), Rc::new(item)))
             .collect();
 
         // Set the model items again
         self.items = resolved_items;
+
+        self.check_references()
     }
 
     fn resolved_interface(&self, interface: &Interface) -> Interface {
@@ -39,21 +100,19 @@ impl Model {
             .consts
             .iter()
             .map(|c| {
-                let resolved_const_type = if let Type::Unresolved(ref ut) = *c.const_type {
-                    if let Some(t) = self.get_resolved_type(ut, &imports) {
-                        t
-                    } else {
-                        c.const_type.clone()
-                    }
-                } else {
-                    c.const_type.clone()
-                };
+                let resolved_const_type =
+                    self.resolved_type(&c.const_type, &imports, interface.get_key(), &[]);
+                let computed_value =
+                    self.computed_value(&c.value, &c.const_type, interface.get_key());
                 Const::new(
                     &c.name,
                     resolved_const_type,
                     c.value.clone(),
+                    computed_value,
                     c.index,
-                    c.docu.clone(),
+                    self.resolved_javadoc(&c.docu, &imports, interface.get_key()),
+                    c.annotations.clone(),
+                    c.location.clone(),
                 )
             })
             .collect();
@@ -64,133 +123,511 @@ impl Model {
             .iter()
             .map(|m| {
                 // Try to resolve return type
-                let resolved_return_arg = self.resolved_arg(&m.return_arg, &imports);
+                let resolved_return_arg =
+                    self.resolved_arg(&m.return_arg, &imports, interface.get_key(), &[]);
 
                 // Go through all method args
                 let resolved_args = m
                     .args
                     .iter()
-                    .map(|a| self.resolved_arg(a, &imports))
+                    .map(|a| self.resolved_arg(a, &imports, interface.get_key(), &[]))
                     .collect::<Vec<Arg>>();
 
                 Method::new(
+                    m.is_one_way,
                     m.name.clone(),
                     resolved_return_arg,
                     resolved_args,
+                    m.transaction_code,
                     m.index,
-                    m.docu.clone(),
+                    self.resolved_javadoc(&m.docu, &imports, interface.get_key()),
+                    m.annotations.clone(),
+                    m.location.clone(),
                 )
             })
             .collect::<Vec<Method>>();
 
+        let resolved_super_interfaces = interface
+            .super_interfaces
+            .iter()
+            .map(|t| self.resolved_type(t, &imports, interface.get_key(), &[]))
+            .collect();
+
         Interface::new(
             interface.pkg.clone(),
+            interface.parent.clone(),
             interface.imports.clone(),
             interface.name.clone(),
-            interface.docu.clone(),
+            resolved_super_interfaces,
+            self.resolved_javadoc(&interface.docu, &imports, interface.get_key()),
             resolved_consts,
             resolved_methods,
+            interface.annotations.clone(),
+            interface.location.clone(),
         )
     }
 
     fn resolved_struct(&self, structure: &Struct) -> Struct {
-        let mut imports = structure.imports.clone(); // TODO?
-        imports.push(structure.pkg.path.clone());
+        let imports = structure.imports.clone(); // TODO?
 
         // Go through all struct members
         let resolved_members = structure
             .members
             .iter()
             .map(|m| {
-                let resolved_arg = self.resolved_arg(&m.arg, &imports);
-                Member::new(resolved_arg, m.index, structure.docu.clone())
+                let resolved_arg = self.resolved_arg(
+                    &m.arg,
+                    &imports,
+                    structure.get_key(),
+                    &structure.type_params,
+                );
+                let computed_default_value = m
+                    .default_value
+                    .as_ref()
+                    .and_then(|v| self.computed_value(v, &m.arg.arg_type, structure.get_key()));
+                Member::new(
+                    resolved_arg,
+                    m.default_value.clone(),
+                    computed_default_value,
+                    m.index,
+                    self.resolved_javadoc(&m.docu, &imports, structure.get_key()),
+                    m.annotations.clone(),
+                    m.location.clone(),
+                )
             })
             .collect();
 
         Struct::new(
             structure.pkg.clone(),
+            structure.parent.clone(),
             structure.imports.clone(),
             &structure.name,
-            &structure.docu,
+            structure.type_params.clone(),
+            self.resolved_javadoc(&structure.docu, &imports, structure.get_key()),
             resolved_members,
+            structure.annotations.clone(),
+            structure.location.clone(),
         )
     }
 
     fn resolved_enum(&self, enumeration: &Enum) -> Enum {
+        let imports = enumeration.imports.clone();
+
+        let resolved_backing_type = self.resolved_type(
+            &enumeration.backing_type,
+            &imports,
+            enumeration.get_key(),
+            &[],
+        );
+
+        // Go through all enum elements
+        let resolved_elements = enumeration
+            .elements
+            .iter()
+            .map(|e| {
+                // See check_values for the elements which cannot be evaluated
+                EnumElement {
+                    computed_value: self.evaluate_enum_element(enumeration, e.index, 0).ok(),
+                    docu: self.resolved_javadoc(&e.docu, &imports, enumeration.get_key()),
+                    ..e.clone()
+                }
+            })
+            .collect();
+
         Enum::new(
             enumeration.pkg.clone(),
+            enumeration.parent.clone(),
+            enumeration.imports.clone(),
             enumeration.name.clone(),
-            enumeration.docu.clone(),
-            enumeration.elements.clone(),
+            self.resolved_javadoc(&enumeration.docu, &imports, enumeration.get_key()),
+            resolved_backing_type,
+            resolved_elements,
+            enumeration.annotations.clone(),
+            enumeration.location.clone(),
+        )
+    }
+
+    fn resolved_union(&self, union: &Union) -> Union {
+        let imports = union.imports.clone();
+
+        // Go through all union alternatives
+        let resolved_members = union
+            .members
+            .iter()
+            .map(|m| {
+                let resolved_arg = self.resolved_arg(&m.arg, &imports, union.get_key(), &[]);
+                let computed_default_value = m
+                    .default_value
+                    .as_ref()
+                    .and_then(|v| self.computed_value(v, &m.arg.arg_type, union.get_key()));
+                Member::new(
+                    resolved_arg,
+                    m.default_value.clone(),
+                    computed_default_value,
+                    m.index,
+                    self.resolved_javadoc(&m.docu, &imports, union.get_key()),
+                    m.annotations.clone(),
+                    m.location.clone(),
+                )
+            })
+            .collect();
+
+        Union::new(
+            union.pkg.clone(),
+            union.parent.clone(),
+            union.imports.clone(),
+            &union.name,
+            self.resolved_javadoc(&union.docu, &imports, union.get_key()),
+            resolved_members,
+            union.annotations.clone(),
+            union.location.clone(),
         )
     }
 
-    fn resolved_arg(&self, arg: &Arg, imports: &[String]) -> Arg {
+    // Links are resolved from the documented item (e.g.: {@link #foo} for one
+    // of its members, or {@link Bar#foo} for a member of another item)
+    fn resolved_javadoc(&self, javadoc: &Javadoc, imports: &[String], scope: &Key) -> Javadoc {
+        let pkg_path = self.items.get(scope).map(|i| i.get_pkg().path.clone());
+
+        let mut resolved_javadoc = javadoc.clone();
+        resolved_javadoc.resolve_links(|target| {
+            let name = target.split('#').next().unwrap_or_default();
+            if name.is_empty() {
+                return Some(scope.clone());
+            }
+
+            self.find_item(
+                name,
+                pkg_path.as_deref().unwrap_or_default(),
+                imports,
+                scope,
+            )
+            .ok()
+            .flatten()
+            .map(|item| item.get_key().clone())
+        });
+
+        resolved_javadoc
+    }
+
+    // Type parameters (e.g.: A and B in Pair<A, B>) are resolved as such and
+    // never looked up in the model
+    fn resolved_arg(
+        &self,
+        arg: &Arg,
+        imports: &[String],
+        scope: &Key,
+        type_params: &[String],
+    ) -> Arg {
         let resolved_generic_args = arg
             .generic_args
             .iter()
-            .map(|ga| self.resolved_arg(ga, imports))
+            .map(|ga| self.resolved_arg(ga, imports, scope, type_params))
             .collect();
 
-        let resolved_type = if let Some(resolved_type) = self.get_resolved_arg_type(&arg, &imports)
-        {
-            resolved_type
-        } else {
-            println!("WARNING: unresolved type: {}", arg.arg_type.get_key());
-            arg.arg_type.clone() // still unresolved
-        };
+        let resolved_type = self.resolved_type(&arg.arg_type, imports, scope, type_params);
 
-        Arg::new(arg.name.clone(), resolved_type, resolved_generic_args)
+        Arg::new(
+            arg.direction,
+            arg.name.clone(),
+            resolved_type,
+            resolved_generic_args,
+            arg.annotations.clone(),
+            arg.location.clone(),
+        )
     }
 
-    fn get_resolved_arg_type(&self, arg: &Arg, imports: &[String]) -> Option<Rc<Type>> {
-        if let Type::Unresolved(ref unresolved_type) = *arg.arg_type {
-            if let Some(resolved_type) = self.get_resolved_type(unresolved_type, &imports) {
-                return Some(resolved_type);
+    fn resolved_type(
+        &self,
+        the_type: &Rc<Type>,
+        imports: &[String],
+        scope: &Key,
+        type_params: &[String],
+    ) -> Rc<Type> {
+        match **the_type {
+            Type::Array(ref array_type) => Rc::new(Type::Array(ArrayType::new(
+                self.resolved_type(&array_type.element_type, imports, scope, type_params),
+                array_type.dimensions.clone(),
+            ))),
+            Type::Unresolved(ref unresolved_type) => {
+                if let Some(type_param) = type_params.iter().find(|p| **p == unresolved_type.name) {
+                    Rc::new(Type::Parameter(type_param.clone()))
+                } else if let Ok(Some(resolved_type)) =
+                    self.get_resolved_type(unresolved_type, imports, scope)
+                {
+                    resolved_type
+                } else {
+                    the_type.clone() // still unresolved (see check_references)
+                }
             }
+            _ => the_type.clone(),
         }
+    }
 
-        return None;
+    // Standard type of the IDL of the file in which the type is referenced
+    // (e.g.: the AIDL string and not the OpenAPI string)
+    fn find_standard_type(&self, unresolved_type: &UnresolvedType) -> Option<&Rc<StandardType>> {
+        self.standard_types
+            .get(&unresolved_type.owner.idl)
+            .and_then(|standard_types| standard_types.get(&unresolved_type.name))
     }
 
+    // The scope is the key of the item in which the type is referenced, so
+    // that nested items can be referenced by their simple name.
     fn get_resolved_type(
         &self,
         unresolved_type: &UnresolvedType,
         imports: &[String],
-    ) -> Option<Rc<Type>> {
+        scope: &Key,
+    ) -> Result<Option<Rc<Type>>, Error> {
         // Try to resolve primitive (key = name)
-        if let Some(standard_type) = self.standard_types.get(&unresolved_type.name) {
-            return Some(Rc::new(Type::Standard(standard_type.clone())));
+        if let Some(standard_type) = self.find_standard_type(unresolved_type) {
+            return Ok(Some(Rc::new(Type::Standard(standard_type.clone()))));
+        }
+
+        let item = self.find_item(
+            &unresolved_type.name,
+            &unresolved_type.owner.path,
+            imports,
+            scope,
+        )?;
+
+        Ok(item.map(|item| Rc::new(Type::Item(item.clone()))))
+    }
+
+    // Look for the item referenced by name (e.g.: Foo, Outer.Inner or
+    // a.b.Foo), the first matching rule wins:
+    // 1. items nested in the scope or in one of its outer items
+    // 2. fully qualified names
+    // 3. single-type imports (e.g.: import a.b.Outer; for Outer.Inner)
+    // 4. items of the same package
+    // 5. wildcard imports (e.g.: import a.b.*; or import a.b.Outer.*;)
+    fn find_item(
+        &self,
+        name: &str,
+        pkg_path: &str,
+        imports: &[String],
+        scope: &Key,
+    ) -> Result<Option<&Rc<Item>>, Error> {
+        // Try with items nested in the scope or in one of its outer items
+        let mut current_scope = Some(scope);
+        while let Some(scope_key) = current_scope {
+            let nested_key = format!("{}.{}", scope_key, name);
+            if let Some(item) = self.items.get(&nested_key) {
+                return Ok(Some(item));
+            }
+
+            current_scope = self.items.get(scope_key).and_then(|i| i.get_parent());
+        }
+
+        // Fully qualified name
+        if let Some(item) = self.items.get(name) {
+            return Ok(Some(item));
+        }
+
+        // Single-type imports, matching the first part of the name
+        let (first_name, rest) = match name.find('.') {
+            Some(pos) => name.split_at(pos),
+            None => (name, ""),
+        };
+        let imported_items = imports
+            .iter()
+            .filter(|import| !import.ends_with(".*"))
+            .filter(|import| import.rsplit('.').next() == Some(first_name))
+            .filter_map(|import| self.items.get(&format!("{}{}", import, rest)));
+        if let Some(item) = unique_item(name, imported_items)? {
+            return Ok(Some(item));
+        }
+
+        // Items of the same package
+        if let Some(item) = self.items.get(&format!("{}.{}", pkg_path, name)) {
+            return Ok(Some(item));
         }
 
-        // Try with items (interfaces/structs/enums)
-        // TODO: support for wildchar imports (e.g: a.b.c.*)
-        for (_, item) in &self.items {
-            if item.get_pkg().path == unresolved_type.owner.path
-                && item.get_name() == unresolved_type.name
-            {
-                return Some(Rc::new(Type::Item(item.clone())));
+        // Wildcard imports
+        let wildcard_items = imports
+            .iter()
+            .filter_map(|import| import.strip_suffix(".*"))
+            .filter_map(|prefix| self.items.get(&format!("{}.{}", prefix, name)));
+
+        unique_item(name, wildcard_items)
+    }
+
+    // Evaluate a const expression of the given type (e.g.: int or long for
+    // AIDL integral values), references being looked up from the item with
+    // the given key
+    pub fn evaluate(
+        &self,
+        expression: &Expression,
+        the_type: &Type,
+        scope: &Key,
+    ) -> Result<Value, EvaluationError> {
+        let integral_type = self.get_integral_type(the_type.get_element_type());
+        self.evaluate_with_depth(expression, integral_type, scope, 0)
+    }
+
+    fn evaluate_with_depth(
+        &self,
+        expression: &Expression,
+        integral_type: Option<IntegralType>,
+        scope: &Key,
+        depth: usize,
+    ) -> Result<Value, EvaluationError> {
+        let evaluate = |e: &Expression| self.evaluate_with_depth(e, integral_type, scope, depth);
+
+        match expression {
+            Expression::Literal(literal) => Value::from_literal(literal, integral_type),
+            Expression::Reference(name) => {
+                if depth >= MAX_EVALUATION_DEPTH {
+                    return Err(EvaluationError::RecursionLimit(name.clone()));
+                }
+
+                self.evaluate_reference(name, scope, depth + 1)
+                    .ok_or_else(|| EvaluationError::UnresolvedReference(name.clone()))?
+            }
+            Expression::Unary(op, expr) => {
+                Value::apply_unary(*op, evaluate(expr)?).map(|value| value.wrapped(integral_type))
             }
+            Expression::Binary(lhs, op, rhs) => {
+                Value::apply_binary(evaluate(lhs)?, *op, evaluate(rhs)?)
+                    .map(|value| value.wrapped(integral_type))
+            }
+            Expression::Array(elements) => elements
+                .iter()
+                .map(evaluate)
+                .collect::<Result<Vec<Value>, EvaluationError>>()
+                .map(Value::Array),
+        }
+    }
 
-            for import in imports {
-                let item_path = format!("{}.{}", item.get_pkg().path, item.get_name());
-                if unresolved_type.name == item_path
-                    || format!("{}.{}", import, unresolved_type.name) == item_path
-                {
-                    return Some(Rc::new(Type::Item(item.clone())));
+    // Integral type of a const, of a member or of an enum, either resolved or
+    // not (None for the other types, e.g.: String)
+    fn get_integral_type(&self, the_type: &Type) -> Option<IntegralType> {
+        match *the_type {
+            Type::Standard(ref st) => st.get_integral_type(),
+            Type::Unresolved(ref unresolved_type) => self
+                .find_standard_type(unresolved_type)
+                .and_then(|st| st.get_integral_type()),
+            _ => None,
+        }
+    }
+
+    // Examples:
+    // - CONST_NAME (in the scope or in one of its outer items)
+    // - ItemName.CONST_NAME
+    // - EnumName.ELEMENT_NAME
+    fn evaluate_reference(
+        &self,
+        name: &str,
+        scope: &Key,
+        depth: usize,
+    ) -> Option<Result<Value, EvaluationError>> {
+        match name.rfind('.') {
+            None => {
+                let mut current_scope = Some(scope);
+                while let Some(scope_key) = current_scope {
+                    let item = self.items.get(scope_key)?;
+                    if let Some(value) = self.evaluate_named_value(item, name, depth) {
+                        return Some(value);
+                    }
+
+                    current_scope = item.get_parent();
+                }
+
+                None
+            }
+            Some(pos) => {
+                let scope_item = self.items.get(scope)?;
+                let item = self
+                    .find_item(
+                        &name[..pos],
+                        &scope_item.get_pkg().path,
+                        scope_item.get_imports(),
+                        scope,
+                    )
+                    .ok()??;
+
+                self.evaluate_named_value(item, &name[pos + 1..], depth)
+            }
+        }
+    }
+
+    // Value of the const (or enum element) declared with the given name in
+    // the item, if any
+    fn evaluate_named_value(
+        &self,
+        item: &Item,
+        name: &str,
+        depth: usize,
+    ) -> Option<Result<Value, EvaluationError>> {
+        match item {
+            Item::Enum(e) => e
+                .elements
+                .iter()
+                .find(|element| element.name == name)
+                .map(|element| self.evaluate_enum_element(e, element.index, depth)),
+            _ => item.get_consts().iter().find(|c| c.name == name).map(|c| {
+                let integral_type = self.get_integral_type(&c.const_type);
+                self.evaluate_with_depth(&c.value, integral_type, item.get_key(), depth)
+            }),
+        }
+    }
+
+    // Elements without value follow the last element with a value (or 0)
+    fn evaluate_enum_element(
+        &self,
+        enumeration: &Enum,
+        index: usize,
+        depth: usize,
+    ) -> Result<Value, EvaluationError> {
+        let previous_elements = &enumeration.elements[..=index];
+        let integral_type = self.get_integral_type(&enumeration.backing_type);
+
+        match previous_elements.iter().rposition(|e| e.value.is_some()) {
+            Some(pos) => {
+                let expression = previous_elements[pos].value.as_ref().unwrap();
+                let offset = (index - pos) as i64;
+                let key = enumeration.get_key();
+                match self.evaluate_with_depth(expression, integral_type, key, depth)? {
+                    Value::Int(i) => Ok(Value::Int(i.wrapping_add(offset))),
+                    value if offset == 0 => Ok(value),
+                    value => Err(EvaluationError::InvalidOperand("+".into(), value)),
                 }
             }
+            None => Ok(Value::Int(index as i64)),
         }
+    }
+
+    // None if the expression cannot be evaluated (see check_values)
+    fn computed_value(
+        &self,
+        expression: &Expression,
+        the_type: &Type,
+        scope: &Key,
+    ) -> Option<Value> {
+        self.evaluate(expression, the_type, scope).ok()
+    }
 
-        // Not found
-        return None;
+    pub fn find_dependencies(
+        &self,
+        from: &Item,
+    ) -> Vec<(DependencyKind, HashSet<usize>, Rc<Item>)> {
+        self.find_dependencies_with_data_flow(from, DataFlow::All)
     }
 
-    pub fn find_dependencies(&self, from: &Item) -> Vec<(HashSet<usize>, Rc<Item>)> {
+    // Same as find_dependencies, only following the method args (and return
+    // values) matching the given data flow
+    pub fn find_dependencies_with_data_flow(
+        &self,
+        from: &Item,
+        data_flow: DataFlow,
+    ) -> Vec<(DependencyKind, HashSet<usize>, Rc<Item>)> {
         let items = match from {
-            Item::Interface(i) => self.find_dependencies_of_interface(i),
+            Item::Interface(i) => self.find_dependencies_of_interface(i, data_flow),
             Item::Struct(s) => self.find_dependencies_of_struct(s),
             Item::Enum(e) => self.find_dependencies_of_enum(e),
+            Item::Union(u) => self.find_dependencies_of_union(u),
+            Item::OpaqueStruct(_) => Vec::new(),
         };
 
         // Map items with reference members
@@ -204,32 +641,137 @@ impl Model {
                     acc
                 });
 
+        // The super-interfaces come first, with the indices of the methods
+        // inherited through them (and of the members using them)
+        let mut dependencies: Vec<(DependencyKind, HashSet<usize>, Rc<Item>)> = match from {
+            Item::Interface(i) => self
+                .find_super_interfaces_of_interface(i)
+                .into_iter()
+                .map(|(indices, item)| {
+                    let mut indices = indices;
+                    indices.extend(map.remove(item.get_key()).unwrap_or_default());
+                    (DependencyKind::Inheritance, indices, item)
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+
         // Re-build items without duplicate and with reference members
-        items
-            .into_iter()
-            .filter_map(|(_, item)| match map.entry(item.get_key().clone()) {
+        dependencies.extend(items.into_iter().filter_map(|(_, item)| {
+            match map.entry(item.get_key().clone()) {
                 hash_map::Entry::Occupied(oe) => {
                     let (_, indices) = oe.remove_entry();
-                    Some((indices, item))
+                    Some((DependencyKind::Reference, indices, item))
                 }
                 hash_map::Entry::Vacant(_) => None,
+            }
+        }));
+
+        dependencies
+    }
+
+    // The direct super-interfaces, with the indices of the methods inherited
+    // through them (see find_inherited_methods)
+    pub fn find_super_interfaces_of_interface(
+        &self,
+        from: &Interface,
+    ) -> Vec<(HashSet<usize>, Rc<Item>)> {
+        let inherited_methods = self.find_inherited_methods(from);
+
+        self.get_super_interfaces(from)
+            .into_iter()
+            .map(|item| {
+                let indices = inherited_methods
+                    .iter()
+                    .filter(|m| m.via == *item.get_key())
+                    .map(|m| m.index)
+                    .collect();
+                (indices, item)
+            })
+            .collect()
+    }
+
+    // Methods of the super-interfaces and of their own super-interfaces, each
+    // one once (e.g.: for a diamond), numbered after the consts and methods of
+    // the interface
+    pub fn find_inherited_methods(&self, interface: &Interface) -> Vec<InheritedMethod> {
+        let mut index = interface
+            .consts
+            .iter()
+            .map(|c| c.index)
+            .chain(interface.methods.iter().map(|m| m.index))
+            .max()
+            .map_or(0, |max| max + 1);
+
+        let mut visited = HashSet::new();
+        visited.insert(interface.get_key().clone());
+
+        let mut inherited_methods = Vec::new();
+        for super_interface in self.get_super_interfaces(interface) {
+            let via = super_interface.get_key().clone();
+
+            // Depth first, the nearest interfaces first
+            let mut stack = vec![super_interface];
+            while let Some(item) = stack.pop() {
+                if !visited.insert(item.get_key().clone()) {
+                    continue;
+                }
+
+                if let Item::Interface(ref i) = *item {
+                    for position in 0..i.methods.len() {
+                        inherited_methods.push(InheritedMethod {
+                            index,
+                            via: via.clone(),
+                            from: item.clone(),
+                            position,
+                        });
+                        index += 1;
+                    }
+
+                    stack.extend(self.get_super_interfaces(i).into_iter().rev());
+                }
+            }
+        }
+
+        inherited_methods
+    }
+
+    // The resolved super-interfaces are looked up again, as the resolved
+    // types refer to the items as they were before their own resolution
+    fn get_super_interfaces(&self, interface: &Interface) -> Vec<Rc<Item>> {
+        interface
+            .super_interfaces
+            .iter()
+            .filter_map(|t| match **t {
+                Type::Item(ref i) => self.items.get(i.get_key()).cloned(),
+                _ => None,
             })
             .collect()
     }
 
-    pub fn find_dependencies_of_interface(&self, from: &Interface) -> Vec<(usize, Rc<Item>)> {
+    pub fn find_dependencies_of_interface(
+        &self,
+        from: &Interface,
+        data_flow: DataFlow,
+    ) -> Vec<(usize, Rc<Item>)> {
         from.methods
             .iter()
             .flat_map(|m| {
                 let mut items: Vec<(usize, Rc<Item>)> = m
                     .args
                     .iter()
+                    .filter(|a| data_flow.matches(a.direction))
                     .flat_map(|a| self.find_dependencies_of_arg(a))
                     .map(|item| (m.index, item))
                     .collect();
 
-                if let Type::Item(ref i) = *m.return_arg.arg_type {
-                    items.push((m.index, i.clone()));
+                // The return value flows out
+                if data_flow != DataFlow::In {
+                    items.extend(
+                        self.find_dependencies_of_arg(&m.return_arg)
+                            .into_iter()
+                            .map(|item| (m.index, item)),
+                    );
                 }
 
                 items
@@ -249,6 +791,18 @@ impl Model {
             .collect()
     }
 
+    pub fn find_dependencies_of_union(&self, from: &Union) -> Vec<(usize, Rc<Item>)> {
+        from.members
+            .iter()
+            .flat_map(|member| {
+                self.find_dependencies_of_arg(&member.arg)
+                    .into_iter()
+                    .map(|item| (member.index, item))
+                    .collect::<Vec<(usize, Rc<Item>)>>()
+            })
+            .collect()
+    }
+
     fn find_dependencies_of_arg(&self, from: &Arg) -> Vec<Rc<Item>> {
         let mut items: Vec<Rc<Item>> = from
             .generic_args
@@ -256,7 +810,7 @@ impl Model {
             .flat_map(|ga| self.find_dependencies_of_arg(&ga))
             .collect();
 
-        if let Type::Item(ref i) = *from.arg_type {
+        if let Type::Item(ref i) = *from.arg_type.get_element_type() {
             items.push(i.clone());
         }
 
@@ -267,26 +821,26 @@ impl Model {
         Vec::new()
     }
 
-    pub fn find_references(&self, to: &Item) -> Vec<(HashSet<usize>, Rc<Item>)> {
+    pub fn find_references(&self, to: &Item) -> Vec<(DependencyKind, HashSet<usize>, Rc<Item>)> {
+        self.find_references_with_data_flow(to, DataFlow::All)
+    }
+
+    // Same as find_references, only following the method args (and return
+    // values) matching the given data flow (e.g.: the methods returning an
+    // item, or writing it to an out arg)
+    pub fn find_references_with_data_flow(
+        &self,
+        to: &Item,
+        data_flow: DataFlow,
+    ) -> Vec<(DependencyKind, HashSet<usize>, Rc<Item>)> {
         self.items
             .iter()
             .filter_map(|(_, candidate_item)| {
-                let matching_dependencies = self
-                    .find_dependencies(candidate_item)
+                // Dependencies are unique by item
+                self.find_dependencies_with_data_flow(candidate_item, data_flow)
                     .into_iter()
-                    .filter(|(_, i)| i.get_key() == to.get_key());
-                let indices =
-                    matching_dependencies.fold(HashSet::new(), |mut acc, (indices, _)| {
-                        for i in indices {
-                            acc.insert(i);
-                        }
-                        acc
-                    });
-                if indices.is_empty() {
-                    None
-                } else {
-                    Some((indices, candidate_item.clone()))
-                }
+                    .find(|(_, _, i)| i.get_key() == to.get_key())
+                    .map(|(kind, indices, _)| (kind, indices, candidate_item.clone()))
             })
             .collect()
     }
@@ -295,6 +849,10 @@ impl Model {
 #[derive(Debug)]
 pub enum Error {
     NotFound(Key),
+    AmbiguousReference {
+        name: String,
+        candidates: Vec<Key>,
+    },
     InvalidType {
         key: Key,
         expected: &'static str,
@@ -304,20 +862,75 @@ pub enum Error {
 
 impl Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Model error: {:#?}", *self)
+        match self {
+            Error::AmbiguousReference { name, candidates } => write!(
+                f,
+                "ambiguous reference to {} (candidates: {})",
+                name,
+                candidates.join(", ")
+            ),
+            _ => write!(f, "Model error: {:#?}", *self),
+        }
     }
 }
 
 impl std::error::Error for Error {}
 
+// The only item referenced by a name, or an error if several items match
+fn unique_item<'a, I: Iterator<Item = &'a Rc<Item>>>(
+    name: &str,
+    candidates: I,
+) -> Result<Option<&'a Rc<Item>>, Error> {
+    let mut candidates: Vec<&Rc<Item>> = candidates.collect();
+    candidates.sort_by(|a, b| a.get_key().cmp(b.get_key()));
+    candidates.dedup_by(|a, b| Rc::ptr_eq(a, b));
+
+    match candidates.len() {
+        0 => Ok(None),
+        1 => Ok(Some(candidates[0])),
+        _ => Err(Error::AmbiguousReference {
+            name: name.to_string(),
+            candidates: candidates.iter().map(|c| c.get_key().clone()).collect(),
+        }),
+    }
+}
+
+// Interface definition language of the parsed files
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Idl {
+    Aidl,
+    Protobuf,
+    OpenApi,
+    Hidl,
+}
+
+impl Display for Idl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Idl::Aidl => "AIDL",
+            Idl::Protobuf => "Protobuf",
+            Idl::OpenApi => "OpenAPI",
+            Idl::Hidl => "HIDL",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+// The items of a package come from files of the same IDL (a package with the
+// same path in another IDL is another package)
 #[derive(Debug)]
 pub struct Package {
     pub path: String,
+    pub idl: Idl,
 }
 
 impl Package {
-    pub fn new<S: Into<String>>(path: S) -> Self {
-        Package { path: path.into() }
+    pub fn new<S: Into<String>>(path: S, idl: Idl) -> Self {
+        Package {
+            path: path.into(),
+            idl,
+        }
     }
 
     pub fn get_key<'a>(&'a self) -> &'a String {
@@ -325,11 +938,39 @@ impl Package {
     }
 }
 
+// Where an element was declared (e.g.: IFoo.aidl:42)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Location {
+    pub path: Option<String>,
+    pub range: SourceRange,
+}
+
+impl Location {
+    pub fn new(path: Option<&str>, range: SourceRange) -> Self {
+        Location {
+            path: path.map(|p| p.to_string()),
+            range,
+        }
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.path {
+            Some(ref path) => write!(f, "{}:{}", path, self.range.start.line),
+            None => write!(f, "line {}", self.range.start.line),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum Item {
     Interface(Interface),
     Struct(Struct),
     Enum(Enum),
+    Union(Union),
+    // Struct without members, implemented in the target languages
+    OpaqueStruct(OpaqueStruct),
 }
 
 impl Item {
@@ -338,6 +979,65 @@ impl Item {
             Item::Interface(i) => &i.name,
             Item::Struct(s) => &s.name,
             Item::Enum(e) => &e.name,
+            Item::Union(u) => &u.name,
+            Item::OpaqueStruct(o) => &o.name,
+        }
+    }
+
+    // Name relative to the package (e.g.: Outer.Inner for nested items)
+    pub fn get_qualified_name(&self) -> &str {
+        let pkg_path = &self.get_pkg().path;
+        let key = self.get_key();
+
+        if key.len() > pkg_path.len() && key.starts_with(pkg_path.as_str()) {
+            &key[pkg_path.len() + 1..]
+        } else {
+            key
+        }
+    }
+
+    pub fn get_parent(&self) -> Option<&Key> {
+        match self {
+            Item::Interface(i) => i.parent.as_ref(),
+            Item::Struct(s) => s.parent.as_ref(),
+            Item::Enum(e) => e.parent.as_ref(),
+            Item::Union(u) => u.parent.as_ref(),
+            Item::OpaqueStruct(o) => o.parent.as_ref(),
+        }
+    }
+
+    pub fn get_imports(&self) -> &[String] {
+        match self {
+            Item::Interface(i) => &i.imports,
+            Item::Struct(s) => &s.imports,
+            Item::Enum(e) => &e.imports,
+            Item::Union(u) => &u.imports,
+            Item::OpaqueStruct(_) => &[],
+        }
+    }
+
+    pub fn get_annotations(&self) -> &[Annotation] {
+        match self {
+            Item::Interface(i) => &i.annotations,
+            Item::Struct(s) => &s.annotations,
+            Item::Enum(e) => &e.annotations,
+            Item::Union(u) => &u.annotations,
+            Item::OpaqueStruct(o) => &o.annotations,
+        }
+    }
+
+    pub fn get_consts(&self) -> &[Const] {
+        match self {
+            Item::Interface(i) => &i.consts,
+            _ => &[],
+        }
+    }
+
+    pub fn get_members(&self) -> &[Member] {
+        match self {
+            Item::Struct(s) => &s.members,
+            Item::Union(u) => &u.members,
+            _ => &[],
         }
     }
 
@@ -346,22 +1046,97 @@ impl Item {
             Item::Interface(i) => &i.pkg,
             Item::Struct(s) => &s.pkg,
             Item::Enum(e) => &e.pkg,
+            Item::Union(u) => &u.pkg,
+            Item::OpaqueStruct(o) => &o.pkg,
         }
     }
 
+    // IDL of the file the item was declared in
+    pub fn get_idl(&self) -> Idl {
+        self.get_pkg().idl
+    }
+
     pub fn get_key(&self) -> &Key {
         match self {
             Item::Interface(i) => i.get_key(),
             Item::Struct(s) => s.get_key(),
             Item::Enum(e) => &e.get_key(),
+            Item::Union(u) => u.get_key(),
+            Item::OpaqueStruct(o) => o.get_key(),
         }
     }
 
-    pub fn get_docu(&self) -> &str {
+    pub fn get_docu(&self) -> &Javadoc {
         match self {
             Item::Interface(i) => &i.docu,
             Item::Struct(s) => &s.docu,
             Item::Enum(e) => &e.docu,
+            Item::Union(u) => &u.docu,
+            Item::OpaqueStruct(o) => &o.docu,
+        }
+    }
+
+    pub fn get_location(&self) -> &Location {
+        match self {
+            Item::Interface(i) => &i.location,
+            Item::Struct(s) => &s.location,
+            Item::Enum(e) => &e.location,
+            Item::Union(u) => &u.location,
+            Item::OpaqueStruct(o) => &o.location,
+        }
+    }
+
+    pub fn get_status(&self) -> Status {
+        Status::new(self.get_docu(), self.get_annotations())
+    }
+
+    // Status of a member from its index (e.g.: a const or a method of an
+    // interface), None for an unknown index
+    pub fn get_member_status(&self, index: usize) -> Option<Status> {
+        match self {
+            Item::Interface(i) => i
+                .consts
+                .iter()
+                .find(|c| c.index == index)
+                .map(Const::get_status)
+                .or_else(|| {
+                    i.methods
+                        .iter()
+                        .find(|m| m.index == index)
+                        .map(Method::get_status)
+                }),
+            Item::Struct(_) | Item::Union(_) => self
+                .get_members()
+                .iter()
+                .find(|m| m.index == index)
+                .map(Member::get_status),
+            Item::Enum(e) => e
+                .elements
+                .iter()
+                .find(|e| e.index == index)
+                .map(EnumElement::get_status),
+            Item::OpaqueStruct(_) => None,
+        }
+    }
+}
+
+// Deprecation and visibility of an item or of a member, either from its
+// javadoc (@deprecated or @hide) or from its annotations (e.g.: @Deprecated)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Status {
+    pub is_deprecated: bool,
+    pub is_hidden: bool,
+}
+
+impl Status {
+    pub fn new(docu: &Javadoc, annotations: &[Annotation]) -> Self {
+        let has_annotation =
+            |names: &[&str]| annotations.iter().any(|a| names.contains(&a.name.as_str()));
+
+        Status {
+            is_deprecated: docu.deprecated.is_some()
+                || has_annotation(&["Deprecated", "deprecated"]),
+            is_hidden: docu.is_hidden || has_annotation(&["Hide", "hide"]),
         }
     }
 }
@@ -369,105 +1144,287 @@ impl Item {
 #[derive(Debug)]
 pub struct Interface {
     pub pkg: Rc<Package>,
+    pub parent: Option<Key>,
     pub imports: Vec<String>,
     pub name: String,
-    pub docu: String,
+    // Interfaces extended by this one (e.g.: interface IFoo extends IBase in
+    // HIDL), empty if none
+    pub super_interfaces: Vec<Rc<Type>>,
+    pub docu: Javadoc,
     pub consts: Vec<Const>,
     pub methods: Vec<Method>,
+    pub annotations: Vec<Annotation>,
+    pub location: Location,
     lazy_key: OnceCell<String>,
 }
 
 impl Interface {
-    pub fn new<S1: Into<String>, S2: Into<String>, V: Into<Vec<String>>>(
+    #[allow(clippy::too_many_arguments)]
+    pub fn new<S: Into<String>, V: Into<Vec<String>>>(
         pkg: Rc<Package>,
+        parent: Option<Key>,
         imports: V,
-        name: S1,
-        docu: S2,
+        name: S,
+        super_interfaces: Vec<Rc<Type>>,
+        docu: Javadoc,
         consts: Vec<Const>,
         methods: Vec<Method>,
+        annotations: Vec<Annotation>,
+        location: Location,
     ) -> Self {
         Interface {
             pkg,
+            parent,
             imports: imports.into(),
             name: name.into(),
-            docu: docu.into(),
+            super_interfaces,
+            docu,
             consts,
             methods,
+            annotations,
+            location,
             lazy_key: OnceCell::new(),
         }
     }
 
     pub fn get_key(&self) -> &Key {
-        &self
-            .lazy_key
-            .get_or_init(|| format!("{}.{}", self.pkg.path, self.name))
+        self.lazy_key
+            .get_or_init(|| item_key(&self.pkg, self.parent.as_ref(), &self.name))
+    }
+
+    // Stable AIDL interfaces are annotated (e.g.: @VintfStability) or frozen
+    // in an API dump (e.g.: aidl_api/foo/1/a/b/IFoo.aidl)
+    pub fn is_stable(&self) -> bool {
+        self.pkg.idl == Idl::Aidl
+            && (self.annotations.iter().any(|a| a.name == "VintfStability")
+                || self.get_api_dump_dir().is_some())
+    }
+
+    // e.g.: 1 for aidl_api/foo/1/a/b/IFoo.aidl, None for the current
+    // (unfrozen) version or for an interface outside of an API dump
+    pub fn get_frozen_version(&self) -> Option<u32> {
+        self.get_api_dump_dir()
+            .and_then(|dir| dir.split('/').nth(2))
+            .and_then(|version| version.parse().ok())
+    }
+
+    // Transactions added by the generated code of stable interfaces
+    pub fn get_meta_transactions(&self) -> Vec<MetaTransaction> {
+        if self.is_stable() {
+            vec![
+                MetaTransaction::GetInterfaceVersion,
+                MetaTransaction::GetInterfaceHash,
+            ]
+        } else {
+            Vec::new()
+        }
+    }
+
+    // e.g.: aidl_api/foo/1/a/b/IFoo.aidl
+    fn get_api_dump_dir(&self) -> Option<&str> {
+        let path = self.location.path.as_deref()?;
+        path.find("aidl_api/").map(|start| &path[start..])
     }
 }
 
 #[derive(Debug)]
 pub struct Struct {
     pub pkg: Rc<Package>,
+    pub parent: Option<Key>,
     pub imports: Vec<String>,
     pub name: String,
-    pub docu: String,
+    pub type_params: Vec<String>,
+    pub docu: Javadoc,
     pub members: Vec<Member>,
+    pub annotations: Vec<Annotation>,
+    pub location: Location,
     lazy_key: OnceCell<String>,
 }
 
 impl Struct {
-    pub fn new<S1: Into<String>, S2: Into<String>, V: Into<Vec<String>>>(
+    #[allow(clippy::too_many_arguments)]
+    pub fn new<S: Into<String>, V: Into<Vec<String>>>(
         pkg: Rc<Package>,
+        parent: Option<Key>,
         imports: V,
-        name: S1,
-        docu: S2,
+        name: S,
+        type_params: Vec<String>,
+        docu: Javadoc,
         members: Vec<Member>,
+        annotations: Vec<Annotation>,
+        location: Location,
     ) -> Self {
         Struct {
             pkg,
+            parent,
             imports: imports.into(),
             name: name.into(),
-            docu: docu.into(),
+            type_params,
+            docu,
             members,
+            annotations,
+            location,
             lazy_key: OnceCell::new(),
         }
     }
 
     pub fn get_key(&self) -> &Key {
-        &self
-            .lazy_key
-            .get_or_init(|| format!("{}.{}", self.pkg.path, self.name))
+        self.lazy_key
+            .get_or_init(|| item_key(&self.pkg, self.parent.as_ref(), &self.name))
     }
 }
 
 #[derive(Debug)]
 pub struct Enum {
     pub pkg: Rc<Package>,
+    pub parent: Option<Key>,
+    pub imports: Vec<String>,
     pub name: String,
-    pub docu: String,
+    pub docu: Javadoc,
+    pub backing_type: Rc<Type>,
     pub elements: Vec<EnumElement>,
+    pub annotations: Vec<Annotation>,
+    pub location: Location,
     lazy_key: OnceCell<String>,
 }
 
 impl Enum {
-    pub fn new<S: Into<String>>(
+    #[allow(clippy::too_many_arguments)]
+    pub fn new<S: Into<String>, V: Into<Vec<String>>>(
         pkg: Rc<Package>,
+        parent: Option<Key>,
+        imports: V,
         name: S,
-        docu: String,
+        docu: Javadoc,
+        backing_type: Rc<Type>,
         elements: Vec<EnumElement>,
+        annotations: Vec<Annotation>,
+        location: Location,
     ) -> Self {
         Enum {
             pkg,
+            parent,
+            imports: imports.into(),
             name: name.into(),
             docu,
+            backing_type,
             elements,
+            annotations,
+            location,
             lazy_key: OnceCell::new(),
         }
     }
 
     pub fn get_key(&self) -> &Key {
-        &self
-            .lazy_key
-            .get_or_init(|| format!("{}.{}", self.pkg.path, self.name))
+        self.lazy_key
+            .get_or_init(|| item_key(&self.pkg, self.parent.as_ref(), &self.name))
+    }
+}
+
+#[derive(Debug)]
+pub struct Union {
+    pub pkg: Rc<Package>,
+    pub parent: Option<Key>,
+    pub imports: Vec<String>,
+    pub name: String,
+    pub docu: Javadoc,
+    pub members: Vec<Member>,
+    pub annotations: Vec<Annotation>,
+    pub location: Location,
+    lazy_key: OnceCell<String>,
+}
+
+impl Union {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new<S: Into<String>, V: Into<Vec<String>>>(
+        pkg: Rc<Package>,
+        parent: Option<Key>,
+        imports: V,
+        name: S,
+        docu: Javadoc,
+        members: Vec<Member>,
+        annotations: Vec<Annotation>,
+        location: Location,
+    ) -> Self {
+        Union {
+            pkg,
+            parent,
+            imports: imports.into(),
+            name: name.into(),
+            docu,
+            members,
+            annotations,
+            location,
+            lazy_key: OnceCell::new(),
+        }
+    }
+
+    pub fn get_key(&self) -> &Key {
+        self.lazy_key
+            .get_or_init(|| item_key(&self.pkg, self.parent.as_ref(), &self.name))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct OpaqueStruct {
+    pub pkg: Rc<Package>,
+    pub parent: Option<Key>,
+    pub name: String,
+    pub docu: Javadoc,
+    pub native_types: Vec<NativeType>,
+    pub annotations: Vec<Annotation>,
+    pub location: Location,
+    lazy_key: OnceCell<String>,
+}
+
+impl OpaqueStruct {
+    pub fn new<S: Into<String>>(
+        pkg: Rc<Package>,
+        parent: Option<Key>,
+        name: S,
+        docu: Javadoc,
+        native_types: Vec<NativeType>,
+        annotations: Vec<Annotation>,
+        location: Location,
+    ) -> Self {
+        OpaqueStruct {
+            pkg,
+            parent,
+            name: name.into(),
+            docu,
+            native_types,
+            annotations,
+            location,
+            lazy_key: OnceCell::new(),
+        }
+    }
+
+    pub fn get_key(&self) -> &Key {
+        self.lazy_key
+            .get_or_init(|| item_key(&self.pkg, self.parent.as_ref(), &self.name))
+    }
+}
+
+// Type implementing an opaque struct in a target language (e.g.:
+// cpp_header "a/b/Foo.h")
+#[derive(Clone, Debug)]
+pub struct NativeType {
+    pub kind: String,
+    pub name: String,
+}
+
+impl NativeType {
+    pub fn new<S1: Into<String>, S2: Into<String>>(kind: S1, name: S2) -> Self {
+        NativeType {
+            kind: kind.into(),
+            name: name.into(),
+        }
+    }
+}
+
+impl Display for NativeType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} \"{}\"", self.kind, self.name)
     }
 }
 
@@ -475,82 +1432,374 @@ impl Enum {
 pub struct Const {
     pub name: String,
     pub const_type: Rc<Type>,
-    pub value: String,
+    pub value: Expression,
+    pub computed_value: Option<Value>,
     pub index: usize,
-    pub docu: String,
+    pub docu: Javadoc,
+    pub annotations: Vec<Annotation>,
+    pub location: Location,
 }
 
 impl Const {
+    #[allow(clippy::too_many_arguments)]
     pub fn new<S: Into<String>>(
         name: S,
         const_type: Rc<Type>,
-        value: String,
+        value: Expression,
+        computed_value: Option<Value>,
         index: usize,
-        docu: String,
+        docu: Javadoc,
+        annotations: Vec<Annotation>,
+        location: Location,
     ) -> Self {
         Const {
             name: name.into(),
             const_type,
             value,
+            computed_value,
             index,
             docu,
+            annotations,
+            location,
         }
     }
+
+    pub fn get_status(&self) -> Status {
+        Status::new(&self.docu, &self.annotations)
+    }
 }
 
 #[derive(Debug)]
 pub struct Member {
     pub arg: Arg,
+    pub default_value: Option<Expression>,
+    pub computed_default_value: Option<Value>,
     pub index: usize,
-    pub docu: String,
+    pub docu: Javadoc,
+    pub annotations: Vec<Annotation>,
+    pub location: Location,
 }
 
 impl Member {
-    pub fn new(arg: Arg, index: usize, docu: String) -> Self {
-        Member { arg, index, docu }
+    pub fn new(
+        arg: Arg,
+        default_value: Option<Expression>,
+        computed_default_value: Option<Value>,
+        index: usize,
+        docu: Javadoc,
+        annotations: Vec<Annotation>,
+        location: Location,
+    ) -> Self {
+        Member {
+            arg,
+            default_value,
+            computed_default_value,
+            index,
+            docu,
+            annotations,
+            location,
+        }
+    }
+
+    pub fn get_status(&self) -> Status {
+        Status::new(&self.docu, &self.annotations)
     }
 }
 
 #[derive(Debug)]
 pub struct Method {
+    pub is_one_way: bool,
     pub name: String,
     pub return_arg: Arg,
     pub args: Vec<Arg>,
+    pub transaction_code: TransactionCode,
     pub index: usize,
-    pub docu: String,
+    pub docu: Javadoc,
+    pub annotations: Vec<Annotation>,
+    pub location: Location,
 }
 
 impl Method {
+    #[allow(clippy::too_many_arguments)]
     pub fn new<S: Into<String>>(
+        is_one_way: bool,
         name: S,
         return_arg: Arg,
         args: Vec<Arg>,
+        transaction_code: TransactionCode,
         index: usize,
-        docu: String,
+        docu: Javadoc,
+        annotations: Vec<Annotation>,
+        location: Location,
     ) -> Self {
         Method {
+            is_one_way,
             name: name.into(),
             return_arg,
             args,
+            transaction_code,
             index,
             docu,
+            annotations,
+            location,
         }
     }
+
+    pub fn get_status(&self) -> Status {
+        Status::new(&self.docu, &self.annotations)
+    }
+}
+
+// A method of a super-interface, as a member of the inheriting interface
+#[derive(Debug)]
+pub struct InheritedMethod {
+    // Index among the members of the inheriting interface
+    pub index: usize,
+    // Key of the direct super-interface the method is inherited through
+    pub via: Key,
+    // Interface declaring the method
+    pub from: Rc<Item>,
+    position: usize,
+}
+
+impl InheritedMethod {
+    pub fn get_method(&self) -> &Method {
+        match *self.from {
+            Item::Interface(ref i) => &i.methods[self.position],
+            _ => unreachable!("methods are only inherited from interfaces"),
+        }
+    }
+}
+
+// Binder transaction codes (see android.os.IBinder)
+pub const FIRST_CALL_TRANSACTION: u32 = 0x0000_0001;
+pub const LAST_CALL_TRANSACTION: u32 = 0x00ff_ffff;
+
+// Meta transactions of stable interfaces (getInterfaceVersion/getInterfaceHash)
+pub const GET_INTERFACE_VERSION_TRANSACTION: u32 = LAST_CALL_TRANSACTION;
+pub const GET_INTERFACE_HASH_TRANSACTION: u32 = LAST_CALL_TRANSACTION - 1;
+
+// Highest transaction ID not colliding with the meta transactions
+pub const MAX_USER_TRANSACTION_ID: u32 =
+    GET_INTERFACE_HASH_TRANSACTION - FIRST_CALL_TRANSACTION - 1;
+
+// Methods implemented by the generated code of stable interfaces
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MetaTransaction {
+    GetInterfaceVersion,
+    GetInterfaceHash,
+}
+
+impl MetaTransaction {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            MetaTransaction::GetInterfaceVersion => "getInterfaceVersion",
+            MetaTransaction::GetInterfaceHash => "getInterfaceHash",
+        }
+    }
+
+    pub fn get_return_type_name(&self) -> &'static str {
+        match self {
+            MetaTransaction::GetInterfaceVersion => "int",
+            MetaTransaction::GetInterfaceHash => "String",
+        }
+    }
+
+    // Code of the binder transaction
+    pub fn get_value(&self) -> u32 {
+        match self {
+            MetaTransaction::GetInterfaceVersion => GET_INTERFACE_VERSION_TRANSACTION,
+            MetaTransaction::GetInterfaceHash => GET_INTERFACE_HASH_TRANSACTION,
+        }
+    }
+}
+
+// The transaction ID of a method, either written in the IDL file
+// (e.g.: void foo() = 3;) or assigned in declaration order
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TransactionCode {
+    Explicit(u32),
+    Implicit(u32),
+}
+
+impl TransactionCode {
+    pub fn get_id(&self) -> u32 {
+        match *self {
+            TransactionCode::Explicit(id) | TransactionCode::Implicit(id) => id,
+        }
+    }
+
+    // Code of the binder transaction (FIRST_CALL_TRANSACTION + ID), None when
+    // the ID is too large (see validate_transaction_codes)
+    pub fn get_value(&self) -> Option<u32> {
+        FIRST_CALL_TRANSACTION.checked_add(self.get_id())
+    }
+
+    pub fn is_explicit(&self) -> bool {
+        matches!(self, TransactionCode::Explicit(_))
+    }
 }
 
 #[derive(Debug)]
 pub struct Arg {
+    pub direction: Direction,
     pub name: String,
     pub arg_type: Rc<Type>,
     pub generic_args: Vec<Arg>,
+    pub annotations: Vec<Annotation>,
+    pub location: Location,
 }
 
 impl Arg {
-    pub fn new<S: Into<String>>(name: S, arg_type: Rc<Type>, generic_args: Vec<Arg>) -> Self {
+    pub fn new<S: Into<String>>(
+        direction: Direction,
+        name: S,
+        arg_type: Rc<Type>,
+        generic_args: Vec<Arg>,
+        annotations: Vec<Annotation>,
+        location: Location,
+    ) -> Self {
         Arg {
+            direction,
             name: name.into(),
             arg_type,
             generic_args,
+            annotations,
+            location,
+        }
+    }
+
+    // Type including the generic args (e.g.: Map<String, List<Foo>>[])
+    pub fn get_full_type_name(&self) -> String {
+        let mut type_name = self.arg_type.get_name().to_string();
+
+        if !self.generic_args.is_empty() {
+            let generic_names = self
+                .generic_args
+                .iter()
+                .map(|ga| ga.get_full_type_name())
+                .collect::<Vec<String>>()
+                .join(", ");
+            type_name = format!("{}<{}>", type_name, generic_names);
+        }
+
+        if let Type::Array(ref a) = *self.arg_type {
+            for dimension in &a.dimensions {
+                type_name.push_str(&dimension.to_string());
+            }
+        }
+
+        type_name
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Direction {
+    In,
+    Out,
+    InOut,
+    Unspecified,
+}
+
+impl Direction {
+    // Args without direction are "in" args
+    pub fn is_in(&self) -> bool {
+        !matches!(self, Direction::Out)
+    }
+
+    pub fn is_out(&self) -> bool {
+        matches!(self, Direction::Out | Direction::InOut)
+    }
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Direction::In => write!(f, "in"),
+            Direction::Out => write!(f, "out"),
+            Direction::InOut => write!(f, "inout"),
+            Direction::Unspecified => Ok(()),
+        }
+    }
+}
+
+// How an item depends on another one
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DependencyKind {
+    // Used by some members (e.g.: as the type of a method arg)
+    Reference,
+    // Extended (e.g.: the super-interface of an interface)
+    Inheritance,
+}
+
+// Which method args to follow when looking for dependencies
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum DataFlow {
+    #[default]
+    All,
+    In,
+    Out,
+}
+
+impl DataFlow {
+    pub fn matches(&self, direction: Direction) -> bool {
+        match self {
+            DataFlow::All => true,
+            DataFlow::In => direction.is_in(),
+            DataFlow::Out => direction.is_out(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Annotation {
+    pub name: String,
+    pub params: Vec<AnnotationParam>,
+}
+
+impl Annotation {
+    pub fn new<S: Into<String>>(name: S, params: Vec<AnnotationParam>) -> Self {
+        Annotation {
+            name: name.into(),
+            params,
+        }
+    }
+}
+
+// Examples:
+// - @nullable
+// - @Descriptor("a.b.IFoo")
+// - @JavaDerive(equals=true, toString=true)
+impl Display for Annotation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "@{}", self.name)?;
+
+        match self.params.as_slice() {
+            [] => Ok(()),
+            [param] if param.name == "value" => write!(f, "({})", param.value),
+            params => {
+                let params_string = params
+                    .iter()
+                    .map(|p| format!("{}={}", p.name, p.value))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                write!(f, "({})", params_string)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AnnotationParam {
+    pub name: String,
+    pub value: Expression,
+}
+
+impl AnnotationParam {
+    pub fn new<S: Into<String>>(name: S, value: Expression) -> Self {
+        AnnotationParam {
+            name: name.into(),
+            value,
         }
     }
 }
@@ -560,14 +1809,19 @@ pub enum Type {
     Standard(Rc<StandardType>),
     Unresolved(UnresolvedType),
     Item(Rc<Item>),
+    Parameter(String),
+    Array(ArrayType),
 }
 
 impl Type {
+    // The key and the name of an array type are the ones of its element type
     pub fn get_key(&self) -> &String {
         match *self {
             Type::Standard(ref g) => g.get_key(),
             Type::Unresolved(ref u) => u.get_key(),
             Type::Item(ref i) => i.get_key(),
+            Type::Parameter(ref p) => p,
+            Type::Array(ref a) => a.element_type.get_key(),
         }
     }
 
@@ -576,6 +1830,15 @@ impl Type {
             Type::Standard(ref g) => &g.name,
             Type::Unresolved(ref u) => &u.name,
             Type::Item(ref i) => i.get_name(),
+            Type::Parameter(ref p) => p,
+            Type::Array(ref a) => a.element_type.get_name(),
+        }
+    }
+
+    pub fn get_element_type(&self) -> &Type {
+        match *self {
+            Type::Array(ref a) => &a.element_type,
+            _ => self,
         }
     }
 }
@@ -586,6 +1849,52 @@ impl Type {
             Type::Standard(st) => st.is_void(),
             Type::Unresolved(_) => false,
             Type::Item(_) => false,
+            Type::Parameter(_) => false,
+            Type::Array(_) => false,
+        }
+    }
+}
+
+impl Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.get_name())?;
+
+        if let Type::Array(ref a) = *self {
+            for dimension in &a.dimensions {
+                write!(f, "{}", dimension)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct ArrayType {
+    pub element_type: Rc<Type>,
+    pub dimensions: Vec<ArrayDimension>,
+}
+
+impl ArrayType {
+    pub fn new(element_type: Rc<Type>, dimensions: Vec<ArrayDimension>) -> Self {
+        ArrayType {
+            element_type,
+            dimensions,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum ArrayDimension {
+    Dynamic,
+    Fixed(String),
+}
+
+impl Display for ArrayDimension {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArrayDimension::Dynamic => write!(f, "[]"),
+            ArrayDimension::Fixed(size) => write!(f, "[{}]", size),
         }
     }
 }
@@ -593,21 +1902,79 @@ impl Type {
 #[derive(Debug)]
 pub struct StandardType {
     pub name: String,
+    pub category: TypeCategory,
+    // Number of generic args (e.g.: 2 for Map<K, V>)
+    pub generic_arity: usize,
     pub package: Rc<Package>,
     lazy_key: OnceCell<String>,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TypeCategory {
+    Void,
+    Primitive,
+    // Strings and char sequences
+    String,
+    // Lists, maps and vectors
+    Container,
+    Binder,
+    FileDescriptor,
+    // Standard parcelables (e.g.: ParcelableHolder)
+    Parcelable,
+}
+
 impl StandardType {
     // TODO: cover more languages (e.g.: Unit, (), Nothing, ...)
     pub fn is_void(&self) -> bool {
         self.name == "void"
     }
+
+    pub fn is_primitive(&self) -> bool {
+        self.category == TypeCategory::Primitive
+    }
+
+    // Types of the integral values of each IDL (e.g.: int in AIDL, int32 in
+    // protobuf, uint32_t in HIDL)
+    pub fn get_integral_type(&self) -> Option<IntegralType> {
+        let (bits, is_signed) = match (self.package.idl, self.name.as_str()) {
+            (Idl::Aidl, "byte") => (8, true),
+            (Idl::Aidl, "int") => (32, true),
+            (Idl::Aidl, "long") => (64, true),
+            (Idl::Protobuf, "int32") | (Idl::Protobuf, "sint32") | (Idl::Protobuf, "sfixed32") => {
+                (32, true)
+            }
+            (Idl::Protobuf, "uint32") | (Idl::Protobuf, "fixed32") => (32, false),
+            (Idl::Protobuf, "int64") | (Idl::Protobuf, "sint64") | (Idl::Protobuf, "sfixed64") => {
+                (64, true)
+            }
+            (Idl::Protobuf, "uint64") | (Idl::Protobuf, "fixed64") => (64, false),
+            (Idl::OpenApi, "integer") => (64, true),
+            (Idl::Hidl, "int8_t") => (8, true),
+            (Idl::Hidl, "uint8_t") => (8, false),
+            (Idl::Hidl, "int16_t") => (16, true),
+            (Idl::Hidl, "uint16_t") => (16, false),
+            (Idl::Hidl, "int32_t") => (32, true),
+            (Idl::Hidl, "uint32_t") => (32, false),
+            (Idl::Hidl, "int64_t") => (64, true),
+            (Idl::Hidl, "uint64_t") => (64, false),
+            _ => return None,
+        };
+
+        Some(IntegralType::new(bits, is_signed))
+    }
 }
 
 impl StandardType {
-    pub fn new<S: Into<String>>(name: S, package: Rc<Package>) -> Self {
+    pub fn new<S: Into<String>>(
+        name: S,
+        category: TypeCategory,
+        generic_arity: usize,
+        package: Rc<Package>,
+    ) -> Self {
         StandardType {
             name: name.into(),
+            category,
+            generic_arity,
             package,
             lazy_key: OnceCell::new(),
         }
@@ -647,7 +2014,745 @@ impl UnresolvedType {
 #[derive(Clone, Debug)]
 pub struct EnumElement {
     pub name: String,
-    pub value: String,
+    // Elements without value follow the previous one (e.g.: B is 1 in
+    // enum E { A, B })
+    pub value: Option<Expression>,
+    pub computed_value: Option<Value>,
     pub index: usize,
-    pub docu: String,
+    pub docu: Javadoc,
+    pub location: Location,
+}
+
+impl EnumElement {
+    // Enum elements have no annotations
+    pub fn get_status(&self) -> Status {
+        Status::new(&self.docu, &[])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostic::Severity;
+    use crate::parser::aidl;
+
+    // Model of AIDL sources, with their diagnostics once resolved
+    fn create_model(sources: &[&str]) -> (Model, Vec<ItemDiagnostic>) {
+        let files = sources.iter().map(|s| aidl::parse(s).unwrap()).collect();
+        let mut model = aidl::create_model(files);
+        let diagnostics = model.resolve_types();
+
+        (model, diagnostics)
+    }
+
+    fn get_const_values(model: &Model, key: &str) -> Vec<(String, Option<Value>)> {
+        model.items[key]
+            .get_consts()
+            .iter()
+            .map(|c| (c.name.clone(), c.computed_value.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_const_values() {
+        let (model, _) = create_model(&[
+            r#"package a;
+            interface IFoo {
+                const int A = 0xFFFFFFFF;
+                const int B = 1 << 31;
+                const long C = 1L << 31;
+                const long D = 0xFFFFFFFF;
+                const int E = 1 + 2 * 3 - (4 - 1) % 2;
+                const int F = 1 << 2 + 1 | 1;
+                const boolean G = 1 + 1 == 2 && !(B > 0);
+                const int H = 0x7FFFFFFF + 1;
+                const String I = "v" + 1 + '.' + C;
+                const byte J = -0x80;
+                const int K = IBar.X * 2;
+                const float L = 1 / 2.0f;
+            }"#,
+            "package a; interface IBar { const int X = 1 << 30; }",
+        ]);
+
+        assert_eq!(
+            get_const_values(&model, "a.IFoo"),
+            vec![
+                ("A".to_string(), Some(Value::Int(-1))),
+                ("B".to_string(), Some(Value::Int(-2147483648))),
+                ("C".to_string(), Some(Value::Int(2147483648))),
+                ("D".to_string(), Some(Value::Int(4294967295))),
+                ("E".to_string(), Some(Value::Int(6))),
+                ("F".to_string(), Some(Value::Int(9))),
+                ("G".to_string(), Some(Value::Bool(true))),
+                ("H".to_string(), Some(Value::Int(-2147483648))),
+                (
+                    "I".to_string(),
+                    Some(Value::String("v1.2147483648".to_string()))
+                ),
+                ("J".to_string(), Some(Value::Int(-128))),
+                ("K".to_string(), Some(Value::Int(-2147483648))),
+                ("L".to_string(), Some(Value::Float(0.5))),
+            ]
+        );
+    }
+
+    // Keys of the items referenced by the members (None if unresolved)
+    fn get_member_type_keys(model: &Model, key: &str) -> Vec<Option<Key>> {
+        model.items[key]
+            .get_members()
+            .iter()
+            .map(|m| match *m.arg.arg_type {
+                Type::Item(ref item) => Some(item.get_key().clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_find_item() {
+        let (model, diagnostics) = create_model(&[
+            "package a; parcelable Foo {} parcelable Outer { parcelable Inner {} }",
+            "package c; parcelable Foo {}",
+            "package d; parcelable Qux {} parcelable Foo {}",
+            "package b; parcelable Foo {}",
+            // 1. nested items, before the single-type imports
+            "package b; import a.Foo;
+             parcelable Scoped { parcelable Foo {} parcelable Inner { Foo foo; } }",
+            // 2. fully qualified names, 3. single-type imports (also for
+            // nested items), before the items of the same package
+            "package b; import a.Foo; import a.Outer;
+             parcelable Imported { c.Foo qualified; Foo foo; Outer.Inner inner; }",
+            // 4. items of the same package, before the wildcard imports,
+            // and 5. wildcard imports
+            "package b; import d.*;
+             parcelable Wildcard { Foo foo; Qux qux; }",
+        ]);
+
+        let key = |k: &str| Some(k.to_string());
+        assert_eq!(
+            get_member_type_keys(&model, "b.Scoped.Inner"),
+            vec![key("b.Scoped.Foo")]
+        );
+        assert_eq!(
+            get_member_type_keys(&model, "b.Imported"),
+            vec![key("c.Foo"), key("a.Foo"), key("a.Outer.Inner")]
+        );
+        assert_eq!(
+            get_member_type_keys(&model, "b.Wildcard"),
+            vec![key("b.Foo"), key("d.Qux")]
+        );
+
+        // The import of a.Foo is shadowed by the nested b.Scoped.Foo
+        let codes = diagnostics
+            .iter()
+            .map(|d| (d.key.as_str(), d.diagnostic.code.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(codes, vec![("b.Scoped", check::UNUSED_IMPORT)]);
+    }
+
+    #[test]
+    fn test_ambiguous_reference() {
+        let (model, diagnostics) = create_model(&[
+            "package a; parcelable Foo {}",
+            "package c; parcelable Foo {}",
+            "package b; import a.*; import c.*; parcelable Bar { Foo foo; }",
+        ]);
+
+        assert_eq!(get_member_type_keys(&model, "b.Bar"), vec![None]);
+        let ambiguous = diagnostics
+            .iter()
+            .filter(|d| d.diagnostic.code == check::AMBIGUOUS_REFERENCE)
+            .map(|d| (d.member_index, d.diagnostic.message.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ambiguous,
+            vec![(
+                Some(0),
+                "ambiguous reference to Foo (candidates: a.Foo, c.Foo)"
+            )]
+        );
+
+        let imports = vec!["a.*".to_string(), "c.*".to_string()];
+        match model.find_item("Foo", "b", &imports, &"b.Bar".to_string()) {
+            Err(Error::AmbiguousReference { name, candidates }) => {
+                assert_eq!(name, "Foo");
+                assert_eq!(candidates, vec!["a.Foo", "c.Foo"]);
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    fn get_link_keys(javadoc: &Javadoc) -> Vec<(String, Option<Key>)> {
+        javadoc
+            .blocks
+            .iter()
+            .flat_map(|b| match b {
+                Block::Paragraph(text) => text.clone(),
+                _ => Vec::new(),
+            })
+            .filter_map(|i| match i {
+                Inline::Link(link) => Some((link.target.clone(), link.key.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_resolved_javadoc() {
+        let (model, _) = create_model(&[
+            "package b; interface IFoo { void bar(); }",
+            "package a; import b.IFoo;
+             /** See {@link #baz}, {@link IFoo#bar} and {@link Missing}. */
+             interface IBar {
+                 /** Like {@link #baz()} or {@link b.IFoo}. */
+                 void baz();
+             }",
+        ]);
+
+        let key = |k: &str| Some(k.to_string());
+        let item = &model.items["a.IBar"];
+        assert_eq!(
+            get_link_keys(item.get_docu()),
+            vec![
+                ("#baz".to_string(), key("a.IBar")),
+                ("IFoo#bar".to_string(), key("b.IFoo")),
+                ("Missing".to_string(), None),
+            ]
+        );
+
+        let Item::Interface(interface) = &**item else {
+            panic!("a.IBar is not an interface");
+        };
+        assert_eq!(
+            get_link_keys(&interface.methods[0].docu),
+            vec![
+                ("#baz()".to_string(), key("a.IBar")),
+                ("b.IFoo".to_string(), key("b.IFoo")),
+            ]
+        );
+
+        let (model, _) = create_model(&[
+            "package b; interface IFoo {}",
+            "package a; import b.IFoo; /** Used by {@link IFoo}. */ enum E { A }",
+        ]);
+        assert_eq!(
+            get_link_keys(model.items["a.E"].get_docu()),
+            vec![("IFoo".to_string(), key("b.IFoo"))]
+        );
+    }
+
+    #[test]
+    fn test_data_flow() {
+        let (model, _) = create_model(&[r#"package a;
+            interface IFoo {
+                void f(in Foo foo, out Bar bar, inout List<Baz> bazs, int i);
+                Qux g(in Foo foo);
+                oneway void h(in IBar bar);
+            }
+            interface IBar {}
+            parcelable Foo {}
+            parcelable Bar {}
+            parcelable Baz {}
+            parcelable Qux {}"#]);
+
+        let find_keys = |data_flow| {
+            let mut keys: Vec<(Key, Vec<usize>)> = model
+                .find_dependencies_with_data_flow(&model.items["a.IFoo"], data_flow)
+                .into_iter()
+                .map(|(_, indices, item)| {
+                    let mut indices: Vec<usize> = indices.into_iter().collect();
+                    indices.sort_unstable();
+                    (item.get_key().clone(), indices)
+                })
+                .collect();
+            keys.sort();
+            keys
+        };
+        let key = |k: &str, indices: &[usize]| (k.to_string(), indices.to_vec());
+
+        assert_eq!(
+            find_keys(DataFlow::All),
+            vec![
+                key("a.Bar", &[0]),
+                key("a.Baz", &[0]),
+                key("a.Foo", &[0, 1]),
+                key("a.IBar", &[2]),
+                key("a.Qux", &[1]),
+            ]
+        );
+        assert_eq!(
+            find_keys(DataFlow::In),
+            vec![
+                key("a.Baz", &[0]),
+                key("a.Foo", &[0, 1]),
+                key("a.IBar", &[2])
+            ]
+        );
+        assert_eq!(
+            find_keys(DataFlow::Out),
+            vec![key("a.Bar", &[0]), key("a.Baz", &[0]), key("a.Qux", &[1])]
+        );
+
+        // The interface only reads Foo, and only produces Qux
+        let find_reference_keys = |to: &str, data_flow| {
+            model
+                .find_references_with_data_flow(&model.items[to], data_flow)
+                .into_iter()
+                .map(|(_, _, item)| item.get_key().clone())
+                .collect::<Vec<Key>>()
+        };
+        assert!(find_reference_keys("a.Foo", DataFlow::Out).is_empty());
+        assert_eq!(find_reference_keys("a.Foo", DataFlow::In), vec!["a.IFoo"]);
+        assert_eq!(find_reference_keys("a.Qux", DataFlow::Out), vec!["a.IFoo"]);
+        assert!(find_reference_keys("a.Qux", DataFlow::In).is_empty());
+    }
+
+    fn get_enum(model: &Model, key: &str) -> (Rc<Type>, Vec<(String, Option<Value>)>) {
+        match *model.items[key] {
+            Item::Enum(ref e) => (
+                e.backing_type.clone(),
+                e.elements
+                    .iter()
+                    .map(|e| (e.name.clone(), e.computed_value.clone()))
+                    .collect(),
+            ),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_enum_values() {
+        let (model, diagnostics) = create_model(&[
+            "package a; enum E { A, B = 5, C, D = C << 1, E = a.F.X >> 38 }",
+            "package a; @Backing(type=\"long\") enum F { X = 1L << 40, Y }",
+        ]);
+        assert!(diagnostics.is_empty());
+
+        let int = |i| Some(Value::Int(i));
+        let (backing_type, elements) = get_enum(&model, "a.E");
+        assert_eq!(backing_type.to_string(), "byte");
+        assert_eq!(
+            elements,
+            vec![
+                ("A".to_string(), int(0)),
+                ("B".to_string(), int(5)),
+                ("C".to_string(), int(6)),
+                ("D".to_string(), int(12)),
+                ("E".to_string(), int(4)),
+            ]
+        );
+
+        let (backing_type, elements) = get_enum(&model, "a.F");
+        assert_eq!(backing_type.to_string(), "long");
+        assert_eq!(
+            elements,
+            vec![
+                ("X".to_string(), int(1 << 40)),
+                ("Y".to_string(), int((1 << 40) + 1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_enum_imports() {
+        let (model, diagnostics) = create_model(&[
+            "package b; interface IConsts { const int X = 3; }",
+            "package a; import b.IConsts; enum E { A = IConsts.X, B }",
+        ]);
+        assert!(diagnostics.is_empty());
+
+        let int = |i| Some(Value::Int(i));
+        assert_eq!(
+            get_enum(&model, "a.E").1,
+            vec![("A".to_string(), int(3)), ("B".to_string(), int(4))]
+        );
+    }
+
+    #[test]
+    fn test_invalid_backing_type() {
+        assert_eq!(
+            get_diagnostic_codes(&[
+                "package a; @Backing(type=\"String\") enum E { A }",
+                "package a; @Backing(type=\"int\") enum F { A }",
+            ]),
+            vec![code("a.E", None, validation::INVALID_BACKING_TYPE)]
+        );
+
+        let (model, _) = create_model(&["package a; @Backing(type=\"String\") enum E { A }"]);
+        assert_eq!(
+            model.validate()[0].diagnostic.message,
+            "String cannot be the backing type of an enum, only byte, int or long"
+        );
+    }
+
+    #[test]
+    fn test_evaluation_diagnostics() {
+        let (_, diagnostics) = create_model(&[
+            "package a; interface IFoo { const int A = UNKNOWN + 1; const int B = 1 / 0; const int C = 2; }",
+            "package a; parcelable Foo { int a = 1 / 0; int b = 2; }",
+            "package a; enum E { A = UNKNOWN, B, C = 3, D }",
+        ]);
+
+        let diagnostics = diagnostics
+            .iter()
+            .map(|d| {
+                (
+                    d.key.as_str(),
+                    d.member_index,
+                    d.diagnostic.code.as_str(),
+                    d.diagnostic.message.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            diagnostics,
+            vec![
+                (
+                    "a.E",
+                    Some(0),
+                    check::UNRESOLVED_REFERENCE,
+                    "cannot evaluate A: unresolved reference: UNKNOWN"
+                ),
+                (
+                    "a.Foo",
+                    Some(0),
+                    check::INVALID_EXPRESSION,
+                    "cannot evaluate a: division by zero"
+                ),
+                (
+                    "a.IFoo",
+                    Some(0),
+                    check::UNRESOLVED_REFERENCE,
+                    "cannot evaluate A: unresolved reference: UNKNOWN"
+                ),
+                (
+                    "a.IFoo",
+                    Some(1),
+                    check::INVALID_EXPRESSION,
+                    "cannot evaluate B: division by zero"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_recursion_limit() {
+        let source = "package a; interface IFoo { const int A = B; const int B = A + 1; }";
+        let (model, _) = create_model(&[source]);
+        assert_eq!(
+            get_const_values(&model, "a.IFoo"),
+            vec![("A".to_string(), None), ("B".to_string(), None)]
+        );
+
+        let interface = match *model.items["a.IFoo"] {
+            Item::Interface(ref i) => i,
+            _ => unreachable!(),
+        };
+        let error = model
+            .evaluate(
+                &interface.consts[0].value,
+                &interface.consts[0].const_type,
+                interface.get_key(),
+            )
+            .unwrap_err();
+        assert!(matches!(error, EvaluationError::RecursionLimit(ref name) if name == "B"));
+    }
+
+    // Key, member index and code of the diagnostics of the model once
+    // resolved and validated
+    fn get_diagnostic_codes(sources: &[&str]) -> Vec<(Key, Option<usize>, String)> {
+        let (model, mut diagnostics) = create_model(sources);
+        diagnostics.append(&mut model.validate());
+
+        diagnostics
+            .into_iter()
+            .map(|d| (d.key, d.member_index, d.diagnostic.code))
+            .collect()
+    }
+
+    fn code(key: &str, member_index: Option<usize>, code: &str) -> (Key, Option<usize>, String) {
+        (key.to_string(), member_index, code.to_string())
+    }
+
+    #[test]
+    fn test_generic_args() {
+        let (model, _) = create_model(&[
+            "package a; parcelable Foo { List raw; List<String> list; List<String, int> wrong; Map<String> map; Map<String, List> nested; }",
+            "package a; interface IFoo { Map f(in List<String, String> l); }",
+        ]);
+
+        let diagnostics = model
+            .validate()
+            .into_iter()
+            .map(|d| {
+                assert_eq!(d.diagnostic.code, validation::INVALID_GENERIC_ARGS);
+                (d.key, d.member_index, d.diagnostic.severity)
+            })
+            .collect::<Vec<_>>();
+
+        // Raw containers are only warnings
+        let entry = |key: &str, index, severity| (key.to_string(), Some(index), severity);
+        assert_eq!(
+            diagnostics,
+            vec![
+                entry("a.Foo", 0, Severity::Warning),
+                entry("a.Foo", 2, Severity::Error),
+                entry("a.Foo", 3, Severity::Error),
+                entry("a.Foo", 4, Severity::Warning),
+                entry("a.IFoo", 0, Severity::Warning),
+                entry("a.IFoo", 0, Severity::Error),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_item() {
+        assert_eq!(
+            get_diagnostic_codes(&["package a; parcelable Foo {}", "package a; enum Foo { A }"]),
+            vec![code("a.Foo", None, validation::DUPLICATE_ITEM)]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_name() {
+        assert_eq!(
+            get_diagnostic_codes(&[
+                "package a; interface IFoo { const int f = 1; void f(); void g(); void f(); }",
+                "package a; enum E { A, B, A }",
+            ]),
+            vec![
+                code("a.E", Some(2), validation::DUPLICATE_NAME),
+                code("a.IFoo", Some(3), validation::DUPLICATE_NAME),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_oneway() {
+        assert_eq!(
+            get_diagnostic_codes(&[
+                "package a; interface IFoo { oneway void f(in List<String> l); oneway int g(); oneway void h(out List<String> l); }"
+            ]),
+            vec![
+                code("a.IFoo", Some(1), validation::INVALID_ONEWAY),
+                code("a.IFoo", Some(2), validation::INVALID_ONEWAY),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_direction() {
+        let codes = get_diagnostic_codes(&[
+            "package a; interface IFoo { void f(List<String> l); void g(out int i); void h(in int i); void k(in int[] a, int b); }",
+        ]);
+        assert_eq!(
+            codes,
+            vec![
+                code("a.IFoo", Some(0), validation::MISSING_DIRECTION),
+                code("a.IFoo", Some(1), validation::INVALID_DIRECTION),
+                code("a.IFoo", Some(2), validation::INVALID_DIRECTION),
+            ]
+        );
+
+        // Redundant directions are only warnings
+        let (model, _) = create_model(&["package a; interface IFoo { void h(in int i); }"]);
+        assert_eq!(model.validate()[0].diagnostic.severity, Severity::Warning);
+    }
+
+    #[test]
+    fn test_const_type() {
+        // Only hexadecimal and octal values can use the unsigned
+        // representation of the type
+        assert_eq!(
+            get_diagnostic_codes(&["package a; interface IFoo {
+                     const int A = 4294967295;
+                     const int B = 0xffffffff;
+                     const byte C = 0xff;
+                     const byte D = 255;
+                     const String E = 1;
+                     const boolean F = true;
+                     const long G = 0xffffffffffffffff;
+                 }"]),
+            vec![
+                code("a.IFoo", Some(0), validation::CONST_TYPE_MISMATCH),
+                code("a.IFoo", Some(3), validation::CONST_TYPE_MISMATCH),
+                code("a.IFoo", Some(4), validation::CONST_TYPE_MISMATCH),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_enum_range() {
+        assert_eq!(
+            get_diagnostic_codes(&[
+                "package a; enum E { A = 127, B, C = 0xff }",
+                "package a; @Backing(type=\"int\") enum F { A = 2147483647, B }",
+            ]),
+            vec![
+                code("a.E", Some(1), validation::ENUM_VALUE_OUT_OF_RANGE),
+                code("a.F", Some(1), validation::ENUM_VALUE_OUT_OF_RANGE),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_package_path() {
+        let parse = |source: &str, path: &str| {
+            let mut file = aidl::parse(source).unwrap();
+            file.path = Some(path.to_string());
+            file
+        };
+        let mut model = aidl::create_model(vec![
+            parse("package a.b; parcelable Foo {}", "src/a/b/Foo.aidl"),
+            parse("package a.b; parcelable Bar {}", "src/a/Bar.aidl"),
+            parse("package a.b; parcelable Baz {}", "Baz.aidl"),
+        ]);
+        model.resolve_types();
+
+        let diagnostics = model.validate();
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].key, "a.b.Bar");
+        assert_eq!(diagnostics[1].key, "a.b.Baz");
+        assert!(diagnostics
+            .iter()
+            .all(|d| d.diagnostic.code == validation::PACKAGE_MISMATCH));
+        assert_eq!(
+            diagnostics[0].diagnostic.message,
+            "package a.b does not match the directory src/a"
+        );
+    }
+
+    #[test]
+    fn test_imports() {
+        assert_eq!(
+            get_diagnostic_codes(&[
+                "package a; parcelable Foo {} parcelable Bar {}",
+                "package b; import a.Foo; import a.Bar; import z.Unknown; import a.*; import y.*;
+                 parcelable Baz { Foo foo; }",
+            ]),
+            // a.Bar is unused, a.* is used by Foo
+            vec![
+                code("b.Baz", None, check::UNUSED_IMPORT),
+                code("b.Baz", None, check::UNKNOWN_IMPORT),
+                code("b.Baz", None, check::UNKNOWN_IMPORT),
+            ]
+        );
+
+        // Files declaring only an enum
+        assert_eq!(
+            get_diagnostic_codes(&[
+                "package a; parcelable Foo {}",
+                "package b; import a.Foo; import z.Unknown; enum E { A }",
+            ]),
+            vec![
+                code("b.E", None, check::UNUSED_IMPORT),
+                code("b.E", None, check::UNKNOWN_IMPORT),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unresolved_type() {
+        assert_eq!(
+            get_diagnostic_codes(&[
+                "package a; parcelable Foo { Unknown u; List<Unknown> l; Unknown[] a; String s; }"
+            ]),
+            vec![
+                code("a.Foo", Some(0), check::UNRESOLVED_TYPE),
+                code("a.Foo", Some(1), check::UNRESOLVED_TYPE),
+                code("a.Foo", Some(2), check::UNRESOLVED_TYPE),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_transaction_codes() {
+        let get_codes = |source: &str| {
+            let (model, _) = create_model(&[source]);
+            model
+                .validate()
+                .iter()
+                .filter(|d| d.diagnostic.code == validation::INVALID_TRANSACTION_CODE)
+                .map(|d| (d.member_index, d.diagnostic.message.clone()))
+                .collect::<Vec<_>>()
+        };
+
+        assert!(get_codes("package a; interface IFoo { void f() = 3; void g() = 1; }").is_empty());
+        assert!(get_codes("package a; interface IFoo { void f(); void g(); }").is_empty());
+        assert_eq!(
+            get_codes("package a; interface IFoo { void f() = 3; void g() = 3; }"),
+            vec![(
+                Some(1),
+                "transaction code 3 of g is already used".to_string()
+            )]
+        );
+        assert_eq!(
+            get_codes("package a; interface IFoo { void f() = 3; void g(); }"),
+            vec![(
+                Some(1),
+                "g has no transaction code, but other methods of IFoo do".to_string()
+            )]
+        );
+        assert_eq!(
+            get_codes("package a; interface IFoo { void f() = 16777214; }"),
+            vec![(
+                Some(0),
+                "transaction code 16777214 of f is too large".to_string()
+            )]
+        );
+
+        let source = "package a; interface IFoo { void f() = 4294967295; }";
+        assert_eq!(
+            get_codes(source),
+            vec![(
+                Some(0),
+                "transaction code 4294967295 of f is too large".to_string()
+            )]
+        );
+        let (model, _) = create_model(&[source]);
+        let method = match *model.items["a.IFoo"] {
+            Item::Interface(ref i) => &i.methods[0],
+            _ => unreachable!(),
+        };
+        assert_eq!(method.transaction_code.get_value(), None);
+    }
+
+    #[test]
+    fn test_meta_transactions() {
+        let source = "package a.b; @VintfStability interface IFoo { void f(); }";
+        let mut file = aidl::parse(source).unwrap();
+        file.path = Some("foo/aidl_api/foo/2/a/b/IFoo.aidl".to_string());
+        let model = aidl::create_model(vec![
+            file,
+            aidl::parse("package a; interface IBar {}").unwrap(),
+        ]);
+
+        let get_interface = |key: &str| match *model.items[key] {
+            Item::Interface(ref i) => i,
+            _ => unreachable!(),
+        };
+
+        let foo = get_interface("a.b.IFoo");
+        assert!(foo.is_stable());
+        assert_eq!(foo.get_frozen_version(), Some(2));
+        assert_eq!(
+            foo.get_meta_transactions()
+                .iter()
+                .map(|t| (t.get_name(), t.get_value()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("getInterfaceVersion", 0x00ff_ffff),
+                ("getInterfaceHash", 0x00ff_fffe)
+            ]
+        );
+        assert_eq!(
+            foo.methods[0].transaction_code,
+            TransactionCode::Implicit(0)
+        );
+
+        let bar = get_interface("a.IBar");
+        assert!(!bar.is_stable());
+        assert_eq!(bar.get_frozen_version(), None);
+        assert!(bar.get_meta_transactions().is_empty());
+    }
 }
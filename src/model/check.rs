@@ -0,0 +1,333 @@
+use std::{collections::HashSet, rc::Rc};
+
+use super::{Arg, EvaluationError, Expression, Item, Key, Location, Model, Type};
+use crate::diagnostic::{Diagnostic, Severity};
+
+// Error codes of the model diagnostics
+pub const UNRESOLVED_TYPE: &str = "M001";
+pub const AMBIGUOUS_REFERENCE: &str = "M002";
+pub const UNKNOWN_IMPORT: &str = "M003";
+pub const UNUSED_IMPORT: &str = "M004";
+pub const UNRESOLVED_REFERENCE: &str = "M005";
+pub const INVALID_EXPRESSION: &str = "M006";
+
+// A problem found in an item, or in one of its members (same index as the
+// one of the dependencies)
+#[derive(Clone, Debug)]
+pub struct ItemDiagnostic {
+    pub key: Key,
+    pub member_index: Option<usize>,
+    pub diagnostic: Diagnostic,
+}
+
+impl ItemDiagnostic {
+    pub fn new<S: Into<String>>(
+        key: &Key,
+        member_index: Option<usize>,
+        location: &Location,
+        severity: Severity,
+        code: &str,
+        message: S,
+    ) -> Self {
+        let mut diagnostic = Diagnostic::new(severity, code, message);
+        diagnostic.path = location.path.clone();
+        diagnostic.range = Some(location.range);
+
+        ItemDiagnostic {
+            key: key.clone(),
+            member_index,
+            diagnostic,
+        }
+    }
+}
+
+impl Model {
+    // Look for the references which could not be resolved and for the
+    // imports which are not needed (sorted by item)
+    pub(super) fn check_references(&self) -> Vec<ItemDiagnostic> {
+        let mut items: Vec<&Rc<Item>> = self.items.values().collect();
+        items.sort_by(|a, b| a.get_key().cmp(b.get_key()));
+
+        let mut diagnostics = Vec::new();
+        for item in items {
+            self.check_types(item, &mut diagnostics);
+            self.check_values(item, &mut diagnostics);
+
+            // Nested items share the imports of their outer item
+            if item.get_parent().is_none() {
+                self.check_imports(item, &mut diagnostics);
+            }
+        }
+
+        diagnostics
+    }
+
+    fn check_types(&self, item: &Item, diagnostics: &mut Vec<ItemDiagnostic>) {
+        match item {
+            Item::Interface(i) => {
+                for t in &i.super_interfaces {
+                    self.check_type(item, None, t, &i.location, diagnostics);
+                }
+
+                for c in &i.consts {
+                    self.check_type(item, Some(c.index), &c.const_type, &c.location, diagnostics);
+                }
+
+                for m in &i.methods {
+                    self.check_arg(item, m.index, &m.return_arg, diagnostics);
+                    for a in &m.args {
+                        self.check_arg(item, m.index, a, diagnostics);
+                    }
+                }
+            }
+            Item::Struct(s) => {
+                for m in &s.members {
+                    self.check_arg(item, m.index, &m.arg, diagnostics);
+                }
+            }
+            Item::Union(u) => {
+                for m in &u.members {
+                    self.check_arg(item, m.index, &m.arg, diagnostics);
+                }
+            }
+            Item::Enum(_) | Item::OpaqueStruct(_) => (),
+        }
+    }
+
+    // Values which could not be evaluated (e.g.: UNKNOWN + 1 or 1 / 0)
+    fn check_values(&self, item: &Item, diagnostics: &mut Vec<ItemDiagnostic>) {
+        let mut check_value =
+            |member_index, name: &str, value: &Expression, the_type: &Type, location: &Location| {
+                if let Err(e) = self.evaluate(value, the_type, item.get_key()) {
+                    diagnostics.push(evaluation_diagnostic(
+                        item,
+                        member_index,
+                        name,
+                        &e,
+                        location,
+                    ));
+                }
+            };
+
+        for c in item
+            .get_consts()
+            .iter()
+            .filter(|c| c.computed_value.is_none())
+        {
+            check_value(c.index, &c.name, &c.value, &c.const_type, &c.location);
+        }
+
+        for m in item.get_members() {
+            if let (Some(ref value), None) = (&m.default_value, &m.computed_default_value) {
+                check_value(m.index, &m.arg.name, value, &m.arg.arg_type, &m.location);
+            }
+        }
+
+        if let Item::Enum(ref e) = *item {
+            for (pos, element) in e.elements.iter().enumerate() {
+                // Only the first element of a run of elements without value
+                // (e.g.: B and C in A = UNKNOWN, B, C) is reported
+                let is_first_failure = element.value.is_some()
+                    || pos == 0
+                    || e.elements[pos - 1].computed_value.is_some();
+                if element.computed_value.is_some() || !is_first_failure {
+                    continue;
+                }
+
+                if let Err(error) = self.evaluate_enum_element(e, element.index, 0) {
+                    diagnostics.push(evaluation_diagnostic(
+                        item,
+                        element.index,
+                        &element.name,
+                        &error,
+                        &element.location,
+                    ));
+                }
+            }
+        }
+    }
+
+    fn check_arg(
+        &self,
+        item: &Item,
+        member_index: usize,
+        arg: &Arg,
+        diagnostics: &mut Vec<ItemDiagnostic>,
+    ) {
+        self.check_type(
+            item,
+            Some(member_index),
+            &arg.arg_type,
+            &arg.location,
+            diagnostics,
+        );
+
+        for generic_arg in &arg.generic_args {
+            self.check_arg(item, member_index, generic_arg, diagnostics);
+        }
+    }
+
+    fn check_type(
+        &self,
+        item: &Item,
+        member_index: Option<usize>,
+        the_type: &Type,
+        location: &Location,
+        diagnostics: &mut Vec<ItemDiagnostic>,
+    ) {
+        if let Type::Unresolved(ref unresolved_type) = *the_type.get_element_type() {
+            // Resolve again to know why it failed
+            let (code, message) =
+                match self.get_resolved_type(unresolved_type, item.get_imports(), item.get_key()) {
+                    Err(e) => (AMBIGUOUS_REFERENCE, e.to_string()),
+                    Ok(_) => (
+                        UNRESOLVED_TYPE,
+                        format!("unresolved type {}", unresolved_type.name),
+                    ),
+                };
+
+            diagnostics.push(ItemDiagnostic::new(
+                item.get_key(),
+                member_index,
+                location,
+                Severity::Error,
+                code,
+                message,
+            ));
+        }
+    }
+
+    fn check_imports(&self, item: &Item, diagnostics: &mut Vec<ItemDiagnostic>) {
+        let used_keys = self.find_used_keys(item);
+
+        for import in item.get_imports() {
+            // Either a package (e.g.: a.b.*) or an item, possibly nested
+            let (exists, used) = match import.strip_suffix(".*") {
+                Some(pkg_path) => {
+                    let prefix = format!("{}.", pkg_path);
+                    (
+                        self.items.keys().any(|k| k.starts_with(&prefix)),
+                        used_keys.iter().any(|k| k.starts_with(&prefix)),
+                    )
+                }
+                None => {
+                    let nested_prefix = format!("{}.", import);
+                    (
+                        self.items.contains_key(import),
+                        used_keys
+                            .iter()
+                            .any(|k| k == import || k.starts_with(&nested_prefix)),
+                    )
+                }
+            };
+
+            if !exists {
+                let mut item_diagnostic = ItemDiagnostic::new(
+                    item.get_key(),
+                    None,
+                    item.get_location(),
+                    Severity::Warning,
+                    UNKNOWN_IMPORT,
+                    format!("import of unknown item {}", import),
+                );
+                item_diagnostic
+                    .diagnostic
+                    .notes
+                    .push("the item is not part of the opened files".to_string());
+                diagnostics.push(item_diagnostic);
+            } else if !used {
+                diagnostics.push(ItemDiagnostic::new(
+                    item.get_key(),
+                    None,
+                    item.get_location(),
+                    Severity::Warning,
+                    UNUSED_IMPORT,
+                    format!("unused import {}", import),
+                ));
+            }
+        }
+    }
+
+    // Keys of the items referenced by an item or by its nested items, either
+    // as types or in const expressions (e.g.: IFoo.BAR)
+    fn find_used_keys(&self, outer_item: &Item) -> HashSet<Key> {
+        let mut used_keys = HashSet::new();
+
+        for item in self.items.values() {
+            if !self.is_nested_in(item, outer_item.get_key()) {
+                continue;
+            }
+
+            for (_, _, dependency) in self.find_dependencies(item) {
+                used_keys.insert(dependency.get_key().clone());
+            }
+
+            let enum_values = match **item {
+                Item::Enum(ref e) => e.elements.iter().filter_map(|e| e.value.as_ref()).collect(),
+                _ => Vec::new(),
+            };
+            let expressions = item
+                .get_consts()
+                .iter()
+                .map(|c| &c.value)
+                .chain(
+                    item.get_members()
+                        .iter()
+                        .filter_map(|m| m.default_value.as_ref()),
+                )
+                .chain(enum_values);
+
+            for name in expressions.flat_map(|e| e.get_references()) {
+                if let Some(pos) = name.rfind('.') {
+                    let found = self.find_item(
+                        &name[..pos],
+                        &item.get_pkg().path,
+                        item.get_imports(),
+                        item.get_key(),
+                    );
+                    if let Ok(Some(referenced_item)) = found {
+                        used_keys.insert(referenced_item.get_key().clone());
+                    }
+                }
+            }
+        }
+
+        used_keys
+    }
+
+    // True for the item itself and for the items nested in it
+    fn is_nested_in(&self, item: &Item, outer_key: &Key) -> bool {
+        let mut current_key = Some(item.get_key());
+        while let Some(key) = current_key {
+            if key == outer_key {
+                return true;
+            }
+
+            current_key = self.items.get(key).and_then(|i| i.get_parent());
+        }
+
+        false
+    }
+}
+
+fn evaluation_diagnostic(
+    item: &Item,
+    member_index: usize,
+    name: &str,
+    error: &EvaluationError,
+    location: &Location,
+) -> ItemDiagnostic {
+    let code = match error {
+        EvaluationError::UnresolvedReference(_) => UNRESOLVED_REFERENCE,
+        _ => INVALID_EXPRESSION,
+    };
+
+    ItemDiagnostic::new(
+        item.get_key(),
+        Some(member_index),
+        location,
+        Severity::Error,
+        code,
+        format!("cannot evaluate {}: {}", name, error),
+    )
+}
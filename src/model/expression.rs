@@ -0,0 +1,759 @@
+use std::fmt::Display;
+
+#[derive(Clone, Debug)]
+pub enum Expression {
+    Literal(Literal),
+    Reference(String),
+    Unary(UnaryOp, Box<Expression>),
+    Binary(Box<Expression>, BinaryOp, Box<Expression>),
+    Array(Vec<Expression>),
+}
+
+// Numbers and chars are kept as written (e.g.: 0x1F, 12L, 1.5f, '\n')
+#[derive(Clone, Debug)]
+pub enum Literal {
+    Bool(bool),
+    Int(String),
+    Float(String),
+    Char(String),
+    String(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UnaryOp {
+    Plus,
+    Minus,
+    Not,
+    BitNot,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BinaryOp {
+    Mul,
+    Div,
+    Rem,
+    Add,
+    Sub,
+    Shl,
+    Shr,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+    Eq,
+    Ne,
+    BitAnd,
+    BitXor,
+    BitOr,
+    And,
+    Or,
+}
+
+impl BinaryOp {
+    pub fn get_symbol(&self) -> &'static str {
+        match self {
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+            BinaryOp::Rem => "%",
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Shl => "<<",
+            BinaryOp::Shr => ">>",
+            BinaryOp::Lt => "<",
+            BinaryOp::Gt => ">",
+            BinaryOp::Le => "<=",
+            BinaryOp::Ge => ">=",
+            BinaryOp::Eq => "==",
+            BinaryOp::Ne => "!=",
+            BinaryOp::BitAnd => "&",
+            BinaryOp::BitXor => "^",
+            BinaryOp::BitOr => "|",
+            BinaryOp::And => "&&",
+            BinaryOp::Or => "||",
+        }
+    }
+
+    // Higher binds tighter
+    fn get_precedence(&self) -> u8 {
+        match self {
+            BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem => 10,
+            BinaryOp::Add | BinaryOp::Sub => 9,
+            BinaryOp::Shl | BinaryOp::Shr => 8,
+            BinaryOp::Lt | BinaryOp::Gt | BinaryOp::Le | BinaryOp::Ge => 7,
+            BinaryOp::Eq | BinaryOp::Ne => 6,
+            BinaryOp::BitAnd => 5,
+            BinaryOp::BitXor => 4,
+            BinaryOp::BitOr => 3,
+            BinaryOp::And => 2,
+            BinaryOp::Or => 1,
+        }
+    }
+}
+
+impl Expression {
+    // Names of the consts referenced in the expression (e.g.: FOO or IBar.BAZ)
+    pub fn get_references(&self) -> Vec<&str> {
+        match self {
+            Expression::Literal(_) => Vec::new(),
+            Expression::Reference(name) => vec![name],
+            Expression::Unary(_, expr) => expr.get_references(),
+            Expression::Binary(lhs, _, rhs) => {
+                let mut references = lhs.get_references();
+                references.append(&mut rhs.get_references());
+                references
+            }
+            Expression::Array(elements) => {
+                elements.iter().flat_map(|e| e.get_references()).collect()
+            }
+        }
+    }
+}
+
+impl Display for Expression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expression::Literal(Literal::Bool(b)) => write!(f, "{}", b),
+            Expression::Literal(Literal::Int(i)) => write!(f, "{}", i),
+            Expression::Literal(Literal::Float(n)) => write!(f, "{}", n),
+            Expression::Literal(Literal::Char(c)) => write!(f, "'{}'", c),
+            Expression::Literal(Literal::String(s)) => write!(f, "\"{}\"", s),
+            Expression::Reference(name) => write!(f, "{}", name),
+            Expression::Unary(op, expr) => {
+                let symbol = match op {
+                    UnaryOp::Plus => "+",
+                    UnaryOp::Minus => "-",
+                    UnaryOp::Not => "!",
+                    UnaryOp::BitNot => "~",
+                };
+                match **expr {
+                    Expression::Binary(..) => write!(f, "{}({})", symbol, expr),
+                    _ => write!(f, "{}{}", symbol, expr),
+                }
+            }
+            Expression::Binary(lhs, op, rhs) => {
+                // Only add the parentheses required by the operator precedence
+                match **lhs {
+                    Expression::Binary(_, ref lhs_op, _)
+                        if lhs_op.get_precedence() < op.get_precedence() =>
+                    {
+                        write!(f, "({})", lhs)?
+                    }
+                    _ => write!(f, "{}", lhs)?,
+                }
+                write!(f, " {} ", op.get_symbol())?;
+                match **rhs {
+                    Expression::Binary(_, ref rhs_op, _)
+                        if rhs_op.get_precedence() <= op.get_precedence() =>
+                    {
+                        write!(f, "({})", rhs)
+                    }
+                    _ => write!(f, "{}", rhs),
+                }
+            }
+            Expression::Array(elements) => write!(
+                f,
+                "{{{}}}",
+                elements
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+// Integral type in which a const expression is evaluated, its operations
+// wrapping around (e.g.: 1 << 31 is negative for an int, not for a long)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IntegralType {
+    pub bits: u32,
+    pub is_signed: bool,
+}
+
+impl IntegralType {
+    pub fn new(bits: u32, is_signed: bool) -> Self {
+        IntegralType { bits, is_signed }
+    }
+
+    // Values of a 64-bit unsigned type are kept with the same bits
+    pub fn contains(&self, value: i64) -> bool {
+        if self.bits >= 64 {
+            return true;
+        }
+
+        let (min, max) = if self.is_signed {
+            (-(1 << (self.bits - 1)), (1 << (self.bits - 1)) - 1)
+        } else {
+            (0, (1 << self.bits) - 1)
+        };
+
+        value >= min && value <= max
+    }
+
+    pub fn wrap(&self, value: i64) -> i64 {
+        if self.bits >= 64 {
+            return value;
+        }
+
+        let shift = 64 - self.bits;
+        if self.is_signed {
+            (value << shift) >> shift
+        } else {
+            (((value as u64) << shift) >> shift) as i64
+        }
+    }
+
+    // Hexadecimal and octal literals may use the unsigned representation of
+    // the type (e.g.: 0xFFFFFFFF is -1 for an int), a value which does not fit
+    // is kept as is (see the validation)
+    fn of_unsigned(&self, value: u64) -> i64 {
+        if self.bits >= 64 || value >> self.bits == 0 {
+            self.wrap(value as i64)
+        } else {
+            value as i64
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Char(char),
+    String(String),
+    Array(Vec<Value>),
+}
+
+impl Value {
+    pub fn from_literal(
+        literal: &Literal,
+        integral_type: Option<IntegralType>,
+    ) -> Result<Self, EvaluationError> {
+        match literal {
+            Literal::Bool(b) => Ok(Value::Bool(*b)),
+            Literal::Int(i) => parse_int(i, integral_type)
+                .map(Value::Int)
+                .ok_or_else(|| EvaluationError::InvalidLiteral(i.clone())),
+            Literal::Float(n) => n
+                .trim_end_matches(&['f', 'F', 'd', 'D'][..])
+                .parse::<f64>()
+                .map(Value::Float)
+                .map_err(|_| EvaluationError::InvalidLiteral(n.clone())),
+            Literal::Char(c) => parse_char(c)
+                .map(Value::Char)
+                .ok_or_else(|| EvaluationError::InvalidLiteral(c.clone())),
+            Literal::String(s) => Ok(Value::String(unescape(s))),
+        }
+    }
+
+    pub fn apply_unary(op: UnaryOp, value: Value) -> Result<Self, EvaluationError> {
+        match (op, value) {
+            (UnaryOp::Plus, Value::Int(i)) => Ok(Value::Int(i)),
+            (UnaryOp::Plus, Value::Float(n)) => Ok(Value::Float(n)),
+            (UnaryOp::Minus, Value::Int(i)) => Ok(Value::Int(i.wrapping_neg())),
+            (UnaryOp::Minus, Value::Float(n)) => Ok(Value::Float(-n)),
+            (UnaryOp::Not, Value::Bool(b)) => Ok(Value::Bool(!b)),
+            (UnaryOp::Not, Value::Int(i)) => Ok(Value::Bool(i == 0)),
+            (UnaryOp::BitNot, Value::Int(i)) => Ok(Value::Int(!i)),
+            (op, value) => Err(EvaluationError::InvalidOperand(format!("{:?}", op), value)),
+        }
+    }
+
+    pub fn apply_binary(lhs: Value, op: BinaryOp, rhs: Value) -> Result<Self, EvaluationError> {
+        match (lhs, rhs) {
+            // String concatenation
+            (Value::String(l), r) if op == BinaryOp::Add => Ok(Value::String(l + &r.to_text())),
+            (l, Value::String(r)) if op == BinaryOp::Add => Ok(Value::String(l.to_text() + &r)),
+            (Value::String(l), Value::String(r)) => compare(op, l.cmp(&r)),
+
+            // Logical operations
+            (l, r) if op == BinaryOp::And || op == BinaryOp::Or => {
+                match (l.as_bool(), r.as_bool()) {
+                    (Some(l), Some(r)) if op == BinaryOp::And => Ok(Value::Bool(l && r)),
+                    (Some(l), Some(r)) => Ok(Value::Bool(l || r)),
+                    (None, _) => Err(EvaluationError::InvalidOperand(op.get_symbol().into(), l)),
+                    (_, None) => Err(EvaluationError::InvalidOperand(op.get_symbol().into(), r)),
+                }
+            }
+
+            // Integer operations (booleans and chars are promoted to integers)
+            (l, r) if l.as_int().is_some() && r.as_int().is_some() => {
+                let (l, r) = (l.as_int().unwrap(), r.as_int().unwrap());
+                match op {
+                    BinaryOp::Mul => Ok(Value::Int(l.wrapping_mul(r))),
+                    BinaryOp::Div if r == 0 => Err(EvaluationError::DivisionByZero),
+                    BinaryOp::Div => Ok(Value::Int(l.wrapping_div(r))),
+                    BinaryOp::Rem if r == 0 => Err(EvaluationError::DivisionByZero),
+                    BinaryOp::Rem => Ok(Value::Int(l.wrapping_rem(r))),
+                    BinaryOp::Add => Ok(Value::Int(l.wrapping_add(r))),
+                    BinaryOp::Sub => Ok(Value::Int(l.wrapping_sub(r))),
+                    BinaryOp::Shl | BinaryOp::Shr if !(0..64).contains(&r) => Err(
+                        EvaluationError::InvalidOperand(op.get_symbol().into(), Value::Int(r)),
+                    ),
+                    BinaryOp::Shl => Ok(Value::Int(l << r)),
+                    BinaryOp::Shr => Ok(Value::Int(l >> r)),
+                    BinaryOp::BitAnd => Ok(Value::Int(l & r)),
+                    BinaryOp::BitXor => Ok(Value::Int(l ^ r)),
+                    BinaryOp::BitOr => Ok(Value::Int(l | r)),
+                    _ => compare(op, l.cmp(&r)),
+                }
+            }
+
+            // Floating point operations (integers are promoted to floats)
+            (l, r) if l.as_float().is_some() && r.as_float().is_some() => {
+                let (l, r) = (l.as_float().unwrap(), r.as_float().unwrap());
+                match op {
+                    BinaryOp::Mul => Ok(Value::Float(l * r)),
+                    BinaryOp::Div => Ok(Value::Float(l / r)),
+                    BinaryOp::Rem => Ok(Value::Float(l % r)),
+                    BinaryOp::Add => Ok(Value::Float(l + r)),
+                    BinaryOp::Sub => Ok(Value::Float(l - r)),
+                    _ => match l.partial_cmp(&r) {
+                        Some(ordering) => compare(op, ordering),
+                        None => Ok(Value::Bool(op == BinaryOp::Ne)),
+                    },
+                }
+            }
+
+            (l, _) => Err(EvaluationError::InvalidOperand(op.get_symbol().into(), l)),
+        }
+    }
+
+    // Result of an operation, in the type of the evaluated expression
+    pub fn wrapped(self, integral_type: Option<IntegralType>) -> Self {
+        match (self, integral_type) {
+            (Value::Int(i), Some(t)) => Value::Int(t.wrap(i)),
+            (value, _) => value,
+        }
+    }
+
+    fn as_int(&self) -> Option<i64> {
+        match *self {
+            Value::Bool(b) => Some(b as i64),
+            Value::Int(i) => Some(i),
+            Value::Char(c) => Some(c as i64),
+            _ => None,
+        }
+    }
+
+    fn as_float(&self) -> Option<f64> {
+        match *self {
+            Value::Float(n) => Some(n),
+            _ => self.as_int().map(|i| i as f64),
+        }
+    }
+
+    fn as_bool(&self) -> Option<bool> {
+        match *self {
+            Value::Bool(b) => Some(b),
+            Value::Int(i) => Some(i != 0),
+            _ => None,
+        }
+    }
+
+    // Text used for string concatenation (i.e. without quotes)
+    fn to_text(&self) -> String {
+        match self {
+            Value::String(s) => s.clone(),
+            Value::Char(c) => c.to_string(),
+            _ => self.to_string(),
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Int(i) => write!(f, "{}", i),
+            Value::Float(n) => write!(f, "{:?}", n),
+            Value::Char(c) => write!(f, "'{}'", c.escape_default()),
+            Value::String(s) => write!(f, "\"{}\"", s.escape_default()),
+            Value::Array(values) => write!(
+                f,
+                "{{{}}}",
+                values
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum EvaluationError {
+    InvalidLiteral(String),
+    InvalidOperand(String, Value),
+    DivisionByZero,
+    UnresolvedReference(String),
+    RecursionLimit(String),
+}
+
+impl Display for EvaluationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EvaluationError::InvalidLiteral(l) => write!(f, "invalid literal: {}", l),
+            EvaluationError::InvalidOperand(op, v) => {
+                write!(f, "invalid operand for '{}': {}", op, v)
+            }
+            EvaluationError::DivisionByZero => write!(f, "division by zero"),
+            EvaluationError::UnresolvedReference(r) => write!(f, "unresolved reference: {}", r),
+            EvaluationError::RecursionLimit(r) => {
+                write!(f, "recursive definition while evaluating {}", r)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EvaluationError {}
+
+fn compare(op: BinaryOp, ordering: std::cmp::Ordering) -> Result<Value, EvaluationError> {
+    use std::cmp::Ordering::*;
+
+    Ok(Value::Bool(match op {
+        BinaryOp::Lt => ordering == Less,
+        BinaryOp::Gt => ordering == Greater,
+        BinaryOp::Le => ordering != Greater,
+        BinaryOp::Ge => ordering != Less,
+        BinaryOp::Eq => ordering == Equal,
+        BinaryOp::Ne => ordering != Equal,
+        _ => {
+            return Err(EvaluationError::InvalidOperand(
+                op.get_symbol().into(),
+                Value::Bool(false),
+            ))
+        }
+    }))
+}
+
+// Examples:
+// - 42, 42L, 255u8
+// - 0x2A, 0X2a
+// - 052 (octal)
+fn parse_int(literal: &str, integral_type: Option<IntegralType>) -> Option<i64> {
+    let digits = literal
+        .trim_end_matches("u8")
+        .trim_end_matches(&['l', 'L'][..]);
+
+    let (digits, radix) = if digits.starts_with("0x") || digits.starts_with("0X") {
+        (&digits[2..], 16)
+    } else if digits.len() > 1 && digits.starts_with('0') {
+        (&digits[1..], 8)
+    } else {
+        (digits, 10)
+    };
+
+    let value = u64::from_str_radix(digits, radix).ok()?;
+
+    match integral_type {
+        Some(t) if radix != 10 => Some(t.of_unsigned(value)),
+        // Hexadecimal values may overflow into the sign bit (e.g.: 0xFFFFFFFFFFFFFFFF)
+        _ => Some(value as i64),
+    }
+}
+
+fn parse_char(literal: &str) -> Option<char> {
+    let unescaped = unescape(literal);
+    let mut chars = unescaped.chars();
+
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
+
+fn unescape(s: &str) -> String {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some('r') => unescaped.push('\r'),
+            Some('0') => unescaped.push('\0'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INT: Option<IntegralType> = Some(IntegralType {
+        bits: 32,
+        is_signed: true,
+    });
+    const LONG: Option<IntegralType> = Some(IntegralType {
+        bits: 64,
+        is_signed: true,
+    });
+
+    fn int(i: &str) -> Expression {
+        Expression::Literal(Literal::Int(i.to_string()))
+    }
+
+    fn binary(lhs: Expression, op: BinaryOp, rhs: Expression) -> Expression {
+        Expression::Binary(Box::new(lhs), op, Box::new(rhs))
+    }
+
+    fn eval_literal(literal: Literal, integral_type: Option<IntegralType>) -> Option<Value> {
+        Value::from_literal(&literal, integral_type).ok()
+    }
+
+    fn eval_binary(lhs: Value, op: BinaryOp, rhs: Value) -> Option<Value> {
+        Value::apply_binary(lhs, op, rhs).ok()
+    }
+
+    #[test]
+    fn test_int_literals() {
+        let int_literal = |i: &str, t| eval_literal(Literal::Int(i.to_string()), t);
+
+        assert_eq!(int_literal("42", None), Some(Value::Int(42)));
+        assert_eq!(int_literal("42L", LONG), Some(Value::Int(42)));
+        assert_eq!(int_literal("255u8", None), Some(Value::Int(255)));
+        assert_eq!(int_literal("0X2a", INT), Some(Value::Int(42)));
+        assert_eq!(int_literal("052", INT), Some(Value::Int(42)));
+        assert_eq!(int_literal("0", INT), Some(Value::Int(0)));
+        assert_eq!(int_literal("0x", INT), None);
+        assert_eq!(int_literal("09", INT), None);
+
+        // Unsigned representation of hexadecimal and octal literals only
+        assert_eq!(int_literal("0xFFFFFFFF", INT), Some(Value::Int(-1)));
+        assert_eq!(int_literal("037777777777", INT), Some(Value::Int(-1)));
+        assert_eq!(
+            int_literal("0x80000000", INT),
+            Some(Value::Int(i32::MIN as i64))
+        );
+        assert_eq!(int_literal("4294967295", INT), Some(Value::Int(4294967295)));
+        assert_eq!(
+            int_literal("0x1FFFFFFFF", INT),
+            Some(Value::Int(0x1FFFFFFFF))
+        );
+        assert_eq!(
+            int_literal("0xFFFFFFFF", LONG),
+            Some(Value::Int(4294967295))
+        );
+        assert_eq!(
+            int_literal("0xFFFFFFFFFFFFFFFFL", LONG),
+            Some(Value::Int(-1))
+        );
+        assert_eq!(
+            int_literal("0xFF", Some(IntegralType::new(8, true))),
+            Some(Value::Int(-1))
+        );
+        assert_eq!(
+            int_literal("0xFFFFFFFF", Some(IntegralType::new(32, false))),
+            Some(Value::Int(4294967295))
+        );
+    }
+
+    #[test]
+    fn test_other_literals() {
+        assert_eq!(
+            eval_literal(Literal::Float("1.5f".to_string()), None),
+            Some(Value::Float(1.5))
+        );
+        assert_eq!(
+            eval_literal(Literal::Float("2e3".to_string()), None),
+            Some(Value::Float(2000.0))
+        );
+        assert_eq!(
+            eval_literal(Literal::Bool(true), INT),
+            Some(Value::Bool(true))
+        );
+
+        let char_literal = |c: &str| eval_literal(Literal::Char(c.to_string()), None);
+        assert_eq!(char_literal("a"), Some(Value::Char('a')));
+        assert_eq!(char_literal("\\n"), Some(Value::Char('\n')));
+        assert_eq!(char_literal("\\'"), Some(Value::Char('\'')));
+        assert_eq!(char_literal("\\\\"), Some(Value::Char('\\')));
+        assert_eq!(char_literal("ab"), None);
+        assert_eq!(char_literal(""), None);
+
+        let string_value = eval_literal(Literal::String("a\\tb\\\"c\\\\".to_string()), None);
+        assert_eq!(string_value, Some(Value::String("a\tb\"c\\".to_string())));
+        assert_eq!(string_value.unwrap().to_string(), "\"a\\tb\\\"c\\\\\"");
+        assert_eq!(Value::Char('\n').to_string(), "'\\n'");
+    }
+
+    #[test]
+    fn test_integral_type() {
+        let byte = IntegralType::new(8, true);
+        assert!(byte.contains(-128) && byte.contains(127));
+        assert!(!byte.contains(128) && !byte.contains(255));
+        assert_eq!(byte.wrap(128), -128);
+        assert_eq!(byte.wrap(-129), 127);
+
+        let uint16 = IntegralType::new(16, false);
+        assert!(uint16.contains(65535) && !uint16.contains(-1));
+        assert_eq!(uint16.wrap(-1), 65535);
+
+        let long = IntegralType::new(64, true);
+        assert!(long.contains(i64::MIN));
+        assert_eq!(long.wrap(i64::MIN), i64::MIN);
+
+        assert_eq!(
+            Value::Int(1 << 31).wrapped(INT),
+            Value::Int(i32::MIN as i64)
+        );
+        assert_eq!(Value::Int(1 << 31).wrapped(LONG), Value::Int(1 << 31));
+        assert_eq!(Value::Int(1 << 31).wrapped(None), Value::Int(1 << 31));
+        assert_eq!(Value::Float(1.5).wrapped(INT), Value::Float(1.5));
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        use Value::{Bool, Char, Float, Int};
+
+        assert_eq!(eval_binary(Int(7), BinaryOp::Div, Int(2)), Some(Int(3)));
+        assert_eq!(eval_binary(Int(-7), BinaryOp::Rem, Int(2)), Some(Int(-1)));
+        assert_eq!(
+            eval_binary(Int(7), BinaryOp::Div, Float(2.0)),
+            Some(Float(3.5))
+        );
+        assert_eq!(eval_binary(Char('a'), BinaryOp::Add, Int(1)), Some(Int(98)));
+        assert_eq!(eval_binary(Bool(true), BinaryOp::Add, Int(1)), Some(Int(2)));
+        assert_eq!(
+            eval_binary(Int(i64::MAX), BinaryOp::Add, Int(1)),
+            Some(Int(i64::MIN))
+        );
+        assert!(matches!(
+            Value::apply_binary(Int(1), BinaryOp::Div, Int(0)),
+            Err(EvaluationError::DivisionByZero)
+        ));
+        assert!(matches!(
+            Value::apply_binary(Int(1), BinaryOp::Rem, Int(0)),
+            Err(EvaluationError::DivisionByZero)
+        ));
+        assert_eq!(
+            Value::apply_unary(UnaryOp::Minus, Int(i64::MIN)).ok(),
+            Some(Int(i64::MIN))
+        );
+        assert_eq!(
+            Value::apply_unary(UnaryOp::BitNot, Int(0)).ok(),
+            Some(Int(-1))
+        );
+        assert_eq!(
+            Value::apply_unary(UnaryOp::Not, Int(0)).ok(),
+            Some(Bool(true))
+        );
+        assert!(Value::apply_unary(UnaryOp::Minus, Bool(true)).is_err());
+    }
+
+    #[test]
+    fn test_shifts() {
+        use Value::Int;
+
+        assert_eq!(
+            eval_binary(Int(1), BinaryOp::Shl, Int(31)),
+            Some(Int(1 << 31))
+        );
+        assert_eq!(eval_binary(Int(-16), BinaryOp::Shr, Int(2)), Some(Int(-4)));
+        assert_eq!(
+            eval_binary(Int(1), BinaryOp::Shl, Int(63)),
+            Some(Int(i64::MIN))
+        );
+        assert_eq!(eval_binary(Int(1), BinaryOp::Shl, Int(64)), None);
+        assert_eq!(eval_binary(Int(1), BinaryOp::Shr, Int(-1)), None);
+    }
+
+    #[test]
+    fn test_comparisons_and_logic() {
+        use Value::{Bool, Float, Int, String};
+
+        assert_eq!(eval_binary(Int(1), BinaryOp::Lt, Int(2)), Some(Bool(true)));
+        assert_eq!(
+            eval_binary(Int(2), BinaryOp::Le, Float(1.5)),
+            Some(Bool(false))
+        );
+        assert_eq!(
+            eval_binary(Float(f64::NAN), BinaryOp::Ne, Float(f64::NAN)),
+            Some(Bool(true))
+        );
+        assert_eq!(
+            eval_binary(String("a".into()), BinaryOp::Lt, String("b".into())),
+            Some(Bool(true))
+        );
+        assert_eq!(
+            eval_binary(Int(1), BinaryOp::And, Bool(false)),
+            Some(Bool(false))
+        );
+        assert_eq!(
+            eval_binary(Bool(false), BinaryOp::Or, Int(2)),
+            Some(Bool(true))
+        );
+        assert_eq!(
+            eval_binary(String("a".into()), BinaryOp::Or, Bool(true)),
+            None
+        );
+        assert_eq!(eval_binary(Int(1), BinaryOp::Add, Bool(true)), Some(Int(2)));
+    }
+
+    #[test]
+    fn test_string_concatenation() {
+        use Value::{Char, Float, Int, String};
+
+        assert_eq!(
+            eval_binary(String("a".into()), BinaryOp::Add, String("b".into())),
+            Some(String("ab".into()))
+        );
+        assert_eq!(
+            eval_binary(String("v".into()), BinaryOp::Add, Int(2)),
+            Some(String("v2".into()))
+        );
+        assert_eq!(
+            eval_binary(Char('c'), BinaryOp::Add, String("d".into())),
+            Some(String("cd".into()))
+        );
+        assert_eq!(
+            eval_binary(Float(1.0), BinaryOp::Add, String("".into())),
+            Some(String("1.0".into()))
+        );
+        assert_eq!(eval_binary(String("a".into()), BinaryOp::Sub, Int(1)), None);
+    }
+
+    #[test]
+    fn test_display() {
+        // (1 + 2) * 3 keeps its parentheses, 1 + 2 * 3 needs none
+        let sum = binary(int("1"), BinaryOp::Add, int("2"));
+        let expression = binary(sum.clone(), BinaryOp::Mul, int("3"));
+        assert_eq!(expression.to_string(), "(1 + 2) * 3");
+
+        let product = binary(int("2"), BinaryOp::Mul, int("3"));
+        let expression = binary(int("1"), BinaryOp::Add, product);
+        assert_eq!(expression.to_string(), "1 + 2 * 3");
+
+        // Left associative: 1 - (2 - 3) but 1 - 2 - 3
+        let difference = binary(int("2"), BinaryOp::Sub, int("3"));
+        let expression = binary(int("1"), BinaryOp::Sub, difference.clone());
+        assert_eq!(expression.to_string(), "1 - (2 - 3)");
+        let expression = binary(difference, BinaryOp::Sub, int("1"));
+        assert_eq!(expression.to_string(), "2 - 3 - 1");
+
+        let expression = Expression::Unary(UnaryOp::BitNot, Box::new(sum));
+        assert_eq!(expression.to_string(), "~(1 + 2)");
+        assert_eq!(expression.get_references(), Vec::<&str>::new());
+
+        let expression = Expression::Array(vec![
+            Expression::Reference("IFoo.A".to_string()),
+            Expression::Literal(Literal::Char("\\n".to_string())),
+            Expression::Literal(Literal::String("s".to_string())),
+        ]);
+        assert_eq!(expression.to_string(), "{IFoo.A, '\\n', \"s\"}");
+        assert_eq!(expression.get_references(), vec!["IFoo.A"]);
+    }
+}
@@ -0,0 +1,532 @@
+// Structured javadoc of an item or of a member, parsed from the text of the
+// comment (e.g.: paragraphs, lists, @param tags and {@link Foo#bar} references)
+
+use super::Key;
+
+// Formatted text of a paragraph, a list entry or a tag
+pub type Text = Vec<Inline>;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Javadoc {
+    pub blocks: Vec<Block>,
+    pub params: Vec<Tag>,
+    pub returns: Option<Text>,
+    pub throws: Vec<Tag>,
+    pub deprecated: Option<Text>,
+    pub is_hidden: bool,
+    pub see: Vec<Text>,
+    // Other block tags (e.g.: @since 12)
+    pub other_tags: Vec<Tag>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Block {
+    Paragraph(Text),
+    List(Vec<Text>),
+    // e.g.: <pre>...</pre>
+    Code(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Inline {
+    Text(String),
+    // e.g.: {@code foo} or <code>foo</code>
+    Code(String),
+    Link(Link),
+}
+
+// e.g.: {@link IFoo#bar the bar method}
+#[derive(Clone, Debug, PartialEq)]
+pub struct Link {
+    // e.g.: IFoo#bar, #bar or a.b.IFoo
+    pub target: String,
+    pub label: Option<String>,
+    // Key of the referenced item, once resolved
+    pub key: Option<Key>,
+}
+
+// Tag with a name (e.g.: @param name text, or @throws Exception text)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tag {
+    pub name: String,
+    pub text: Text,
+}
+
+impl Javadoc {
+    pub fn parse(docu: &str) -> Self {
+        let mut javadoc = Javadoc::default();
+        let mut description = String::new();
+        let mut current_tag: Option<(String, String)> = None;
+        let mut in_code_block = false;
+
+        for line in docu.lines() {
+            let trimmed = line.trim_start();
+
+            // Annotations in code blocks are not block tags
+            if trimmed.starts_with('@') && !in_code_block {
+                if let Some((name, text)) = current_tag.take() {
+                    javadoc.add_tag(&name, &text);
+                }
+                let (name, text) = split_word(&trimmed[1..]);
+                current_tag = Some((name.to_string(), text.to_string()));
+                continue;
+            }
+
+            in_code_block = update_code_block(in_code_block, line);
+            let text = match current_tag {
+                Some((_, ref mut text)) => text,
+                None => &mut description,
+            };
+            text.push('\n');
+            text.push_str(line);
+        }
+
+        if let Some((name, text)) = current_tag {
+            javadoc.add_tag(&name, &text);
+        }
+
+        if description.contains("{@hide}") {
+            javadoc.is_hidden = true;
+            description = description.replace("{@hide}", "");
+        }
+        javadoc.blocks = parse_blocks(&description);
+
+        javadoc
+    }
+
+    // Set the key of the item referenced by each link, as found by the given
+    // function from the target (e.g.: the key of IFoo for IFoo#bar)
+    pub fn resolve_links<F: Fn(&str) -> Option<Key>>(&mut self, find_key: F) {
+        let block_texts = self.blocks.iter_mut().flat_map(|b| match b {
+            Block::Paragraph(text) => vec![text],
+            Block::List(entries) => entries.iter_mut().collect(),
+            Block::Code(_) => Vec::new(),
+        });
+        let tag_texts = self
+            .params
+            .iter_mut()
+            .chain(self.throws.iter_mut())
+            .chain(self.other_tags.iter_mut())
+            .map(|t| &mut t.text);
+        let texts = block_texts
+            .chain(tag_texts)
+            .chain(self.returns.iter_mut())
+            .chain(self.deprecated.iter_mut())
+            .chain(self.see.iter_mut());
+
+        for inline in texts.flatten() {
+            if let Inline::Link(link) = inline {
+                link.key = find_key(&link.target);
+            }
+        }
+    }
+
+    fn add_tag(&mut self, name: &str, text: &str) {
+        match name {
+            "param" | "throws" | "exception" => {
+                let (tag_name, text) = split_word(text.trim_start());
+                let tag = Tag {
+                    name: tag_name.to_string(),
+                    text: parse_inlines(text),
+                };
+                if name == "param" {
+                    self.params.push(tag);
+                } else {
+                    self.throws.push(tag);
+                }
+            }
+            "return" | "returns" => self.returns = Some(parse_inlines(text)),
+            "deprecated" => self.deprecated = Some(parse_inlines(text)),
+            "hide" => self.is_hidden = true,
+            "see" => self.see.push(parse_see(text.trim())),
+            _ => self.other_tags.push(Tag {
+                name: name.to_string(),
+                text: parse_inlines(text),
+            }),
+        }
+    }
+}
+
+// Examples:
+// - @see IFoo#bar the bar method
+// - @see "The AIDL documentation"
+// - @see <a href="https://source.android.com">AOSP</a>
+fn parse_see(text: &str) -> Text {
+    if text.starts_with('"') || text.starts_with('<') {
+        return parse_inlines(text.trim_matches('"'));
+    }
+
+    let (target, label) = split_word(text);
+    vec![Inline::Link(Link {
+        target: target.to_string(),
+        label: Some(label.trim())
+            .filter(|l| !l.is_empty())
+            .map(str::to_string),
+        key: None,
+    })]
+}
+
+// Paragraphs are separated by blank lines or by <p>, lists are either HTML
+// lists or lines starting with "- "
+fn parse_blocks(description: &str) -> Vec<Block> {
+    let mut builder = BlockBuilder::default();
+    let mut rest = description;
+    let mut at_line_start = true;
+
+    while let Some(c) = rest.chars().next() {
+        if at_line_start {
+            let line = rest.split('\n').next().unwrap_or_default();
+            let trimmed = line.trim_start();
+
+            if trimmed.is_empty() {
+                builder.end_block();
+            } else if trimmed.starts_with("- ") {
+                builder.start_dash_entry();
+                rest = &rest[line.len() - trimmed.len() + 2..];
+                at_line_start = false;
+                continue;
+            }
+        }
+
+        if let Some(after) = strip_html_tag(rest, "pre") {
+            builder.end_block();
+            let end = find_html_tag(after, "/pre").unwrap_or(after.len());
+            builder
+                .blocks
+                .push(Block::Code(parse_code_block(&after[..end])));
+            rest = strip_html_tag(&after[end..], "/pre").unwrap_or("");
+        } else if let Some(after) = strip_html_tag(rest, "p").or_else(|| strip_html_tag(rest, "/p"))
+        {
+            builder.end_block();
+            rest = after;
+        } else if let Some(after) =
+            strip_html_tag(rest, "ul").or_else(|| strip_html_tag(rest, "ol"))
+        {
+            builder.end_block();
+            builder.list = Some(Vec::new());
+            rest = after;
+        } else if let Some(after) =
+            strip_html_tag(rest, "/ul").or_else(|| strip_html_tag(rest, "/ol"))
+        {
+            builder.end_list();
+            rest = after;
+        } else if let Some(after) =
+            strip_html_tag(rest, "li").or_else(|| strip_html_tag(rest, "/li"))
+        {
+            builder.end_paragraph();
+            rest = after;
+        } else {
+            builder.paragraph.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+
+        at_line_start = c == '\n';
+    }
+    builder.end_list();
+
+    builder.blocks
+}
+
+#[derive(Default)]
+struct BlockBuilder {
+    blocks: Vec<Block>,
+    // Text of the current paragraph or list entry
+    paragraph: String,
+    list: Option<Vec<Text>>,
+    is_dash_list: bool,
+}
+
+impl BlockBuilder {
+    fn end_paragraph(&mut self) {
+        let text = parse_inlines(&self.paragraph);
+        self.paragraph.clear();
+        if text.is_empty() {
+            return;
+        }
+
+        match self.list {
+            Some(ref mut entries) => entries.push(text),
+            None => self.blocks.push(Block::Paragraph(text)),
+        }
+    }
+
+    fn end_list(&mut self) {
+        self.end_paragraph();
+        if let Some(entries) = self.list.take() {
+            if !entries.is_empty() {
+                self.blocks.push(Block::List(entries));
+            }
+        }
+        self.is_dash_list = false;
+    }
+
+    fn start_dash_entry(&mut self) {
+        if self.list.is_some() && !self.is_dash_list {
+            // Inside an HTML list
+            return;
+        }
+
+        self.end_paragraph();
+        if !self.is_dash_list {
+            self.list = Some(Vec::new());
+            self.is_dash_list = true;
+        }
+    }
+
+    // End of a paragraph, or of a list of lines starting with "- "
+    fn end_block(&mut self) {
+        if self.is_dash_list {
+            self.end_list();
+        } else {
+            self.end_paragraph();
+        }
+    }
+}
+
+// Text of a code block without the surrounding blank lines (e.g.:
+// <pre>{@code ...}</pre>)
+fn parse_code_block(text: &str) -> String {
+    let trimmed = text.trim();
+    let code = trimmed
+        .strip_prefix("{@code")
+        .and_then(|t| t.strip_suffix('}'))
+        .unwrap_or(trimmed);
+
+    decode_entities(code.trim_matches('\n'))
+}
+
+fn parse_inlines(text: &str) -> Text {
+    let mut inlines = Vec::new();
+    let mut current_text = String::new();
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        if rest.starts_with("{@") {
+            let end = find_closing_brace(rest);
+            let (name, content) = split_word(&rest[2..end]);
+            let content = content.trim();
+
+            match name {
+                "link" | "linkplain" => {
+                    let (target, label) = split_word(content);
+                    push_text(&mut inlines, &mut current_text);
+                    inlines.push(Inline::Link(Link {
+                        target: target.to_string(),
+                        label: Some(label.trim())
+                            .filter(|l| !l.is_empty())
+                            .map(str::to_string),
+                        key: None,
+                    }));
+                }
+                "literal" => current_text.push_str(content),
+                _ => {
+                    // e.g.: {@code foo} or {@value FOO}
+                    push_text(&mut inlines, &mut current_text);
+                    inlines.push(Inline::Code(content.to_string()));
+                }
+            }
+            rest = rest.get(end + 1..).unwrap_or("");
+        } else if let Some(after) = strip_html_tag(rest, "code") {
+            let end = find_html_tag(after, "/code").unwrap_or(after.len());
+            push_text(&mut inlines, &mut current_text);
+            inlines.push(Inline::Code(decode_entities(&after[..end])));
+            rest = strip_html_tag(&after[end..], "/code").unwrap_or("");
+        } else if let Some(after) = skip_html_tag(rest) {
+            // Other markup (e.g.: <b> or <br/>) is dropped
+            rest = after;
+        } else if c.is_whitespace() {
+            let is_leading = current_text.is_empty() && inlines.is_empty();
+            if !is_leading && !current_text.ends_with(' ') {
+                current_text.push(' ');
+            }
+            rest = rest.trim_start();
+        } else {
+            current_text.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    push_text(&mut inlines, &mut current_text);
+
+    // Spaces between two inlines are kept, not the ones around the text
+    if let Some(Inline::Text(text)) = inlines.last_mut() {
+        text.truncate(text.trim_end().len());
+    }
+    inlines.retain(|i| !matches!(i, Inline::Text(t) if t.is_empty()));
+
+    inlines
+}
+
+fn push_text(inlines: &mut Text, text: &mut String) {
+    if !text.is_empty() {
+        inlines.push(Inline::Text(decode_entities(text)));
+        text.clear();
+    }
+}
+
+// Position of the '}' closing an inline tag (e.g.: {@code {a, b}}), or the
+// end of the text if missing
+fn find_closing_brace(text: &str) -> usize {
+    let mut depth = 0;
+
+    for (i, c) in text.char_indices() {
+        if c == '{' {
+            depth += 1;
+        } else if c == '}' {
+            depth -= 1;
+            if depth == 0 {
+                return i;
+            }
+        }
+    }
+
+    text.len()
+}
+
+// Text after an HTML tag (e.g.: <li> or <P class="x">), if the text starts
+// with it
+fn strip_html_tag<'a>(text: &'a str, name: &str) -> Option<&'a str> {
+    let after_name = text
+        .strip_prefix('<')
+        .filter(|t| {
+            t.get(..name.len())
+                .is_some_and(|prefix| prefix.eq_ignore_ascii_case(name))
+        })
+        .map(|t| &t[name.len()..])?;
+
+    match after_name.chars().next() {
+        Some('>') => Some(&after_name[1..]),
+        Some(c) if c.is_whitespace() || c == '/' => {
+            after_name.find('>').map(|pos| &after_name[pos + 1..])
+        }
+        _ => None,
+    }
+}
+
+// Position of the first HTML tag with the given name (e.g.: /pre)
+fn find_html_tag(text: &str, name: &str) -> Option<usize> {
+    text.match_indices('<')
+        .map(|(pos, _)| pos)
+        .find(|pos| strip_html_tag(&text[*pos..], name).is_some())
+}
+
+// Text after any HTML tag (e.g.: <b>, </i> or <br/>)
+fn skip_html_tag(text: &str) -> Option<&str> {
+    let name = text.strip_prefix('<')?;
+    let name = name.strip_prefix('/').unwrap_or(name);
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    let end = text.find('>')?;
+    Some(&text[end + 1..])
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#64;", "@")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+fn update_code_block(in_code_block: bool, line: &str) -> bool {
+    match (find_html_tag(line, "pre"), find_html_tag(line, "/pre")) {
+        (Some(start), Some(end)) => start > end,
+        (Some(_), None) => true,
+        (None, Some(_)) => false,
+        (None, None) => in_code_block,
+    }
+}
+
+// e.g.: ("param", "name the name") for "param name the name"
+fn split_word(text: &str) -> (&str, &str) {
+    match text.find(char::is_whitespace) {
+        Some(pos) => (&text[..pos], &text[pos..]),
+        None => (text, ""),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(s: &str) -> Inline {
+        Inline::Text(s.to_string())
+    }
+
+    fn code(s: &str) -> Inline {
+        Inline::Code(s.to_string())
+    }
+
+    #[test]
+    fn test_paragraphs_and_lists() {
+        let javadoc = Javadoc::parse(
+            "Does things\nwith <b>bold</b> {@code code}.\n\nSecond &lt;paragraph&gt;<p>Third\n<ul>\n<li>one\n<li>two</li>\n</ul>\n- three\n- four\n<pre>\nint a = 1;\n@Foo\n</pre>",
+        );
+
+        assert_eq!(
+            javadoc.blocks,
+            vec![
+                Block::Paragraph(vec![
+                    text("Does things with bold "),
+                    code("code"),
+                    text("."),
+                ]),
+                Block::Paragraph(vec![text("Second <paragraph>")]),
+                Block::Paragraph(vec![text("Third")]),
+                Block::List(vec![vec![text("one")], vec![text("two")]]),
+                Block::List(vec![vec![text("three")], vec![text("four")]]),
+                Block::Code("int a = 1;\n@Foo".to_string()),
+            ]
+        );
+        assert!(javadoc.other_tags.is_empty());
+    }
+
+    #[test]
+    fn test_tags() {
+        let javadoc = Javadoc::parse(
+            "Summary {@hide}\n@param name The name,\n  on 2 lines\n@param id\n@return the {@link IFoo#bar result}\n@throws IllegalStateException when closed\n@deprecated use {@link #baz}\n@see IBar\n@since 12",
+        );
+
+        assert_eq!(
+            javadoc.blocks,
+            vec![Block::Paragraph(vec![text("Summary")])]
+        );
+        assert!(javadoc.is_hidden);
+        assert_eq!(
+            javadoc.params,
+            vec![
+                Tag {
+                    name: "name".to_string(),
+                    text: vec![text("The name, on 2 lines")],
+                },
+                Tag {
+                    name: "id".to_string(),
+                    text: Vec::new(),
+                },
+            ]
+        );
+        assert_eq!(
+            javadoc.returns,
+            Some(vec![
+                text("the "),
+                Inline::Link(Link {
+                    target: "IFoo#bar".to_string(),
+                    label: Some("result".to_string()),
+                    key: None,
+                }),
+            ])
+        );
+        assert_eq!(javadoc.throws[0].name, "IllegalStateException");
+        assert_eq!(javadoc.see.len(), 1);
+        assert_eq!(javadoc.other_tags[0].name, "since");
+
+        let mut javadoc = javadoc;
+        javadoc.resolve_links(|target| Some(format!("a.{}", target)));
+        assert!(matches!(
+            &javadoc.deprecated.unwrap()[1],
+            Inline::Link(Link { key: Some(key), .. }) if key == "a.#baz"
+        ));
+    }
+}
@@ -0,0 +1,591 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use super::{
+    Arg, Direction, Enum, Idl, Interface, Item, ItemDiagnostic, Location, Method, Model, Type,
+    TypeCategory, Value, MAX_USER_TRANSACTION_ID,
+};
+use crate::diagnostic::Severity;
+
+// Error codes of the validation diagnostics
+pub const DUPLICATE_ITEM: &str = "V001";
+pub const DUPLICATE_NAME: &str = "V002";
+pub const INVALID_ONEWAY: &str = "V003";
+pub const MISSING_DIRECTION: &str = "V004";
+pub const INVALID_DIRECTION: &str = "V005";
+pub const CONST_TYPE_MISMATCH: &str = "V006";
+pub const ENUM_VALUE_OUT_OF_RANGE: &str = "V007";
+pub const PACKAGE_MISMATCH: &str = "V008";
+pub const INVALID_BACKING_TYPE: &str = "V009";
+pub const INVALID_GENERIC_ARGS: &str = "V010";
+pub const INVALID_SUPER_INTERFACE: &str = "V011";
+pub const INVALID_TRANSACTION_CODE: &str = "V012";
+
+impl Model {
+    // Check the AIDL rules which are not enforced by the grammar (e.g.:
+    // oneway methods returning a value), sorted by item
+    pub fn validate(&self) -> Vec<ItemDiagnostic> {
+        let mut items: Vec<&Rc<Item>> = self.items.values().collect();
+        items.sort_by(|a, b| a.get_key().cmp(b.get_key()));
+
+        let mut diagnostics = Vec::new();
+        for item in items {
+            self.validate_unique_key(item, &mut diagnostics);
+            validate_unique_names(item, &mut diagnostics);
+
+            match **item {
+                Item::Interface(ref i) => {
+                    validate_interface(item, i, &mut diagnostics);
+                    self.validate_super_interfaces(item, i, &mut diagnostics);
+                }
+                Item::Enum(ref e) => validate_enum(item, e, &mut diagnostics),
+                Item::Struct(_) | Item::Union(_) | Item::OpaqueStruct(_) => (),
+            }
+
+            for m in item.get_members() {
+                validate_generic_args(item, m.index, &m.arg, &mut diagnostics);
+            }
+
+            if item.get_parent().is_none() {
+                validate_package_path(item, &mut diagnostics);
+            }
+        }
+
+        diagnostics
+    }
+
+    fn validate_unique_key(&self, item: &Item, diagnostics: &mut Vec<ItemDiagnostic>) {
+        for duplicate_item in &self.duplicate_items {
+            if duplicate_item.get_key() != item.get_key() {
+                continue;
+            }
+
+            let mut item_diagnostic = ItemDiagnostic::new(
+                item.get_key(),
+                None,
+                item.get_location(),
+                Severity::Error,
+                DUPLICATE_ITEM,
+                format!("{} is declared more than once", item.get_key()),
+            );
+            item_diagnostic.diagnostic.notes.push(format!(
+                "also declared at {}",
+                duplicate_item.get_location()
+            ));
+            diagnostics.push(item_diagnostic);
+        }
+    }
+
+    // Only interfaces can be extended, and an interface cannot inherit from
+    // itself (e.g.: IFoo extends IBar extends IFoo)
+    fn validate_super_interfaces(
+        &self,
+        item: &Item,
+        interface: &Interface,
+        diagnostics: &mut Vec<ItemDiagnostic>,
+    ) {
+        let mut error = |message: String| {
+            diagnostics.push(ItemDiagnostic::new(
+                item.get_key(),
+                None,
+                &interface.location,
+                Severity::Error,
+                INVALID_SUPER_INTERFACE,
+                message,
+            ));
+        };
+
+        for super_interface in &interface.super_interfaces {
+            let is_interface = match **super_interface {
+                Type::Item(ref i) => matches!(**i, Item::Interface(_)),
+                Type::Unresolved(_) => true, // see check_references
+                _ => false,
+            };
+
+            if !is_interface {
+                error(format!(
+                    "{} cannot extend {}, which is not an interface",
+                    interface.name, super_interface
+                ));
+            }
+        }
+
+        if self.inherits_from_itself(interface) {
+            error(format!("{} cannot inherit from itself", interface.name));
+        }
+    }
+
+    fn inherits_from_itself(&self, interface: &Interface) -> bool {
+        let mut visited = HashSet::new();
+        let mut stack = self.get_super_interfaces(interface);
+
+        while let Some(item) = stack.pop() {
+            if item.get_key() == interface.get_key() {
+                return true;
+            }
+
+            if visited.insert(item.get_key().clone()) {
+                if let Item::Interface(ref i) = *item {
+                    stack.extend(self.get_super_interfaces(i));
+                }
+            }
+        }
+
+        false
+    }
+}
+
+// Methods, consts, members and enum elements must have unique names
+fn validate_unique_names(item: &Item, diagnostics: &mut Vec<ItemDiagnostic>) {
+    let elements: Vec<Vec<(&str, usize, &Location)>> = match item {
+        Item::Interface(i) => vec![
+            i.consts
+                .iter()
+                .map(|c| (c.name.as_str(), c.index, &c.location))
+                .collect(),
+            i.methods
+                .iter()
+                .map(|m| (m.name.as_str(), m.index, &m.location))
+                .collect(),
+        ],
+        Item::Struct(s) => vec![s
+            .members
+            .iter()
+            .map(|m| (m.arg.name.as_str(), m.index, &m.location))
+            .collect()],
+        Item::Union(u) => vec![u
+            .members
+            .iter()
+            .map(|m| (m.arg.name.as_str(), m.index, &m.location))
+            .collect()],
+        Item::Enum(e) => vec![e
+            .elements
+            .iter()
+            .map(|e| (e.name.as_str(), e.index, &e.location))
+            .collect()],
+        Item::OpaqueStruct(_) => Vec::new(),
+    };
+
+    for (name, index, location) in elements.iter().flat_map(|e| find_duplicates(e)) {
+        diagnostics.push(ItemDiagnostic::new(
+            item.get_key(),
+            Some(index),
+            location,
+            Severity::Error,
+            DUPLICATE_NAME,
+            format!("{} is declared more than once in {}", name, item.get_name()),
+        ));
+    }
+}
+
+fn find_duplicates<'a>(
+    elements: &[(&'a str, usize, &'a Location)],
+) -> Vec<(&'a str, usize, &'a Location)> {
+    let mut names = HashSet::new();
+
+    elements
+        .iter()
+        .filter(|(name, _, _)| !names.insert(*name))
+        .cloned()
+        .collect()
+}
+
+fn validate_interface(item: &Item, interface: &Interface, diagnostics: &mut Vec<ItemDiagnostic>) {
+    for c in &interface.consts {
+        if let Some(ref value) = c.computed_value {
+            if !value_matches_type(value, &c.const_type) {
+                diagnostics.push(ItemDiagnostic::new(
+                    item.get_key(),
+                    Some(c.index),
+                    &c.location,
+                    Severity::Error,
+                    CONST_TYPE_MISMATCH,
+                    format!("{} is not a valid {} value", value, c.const_type),
+                ));
+            }
+        }
+    }
+
+    for m in &interface.methods {
+        validate_oneway(item, m, diagnostics);
+        validate_generic_args(item, m.index, &m.return_arg, diagnostics);
+
+        for a in &m.args {
+            // Directions are only declared in AIDL (e.g.: a HIDL method
+            // returns its results as out args)
+            if item.get_idl() == Idl::Aidl {
+                validate_direction(item, m, a, diagnostics);
+            }
+            validate_generic_args(item, m.index, a, diagnostics);
+        }
+    }
+
+    // Only AIDL methods can declare their transaction codes
+    if item.get_idl() == Idl::Aidl {
+        validate_transaction_codes(item, interface, diagnostics);
+    }
+}
+
+// Transaction codes must be unique and either all explicit or all implicit,
+// without colliding with the meta transactions (e.g.: getInterfaceVersion)
+fn validate_transaction_codes(
+    item: &Item,
+    interface: &Interface,
+    diagnostics: &mut Vec<ItemDiagnostic>,
+) {
+    let mut error = |method: &Method, message: String, note: Option<&str>| {
+        let mut item_diagnostic = ItemDiagnostic::new(
+            item.get_key(),
+            Some(method.index),
+            &method.location,
+            Severity::Error,
+            INVALID_TRANSACTION_CODE,
+            message,
+        );
+        item_diagnostic
+            .diagnostic
+            .notes
+            .extend(note.map(String::from));
+        diagnostics.push(item_diagnostic);
+    };
+
+    let explicit_count = interface
+        .methods
+        .iter()
+        .filter(|m| m.transaction_code.is_explicit())
+        .count();
+    if explicit_count > 0 && explicit_count < interface.methods.len() {
+        for m in interface
+            .methods
+            .iter()
+            .filter(|m| !m.transaction_code.is_explicit())
+        {
+            error(
+                m,
+                format!(
+                    "{} has no transaction code, but other methods of {} do",
+                    m.name, interface.name
+                ),
+                Some("either all or none of the methods must have a transaction code"),
+            );
+        }
+        return;
+    }
+
+    let mut ids = HashSet::new();
+    for m in interface
+        .methods
+        .iter()
+        .filter(|m| m.transaction_code.is_explicit())
+    {
+        let id = m.transaction_code.get_id();
+        if id > MAX_USER_TRANSACTION_ID {
+            error(
+                m,
+                format!("transaction code {} of {} is too large", id, m.name),
+                Some("getInterfaceVersion and getInterfaceHash use the last transaction codes"),
+            );
+        } else if !ids.insert(id) {
+            error(
+                m,
+                format!("transaction code {} of {} is already used", id, m.name),
+                None,
+            );
+        }
+    }
+}
+
+// Containers need their element types (e.g.: List<T> and Map<K, V>), raw
+// containers are only supported by some backends
+fn validate_generic_args(
+    item: &Item,
+    member_index: usize,
+    arg: &Arg,
+    diagnostics: &mut Vec<ItemDiagnostic>,
+) {
+    if let Type::Standard(ref st) = *arg.arg_type.get_element_type() {
+        let count = arg.generic_args.len();
+
+        let severity = if count == st.generic_arity {
+            None
+        } else if count == 0 && st.category == TypeCategory::Container {
+            Some(Severity::Warning)
+        } else {
+            Some(Severity::Error)
+        };
+
+        if let Some(severity) = severity {
+            diagnostics.push(ItemDiagnostic::new(
+                item.get_key(),
+                Some(member_index),
+                &arg.location,
+                severity,
+                INVALID_GENERIC_ARGS,
+                format!(
+                    "{} expects {} generic argument(s), found {}",
+                    st.name, st.generic_arity, count
+                ),
+            ));
+        }
+    }
+
+    for generic_arg in &arg.generic_args {
+        validate_generic_args(item, member_index, generic_arg, diagnostics);
+    }
+}
+
+// Oneway methods cannot return anything to the caller
+fn validate_oneway(item: &Item, method: &Method, diagnostics: &mut Vec<ItemDiagnostic>) {
+    if !method.is_one_way {
+        return;
+    }
+
+    let mut error = |message: String| {
+        diagnostics.push(ItemDiagnostic::new(
+            item.get_key(),
+            Some(method.index),
+            &method.location,
+            Severity::Error,
+            INVALID_ONEWAY,
+            message,
+        ));
+    };
+
+    if !method.return_arg.arg_type.is_void() {
+        error(format!(
+            "oneway method {} cannot return a value",
+            method.name
+        ));
+    }
+
+    for arg in method.args.iter().filter(|a| a.direction.is_out()) {
+        error(format!(
+            "oneway method {} cannot have an {} argument ({})",
+            method.name, arg.direction, arg.name
+        ));
+    }
+}
+
+// Only the args which can be written back by the callee (e.g.: parcelables,
+// arrays or lists) have a direction, primitives are always "in"
+fn validate_direction(
+    item: &Item,
+    method: &Method,
+    arg: &Arg,
+    diagnostics: &mut Vec<ItemDiagnostic>,
+) {
+    let can_be_out = can_be_out(&arg.arg_type);
+
+    let (severity, code, message) = match arg.direction {
+        Direction::Unspecified if can_be_out => (
+            Severity::Error,
+            MISSING_DIRECTION,
+            format!(
+                "{} ({}) must be declared as in, out or inout",
+                arg.name,
+                arg.get_full_type_name()
+            ),
+        ),
+        Direction::Out | Direction::InOut if !can_be_out => (
+            Severity::Error,
+            INVALID_DIRECTION,
+            format!(
+                "{} ({}) can only be an in argument",
+                arg.name,
+                arg.get_full_type_name()
+            ),
+        ),
+        Direction::In if is_primitive(&arg.arg_type) => (
+            Severity::Warning,
+            INVALID_DIRECTION,
+            format!(
+                "{} ({}) is a primitive, in is redundant",
+                arg.name,
+                arg.get_full_type_name()
+            ),
+        ),
+        _ => return,
+    };
+
+    diagnostics.push(ItemDiagnostic::new(
+        item.get_key(),
+        Some(method.index),
+        &arg.location,
+        severity,
+        code,
+        message,
+    ));
+}
+
+fn is_primitive(the_type: &Type) -> bool {
+    matches!(*the_type, Type::Standard(ref st) if st.is_primitive())
+}
+
+// Arrays, containers and parcelables (unresolved types are given the benefit
+// of the doubt)
+fn can_be_out(the_type: &Type) -> bool {
+    match *the_type {
+        Type::Array(_) => true,
+        Type::Standard(ref st) => match st.category {
+            TypeCategory::Container | TypeCategory::Parcelable => true,
+            // Unlike a FileDescriptor, a ParcelFileDescriptor is a parcelable
+            TypeCategory::FileDescriptor => st.name == "ParcelFileDescriptor",
+            _ => false,
+        },
+        Type::Item(ref i) => matches!(
+            **i,
+            Item::Struct(_) | Item::Union(_) | Item::OpaqueStruct(_)
+        ),
+        Type::Parameter(_) => true,
+        Type::Unresolved(_) => true,
+    }
+}
+
+// Only hexadecimal and octal literals may use the unsigned representation of
+// an integral type (e.g.: 0xff for a byte), they are already reinterpreted
+// when evaluated (see IntegralType), so decimal values such as 4294967295 for
+// an int are out of range
+fn value_matches_type(value: &Value, the_type: &Type) -> bool {
+    match (value, the_type) {
+        (Value::Array(elements), Type::Array(array_type)) => elements
+            .iter()
+            .all(|e| value_matches_type(e, &array_type.element_type)),
+        (_, Type::Standard(st)) => match (value, st.name.as_str()) {
+            (Value::Bool(_), "boolean") => true,
+            (Value::Int(i), "byte" | "int" | "long") => {
+                st.get_integral_type().is_some_and(|t| t.contains(*i))
+            }
+            (Value::Int(_), "float") | (Value::Int(_), "double") => true,
+            (Value::Float(_), "float") | (Value::Float(_), "double") => true,
+            (Value::Char(_), "char") => true,
+            (Value::String(_), "String") => true,
+            _ => false,
+        },
+        // Only standard types can be constants
+        _ => true,
+    }
+}
+
+// Enums are backed by the integral types of their IDL (int32 for protobuf
+// enums, and also string for OpenAPI enums), which must fit all the values
+fn validate_enum(item: &Item, enumeration: &Enum, diagnostics: &mut Vec<ItemDiagnostic>) {
+    let backing_type_names = get_backing_type_names(item.get_idl());
+    let backing_type = match *enumeration.backing_type {
+        Type::Standard(ref st) if backing_type_names.contains(&st.name.as_str()) => st,
+        _ => {
+            diagnostics.push(ItemDiagnostic::new(
+                item.get_key(),
+                None,
+                &enumeration.location,
+                Severity::Error,
+                INVALID_BACKING_TYPE,
+                format!(
+                    "{} cannot be the backing type of an enum, only {}",
+                    enumeration.backing_type,
+                    join_alternatives(backing_type_names)
+                ),
+            ));
+            return;
+        }
+    };
+
+    let is_string = backing_type.name == "string";
+    let integral_type = backing_type.get_integral_type();
+    for element in &enumeration.elements {
+        let (code, message) = match element.computed_value {
+            Some(Value::String(_)) if is_string => continue,
+            Some(Value::Int(value))
+                if !is_string && !integral_type.is_some_and(|t| t.contains(value)) =>
+            {
+                (
+                    ENUM_VALUE_OUT_OF_RANGE,
+                    format!(
+                        "{} = {} does not fit in the backing type {}",
+                        element.name, value, backing_type.name
+                    ),
+                )
+            }
+            Some(Value::Int(_)) if !is_string => continue,
+            None => continue,
+            Some(ref value) => (
+                CONST_TYPE_MISMATCH,
+                format!(
+                    "{} = {} is not a valid {} value",
+                    element.name, value, backing_type.name
+                ),
+            ),
+        };
+
+        diagnostics.push(ItemDiagnostic::new(
+            item.get_key(),
+            Some(element.index),
+            &element.location,
+            Severity::Error,
+            code,
+            message,
+        ));
+    }
+}
+
+fn get_backing_type_names(idl: Idl) -> &'static [&'static str] {
+    match idl {
+        Idl::Aidl => &["byte", "int", "long"],
+        Idl::Protobuf => &["int32"],
+        Idl::OpenApi => &["integer", "string"],
+        Idl::Hidl => &[
+            "int8_t", "uint8_t", "int16_t", "uint16_t", "int32_t", "uint32_t", "int64_t",
+            "uint64_t",
+        ],
+    }
+}
+
+// Example:
+// - [byte, int, long] => byte, int or long
+fn join_alternatives(names: &[&str]) -> String {
+    match names.split_last() {
+        Some((last, [])) => last.to_string(),
+        Some((last, others)) => format!("{} or {}", others.join(", "), last),
+        None => String::new(),
+    }
+}
+
+// The file of a top-level item is expected in the directory of its package
+// (e.g.: a/b/IFoo.aidl for a.b.IFoo), unlike the .proto files which can be
+// anywhere
+fn validate_package_path(item: &Item, diagnostics: &mut Vec<ItemDiagnostic>) {
+    let location = item.get_location();
+    let path = match location.path.as_ref().map(Path::new) {
+        Some(path) if path.extension().is_some_and(|ext| ext == "aidl") => path,
+        _ => return,
+    };
+    let directory = match path.parent() {
+        Some(directory) => directory,
+        None => return,
+    };
+
+    let pkg_path = &item.get_pkg().path;
+    let pkg_directory: PathBuf = pkg_path.split('.').collect();
+
+    if !pkg_path.is_empty() && !directory.ends_with(&pkg_directory) {
+        let mut item_diagnostic = ItemDiagnostic::new(
+            item.get_key(),
+            None,
+            location,
+            Severity::Error,
+            PACKAGE_MISMATCH,
+            format!(
+                "package {} does not match the directory {}",
+                pkg_path,
+                directory.display()
+            ),
+        );
+        item_diagnostic.diagnostic.notes.push(format!(
+            "expected the file in a {} directory",
+            pkg_directory.display()
+        ));
+        diagnostics.push(item_diagnostic);
+    }
+}
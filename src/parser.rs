@@ -1,2 +1,29 @@
 pub mod aidl;
 pub mod error;
+pub mod front_end;
+pub mod hidl;
+pub mod openapi;
+pub mod protobuf;
+
+use crate::diagnostic::SourceRange;
+use std::ops::Deref;
+
+// Source range of an AST node, left out when comparing the nodes in tests
+// (e.g.: a parsed node and the same one built by hand), which check the ranges
+// on their own
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NodeRange(pub SourceRange);
+
+impl PartialEq for NodeRange {
+    fn eq(&self, other: &Self) -> bool {
+        cfg!(test) || self.0 == other.0
+    }
+}
+
+impl Deref for NodeRange {
+    type Target = SourceRange;
+
+    fn deref(&self) -> &SourceRange {
+        &self.0
+    }
+}
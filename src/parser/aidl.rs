@@ -1,11 +1,18 @@
 pub mod ast;
+pub mod cst;
+mod format;
 mod parse;
 mod primitive;
 
+use crate::diagnostic::Diagnostic;
 use crate::model::{self, Model};
-pub use parse::parse;
+use crate::parser::{front_end::FrontEnd, NodeRange};
+pub use format::format;
+pub use parse::{parse, parse_with_recovery};
+// Shared with the IDLs using the same expressions and annotations (e.g.: HIDL)
+pub(crate) use parse::{parse_annotation, parse_const_expr, Span};
 use primitive::Primitive;
-use std::{collections::HashMap, rc::Rc};
+use std::rc::Rc;
 use strum::IntoEnumIterator;
 
 // Post-increment macro
@@ -21,11 +28,38 @@ macro_rules! post_inc {
     };
 }
 
+// The valid items of a file which could not be completely parsed are still
+// part of the model
+#[derive(Default)]
+pub struct AidlFrontEnd {
+    files: Vec<ast::File>,
+}
+
+impl FrontEnd for AidlFrontEnd {
+    fn get_extensions(&self) -> &[&'static str] {
+        &["aidl"]
+    }
+
+    fn parse(&mut self, path: &str, source: &str) -> Option<Vec<Diagnostic>> {
+        let (opt_file, diagnostics) = parse_with_recovery(source);
+        if let Some(mut file) = opt_file {
+            file.path = Some(path.to_string());
+            self.files.push(file);
+        }
+
+        Some(diagnostics)
+    }
+
+    fn create_model(&mut self) -> Model {
+        create_model(std::mem::take(&mut self.files))
+    }
+}
+
 pub fn create_model(files: Vec<ast::File>) -> Model {
-    let mut items = HashMap::new();
+    let mut model = Model::default();
 
     // Root package
-    let root_pkg = Rc::new(model::Package::new(""));
+    let root_pkg = Rc::new(model::Package::new("", model::Idl::Aidl));
 
     // Go through files
     files.into_iter().for_each(|file| {
@@ -33,209 +67,571 @@ pub fn create_model(files: Vec<ast::File>) -> Model {
         let imports = file.imports;
 
         // Add items
-        file.items.into_iter().for_each(|item| match item {
-            ast::Item::Interface {
-                name,
-                docu,
-                consts,
-                methods,
-                annotations: _,
-            } => {
-                let interface =
-                    create_model_interface(&pkg, &imports, &name, &consts, &methods, docu);
-
-                items.insert(
-                    interface.get_key().clone(),
-                    Rc::new(model::Item::Interface(interface)),
-                );
-            }
-            ast::Item::Parcelable {
-                name,
-                docu,
-                members,
-                annotations: _,
-            } => {
-                let strukt = create_model_struct(&pkg, &imports, &name, &members, docu);
-                items.insert(
-                    strukt.get_key().clone(),
-                    Rc::new(model::Item::Struct(strukt)),
-                );
-            }
-            ast::Item::Enum {
-                name,
-                docu,
-                elements,
-                annotations: _,
-            } => {
-                let enumeration = create_model_enum(&pkg, &imports, &name, &elements, docu);
-                items.insert(
-                    enumeration.get_key().clone(),
-                    Rc::new(model::Item::Enum(enumeration)),
-                );
-            }
-        });
+        add_model_items(
+            &mut model,
+            &pkg,
+            &imports,
+            file.path.as_deref(),
+            None,
+            file.items,
+        );
     });
 
     // Create standard types
-    let standard_types = primitive::Primitive::iter()
-        .map(|p| Primitive::get_name(&p))
-        .map(|n| model::StandardType::new(n, root_pkg.clone()))
+    let standard_types = Primitive::iter()
+        .map(|p| {
+            model::StandardType::new(
+                p.get_name(),
+                p.get_category(),
+                p.get_generic_arity(),
+                root_pkg.clone(),
+            )
+        })
         .map(|st| (st.get_key().clone(), Rc::new(st)))
         .collect();
+    model
+        .standard_types
+        .insert(model::Idl::Aidl, standard_types);
 
-    let mut model = Model {
-        items,
-        standard_types,
-    };
-
-    model.resolve_types();
+    // The types are resolved separately (see Model::resolve_types)
     model
 }
 
+fn add_model_items(
+    model: &mut Model,
+    pkg: &Rc<model::Package>,
+    imports: &[String],
+    path: Option<&str>,
+    parent: Option<&model::Key>,
+    ast_items: Vec<ast::Item>,
+) {
+    ast_items.into_iter().for_each(|item| match item {
+        ast::Item::Interface {
+            name,
+            docu,
+            consts,
+            methods,
+            nested_items,
+            annotations,
+            location,
+        } => {
+            let interface = create_model_interface(
+                pkg,
+                imports,
+                parent,
+                &name,
+                &consts,
+                &methods,
+                docu,
+                &annotations,
+                path,
+                &location,
+            );
+            let key = model.insert_item(model::Item::Interface(interface));
+            add_model_items(model, pkg, imports, path, Some(&key), nested_items);
+        }
+        ast::Item::Parcelable {
+            name,
+            type_params,
+            docu,
+            members,
+            nested_items,
+            annotations,
+            location,
+        } => {
+            let strukt = create_model_struct(
+                pkg,
+                imports,
+                parent,
+                &name,
+                type_params,
+                &members,
+                docu,
+                &annotations,
+                path,
+                &location,
+            );
+            let key = model.insert_item(model::Item::Struct(strukt));
+            add_model_items(model, pkg, imports, path, Some(&key), nested_items);
+        }
+        ast::Item::UnstructuredParcelable {
+            name,
+            docu,
+            cpp_header,
+            ndk_header,
+            rust_type,
+            annotations,
+            location,
+        } => {
+            let native_types = [
+                ("cpp_header", cpp_header),
+                ("ndk_header", ndk_header),
+                ("rust_type", rust_type),
+            ]
+            .iter()
+            .filter_map(|(kind, name)| {
+                name.as_ref()
+                    .map(|name| model::NativeType::new(*kind, name))
+            })
+            .collect();
+
+            let opaque_struct = model::OpaqueStruct::new(
+                pkg.clone(),
+                parent.cloned(),
+                name,
+                model::Javadoc::parse(&docu),
+                native_types,
+                create_model_annotations(&annotations),
+                model::Location::new(path, location.0),
+            );
+            model.insert_item(model::Item::OpaqueStruct(opaque_struct));
+        }
+        ast::Item::Enum {
+            name,
+            docu,
+            elements,
+            annotations,
+            location,
+        } => {
+            let enumeration = create_model_enum(
+                pkg,
+                imports,
+                parent,
+                &name,
+                &elements,
+                docu,
+                &annotations,
+                path,
+                &location,
+            );
+            model.insert_item(model::Item::Enum(enumeration));
+        }
+        ast::Item::Union {
+            name,
+            docu,
+            members,
+            annotations,
+            location,
+        } => {
+            let union = create_model_union(
+                pkg,
+                imports,
+                parent,
+                &name,
+                &members,
+                docu,
+                &annotations,
+                path,
+                &location,
+            );
+            model.insert_item(model::Item::Union(union));
+        }
+    });
+}
+
 fn create_model_package(pkg: &str) -> model::Package {
-    model::Package::new(pkg)
+    model::Package::new(pkg, model::Idl::Aidl)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn create_model_interface(
     pkg: &Rc<model::Package>,
     imports: &[String],
+    parent: Option<&model::Key>,
     name: &str,
     consts: &Vec<ast::Const>,
     methods: &Vec<ast::Method>,
     docu: String,
+    annotations: &[ast::Annotation],
+    path: Option<&str>,
+    location: &NodeRange,
 ) -> model::Interface {
     let mut index = 0;
+    let mut method_index = 0;
 
     model::Interface::new(
         pkg.clone(),
+        parent.cloned(),
         imports,
         name,
-        docu,
+        Vec::new(),
+        model::Javadoc::parse(&docu),
         consts
             .into_iter()
-            .map(|c| create_model_const(post_inc!(index), pkg, c))
+            .map(|c| create_model_const(post_inc!(index), pkg, path, c))
             .collect(),
         methods
             .into_iter()
-            .map(|m| create_model_method(post_inc!(index), pkg, m))
+            .map(|m| create_model_method(post_inc!(index), post_inc!(method_index), pkg, path, m))
             .collect(),
+        create_model_annotations(annotations),
+        model::Location::new(path, location.0),
     )
 }
 
+#[allow(clippy::too_many_arguments)]
 fn create_model_struct(
     pkg: &Rc<model::Package>,
     imports: &[String],
+    parent: Option<&model::Key>,
     name: &str,
+    type_params: Vec<String>,
     members: &Vec<ast::Member>,
     docu: String,
+    annotations: &[ast::Annotation],
+    path: Option<&str>,
+    location: &NodeRange,
 ) -> model::Struct {
     let mut index = 0;
 
     model::Struct::new(
         pkg.clone(),
+        parent.cloned(),
         imports,
         name,
-        docu,
+        type_params,
+        model::Javadoc::parse(&docu),
         members
             .into_iter()
-            .map(|m| create_model_member(post_inc!(index), pkg, m))
+            .map(|m| create_model_member(post_inc!(index), pkg, path, m))
             .collect(),
+        create_model_annotations(annotations),
+        model::Location::new(path, location.0),
     )
 }
 
+#[allow(clippy::too_many_arguments)]
 fn create_model_enum(
     pkg: &Rc<model::Package>,
-    _imports: &[String],
+    imports: &[String],
+    parent: Option<&model::Key>,
     name: &str,
     elements: &Vec<ast::EnumElement>,
     docu: String,
+    annotations: &[ast::Annotation],
+    path: Option<&str>,
+    location: &NodeRange,
 ) -> model::Enum {
     let mut index = 0;
 
     model::Enum::new(
         pkg.clone(),
+        parent.cloned(),
+        imports,
         name,
-        docu,
+        model::Javadoc::parse(&docu),
+        Rc::new(create_model_enum_backing_type(pkg, annotations)),
         elements
             .into_iter()
-            .map(|e| create_model_enum_element(post_inc!(index), pkg, e))
+            .map(|e| create_model_enum_element(post_inc!(index), pkg, path, e))
+            .collect(),
+        create_model_annotations(annotations),
+        model::Location::new(path, location.0),
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn create_model_union(
+    pkg: &Rc<model::Package>,
+    imports: &[String],
+    parent: Option<&model::Key>,
+    name: &str,
+    members: &Vec<ast::Member>,
+    docu: String,
+    annotations: &[ast::Annotation],
+    path: Option<&str>,
+    location: &NodeRange,
+) -> model::Union {
+    let mut index = 0;
+
+    model::Union::new(
+        pkg.clone(),
+        parent.cloned(),
+        imports,
+        name,
+        model::Javadoc::parse(&docu),
+        members
+            .into_iter()
+            .map(|m| create_model_member(post_inc!(index), pkg, path, m))
             .collect(),
+        create_model_annotations(annotations),
+        model::Location::new(path, location.0),
     )
 }
 
 fn create_model_const(
     index: usize,
     package: &Rc<model::Package>,
+    path: Option<&str>,
     the_const: &ast::Const,
 ) -> model::Const {
-    let const_type = create_model_unresolved_type(package, &the_const.const_type);
+    let const_type = create_model_type(package, &the_const.const_type);
 
     model::Const::new(
         &the_const.name,
-        Rc::new(model::Type::Unresolved(const_type)),
-        the_const.value.clone(),
+        Rc::new(const_type),
+        create_model_expression(&the_const.value),
+        None,
         index,
-        the_const.docu.clone(),
+        model::Javadoc::parse(&the_const.docu),
+        create_model_annotations(&the_const.annotations),
+        model::Location::new(path, the_const.location.0),
     )
 }
 
 fn create_model_member(
     index: usize,
     package: &Rc<model::Package>,
+    path: Option<&str>,
     member: &ast::Member,
 ) -> model::Member {
+    let location = model::Location::new(path, member.location.0);
+
     model::Member::new(
-        create_model_arg(package, &member.member_type, member.name.clone()),
+        create_model_arg(
+            package,
+            &member.member_type,
+            model::Direction::Unspecified,
+            member.name.clone(),
+            &[],
+            location.clone(),
+        ),
+        member.value.as_ref().map(create_model_expression),
+        None,
         index,
-        member.docu.clone(),
+        model::Javadoc::parse(&member.docu),
+        create_model_annotations(&member.annotations),
+        location,
     )
 }
 
+pub(crate) fn create_model_annotations(annotations: &[ast::Annotation]) -> Vec<model::Annotation> {
+    annotations
+        .iter()
+        .map(|a| {
+            model::Annotation::new(
+                &a.name,
+                a.params
+                    .iter()
+                    .map(|p| {
+                        model::AnnotationParam::new(&p.name, create_model_expression(&p.value))
+                    })
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+pub(crate) fn create_model_expression(expr: &ast::ConstExpr) -> model::Expression {
+    match expr {
+        ast::ConstExpr::Literal(literal) => {
+            model::Expression::Literal(create_model_literal(literal))
+        }
+        ast::ConstExpr::Reference(name) => model::Expression::Reference(name.clone()),
+        ast::ConstExpr::Unary(op, operand) => model::Expression::Unary(
+            create_model_unary_op(op),
+            Box::new(create_model_expression(operand)),
+        ),
+        ast::ConstExpr::Binary(lhs, op, rhs) => model::Expression::Binary(
+            Box::new(create_model_expression(lhs)),
+            create_model_binary_op(op),
+            Box::new(create_model_expression(rhs)),
+        ),
+        ast::ConstExpr::Array(elements) => {
+            model::Expression::Array(elements.iter().map(create_model_expression).collect())
+        }
+    }
+}
+
+fn create_model_literal(literal: &ast::Literal) -> model::Literal {
+    match literal {
+        ast::Literal::Bool(b) => model::Literal::Bool(*b),
+        ast::Literal::Int(i) => model::Literal::Int(i.clone()),
+        ast::Literal::Float(f) => model::Literal::Float(f.clone()),
+        ast::Literal::Char(c) => model::Literal::Char(c.clone()),
+        ast::Literal::String(s) => model::Literal::String(s.clone()),
+    }
+}
+
+fn create_model_unary_op(op: &ast::UnaryOp) -> model::UnaryOp {
+    match op {
+        ast::UnaryOp::Plus => model::UnaryOp::Plus,
+        ast::UnaryOp::Minus => model::UnaryOp::Minus,
+        ast::UnaryOp::Not => model::UnaryOp::Not,
+        ast::UnaryOp::BitNot => model::UnaryOp::BitNot,
+    }
+}
+
+fn create_model_binary_op(op: &ast::BinaryOp) -> model::BinaryOp {
+    match op {
+        ast::BinaryOp::Mul => model::BinaryOp::Mul,
+        ast::BinaryOp::Div => model::BinaryOp::Div,
+        ast::BinaryOp::Rem => model::BinaryOp::Rem,
+        ast::BinaryOp::Add => model::BinaryOp::Add,
+        ast::BinaryOp::Sub => model::BinaryOp::Sub,
+        ast::BinaryOp::Shl => model::BinaryOp::Shl,
+        ast::BinaryOp::Shr => model::BinaryOp::Shr,
+        ast::BinaryOp::Lt => model::BinaryOp::Lt,
+        ast::BinaryOp::Gt => model::BinaryOp::Gt,
+        ast::BinaryOp::Le => model::BinaryOp::Le,
+        ast::BinaryOp::Ge => model::BinaryOp::Ge,
+        ast::BinaryOp::Eq => model::BinaryOp::Eq,
+        ast::BinaryOp::Ne => model::BinaryOp::Ne,
+        ast::BinaryOp::BitAnd => model::BinaryOp::BitAnd,
+        ast::BinaryOp::BitXor => model::BinaryOp::BitXor,
+        ast::BinaryOp::BitOr => model::BinaryOp::BitOr,
+        ast::BinaryOp::And => model::BinaryOp::And,
+        ast::BinaryOp::Or => model::BinaryOp::Or,
+    }
+}
+
+// Given by @Backing(type="int"), byte by default
+fn create_model_enum_backing_type(
+    package: &Rc<model::Package>,
+    annotations: &[ast::Annotation],
+) -> model::Type {
+    let type_name = annotations
+        .iter()
+        .filter(|a| a.name == "Backing")
+        .flat_map(|a| a.params.iter())
+        .find_map(|p| match p.value {
+            ast::ConstExpr::Literal(ast::Literal::String(ref s)) if p.name == "type" => {
+                Some(s.clone())
+            }
+            _ => None,
+        })
+        .unwrap_or_else(|| "byte".to_string());
+
+    model::Type::Unresolved(model::UnresolvedType::new(package.clone(), type_name))
+}
+
 fn create_model_enum_element(
     index: usize,
     _package: &Rc<model::Package>,
+    path: Option<&str>,
     element: &ast::EnumElement,
 ) -> model::EnumElement {
     model::EnumElement {
         name: element.name.clone(),
-        value: element.value.clone(),
+        value: element.value.as_ref().map(create_model_expression),
+        computed_value: None,
         index,
-        docu: element.docu.clone(),
+        docu: model::Javadoc::parse(&element.docu),
+        location: model::Location::new(path, element.location.0),
     }
 }
 
 fn create_model_method(
     index: usize,
+    method_index: u32,
     package: &Rc<model::Package>,
+    path: Option<&str>,
     method: &ast::Method,
 ) -> model::Method {
-    let return_arg = create_model_arg(package, &method.return_type, String::new());
+    let location = model::Location::new(path, method.location.0);
+
+    let return_arg = create_model_arg(
+        package,
+        &method.return_type,
+        model::Direction::Unspecified,
+        String::new(),
+        &[],
+        location.clone(),
+    );
     let args = method
         .args
         .iter()
-        .map(|a| create_model_arg(package, &a.arg_type, a.name.clone()))
+        .map(|a| {
+            create_model_arg(
+                package,
+                &a.arg_type,
+                create_model_direction(&a.direction),
+                a.name.clone(),
+                &a.annotations,
+                model::Location::new(path, a.location.0),
+            )
+        })
         .collect();
 
-    model::Method::new(&method.name, return_arg, args, index, method.docu.clone())
+    model::Method::new(
+        method.is_one_way,
+        &method.name,
+        return_arg,
+        args,
+        match method.transaction_code {
+            Some(code) => model::TransactionCode::Explicit(code),
+            None => model::TransactionCode::Implicit(method_index),
+        },
+        index,
+        model::Javadoc::parse(&method.docu),
+        create_model_annotations(&method.annotations),
+        location,
+    )
 }
 
 fn create_model_arg(
     package: &Rc<model::Package>,
     arg_type: &ast::Type,
+    direction: model::Direction,
     name: String,
+    annotations: &[ast::Annotation],
+    location: model::Location,
 ) -> model::Arg {
     let generic_args = arg_type
         .generic_types
         .iter()
-        .map(|t| create_model_arg(package, t, String::new()))
+        .map(|t| {
+            create_model_arg(
+                package,
+                t,
+                model::Direction::Unspecified,
+                String::new(),
+                &[],
+                location.clone(),
+            )
+        })
         .collect();
 
-    model::Arg {
+    model::Arg::new(
+        direction,
         name,
-        arg_type: Rc::new(model::Type::Unresolved(create_model_unresolved_type(
-            package, arg_type,
-        ))),
+        Rc::new(create_model_type(package, arg_type)),
         generic_args,
+        create_model_annotations(annotations),
+        location,
+    )
+}
+
+fn create_model_direction(direction: &ast::Direction) -> model::Direction {
+    match direction {
+        ast::Direction::In => model::Direction::In,
+        ast::Direction::Out => model::Direction::Out,
+        ast::Direction::InOut => model::Direction::InOut,
+        ast::Direction::Unspecified => model::Direction::Unspecified,
+    }
+}
+
+fn create_model_type(package: &Rc<model::Package>, arg_type: &ast::Type) -> model::Type {
+    let element_type = model::Type::Unresolved(create_model_unresolved_type(package, arg_type));
+
+    if arg_type.array_dimensions.is_empty() {
+        element_type
+    } else {
+        model::Type::Array(model::ArrayType::new(
+            Rc::new(element_type),
+            arg_type
+                .array_dimensions
+                .iter()
+                .map(create_model_array_dimension)
+                .collect(),
+        ))
+    }
+}
+
+fn create_model_array_dimension(dimension: &ast::ArrayDimension) -> model::ArrayDimension {
+    match dimension {
+        ast::ArrayDimension::Dynamic => model::ArrayDimension::Dynamic,
+        ast::ArrayDimension::Fixed(size) => model::ArrayDimension::Fixed(size.clone()),
     }
 }
 
@@ -243,6 +639,5 @@ fn create_model_unresolved_type(
     package: &Rc<model::Package>,
     arg_type: &ast::Type,
 ) -> model::UnresolvedType {
-    // TODO: deal with array (e.g.: Krumpli[])
     model::UnresolvedType::new(package.clone(), arg_type.name.clone())
 }
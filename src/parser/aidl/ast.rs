@@ -1,5 +1,9 @@
+use crate::parser::NodeRange;
+
 #[derive(Debug)]
 pub struct File {
+    // Path of the parsed file, when known
+    pub path: Option<String>,
     pub package: String,
     pub imports: Vec<String>,
     pub items: Vec<Item>,
@@ -8,6 +12,12 @@ pub struct File {
 pub enum InterfaceElement {
     Const(Const),
     Method(Method),
+    Item(Item),
+}
+
+pub enum ParcelableElement {
+    Member(Member),
+    Item(Item),
 }
 
 #[derive(Debug, PartialEq)]
@@ -17,19 +27,42 @@ pub enum Item {
         docu: String,
         consts: Vec<Const>,
         methods: Vec<Method>,
+        nested_items: Vec<Item>,
         annotations: Vec<Annotation>,
+        location: NodeRange,
     },
     Parcelable {
         name: String,
+        type_params: Vec<String>,
         docu: String,
         members: Vec<Member>,
+        nested_items: Vec<Item>,
         annotations: Vec<Annotation>,
+        location: NodeRange,
+    },
+    // Declared without members, implemented in the target languages
+    UnstructuredParcelable {
+        name: String,
+        docu: String,
+        cpp_header: Option<String>,
+        ndk_header: Option<String>,
+        rust_type: Option<String>,
+        annotations: Vec<Annotation>,
+        location: NodeRange,
     },
     Enum {
         name: String,
         docu: String,
         elements: Vec<EnumElement>,
         annotations: Vec<Annotation>,
+        location: NodeRange,
+    },
+    Union {
+        name: String,
+        docu: String,
+        members: Vec<Member>,
+        annotations: Vec<Annotation>,
+        location: NodeRange,
     },
 }
 
@@ -37,55 +70,131 @@ pub enum Item {
 pub struct Const {
     pub name: String,
     pub const_type: Type,
-    pub value: String,
+    pub value: ConstExpr,
     pub docu: String,
     pub annotations: Vec<Annotation>,
+    pub location: NodeRange,
 }
 
 impl Const {
-    pub fn new<S1: Into<String>, S2: Into<String>>(
-        name: S1,
+    pub fn new<S: Into<String>>(
+        name: S,
         const_type: Type,
-        value: S2,
+        value: ConstExpr,
         docu: String,
         annotations: Vec<Annotation>,
+        location: NodeRange,
     ) -> Self {
         Const {
             name: name.into(),
             const_type,
-            value: value.into(),
+            value,
             docu,
             annotations,
+            location,
         }
     }
 }
 
+#[derive(Debug, PartialEq)]
+pub enum ConstExpr {
+    Literal(Literal),
+    Reference(String),
+    Unary(UnaryOp, Box<ConstExpr>),
+    Binary(Box<ConstExpr>, BinaryOp, Box<ConstExpr>),
+    Array(Vec<ConstExpr>),
+}
+
+impl ConstExpr {
+    #[cfg(test)]
+    pub fn int<S: Into<String>>(value: S) -> Self {
+        ConstExpr::Literal(Literal::Int(value.into()))
+    }
+
+    #[cfg(test)]
+    pub fn string<S: Into<String>>(value: S) -> Self {
+        ConstExpr::Literal(Literal::String(value.into()))
+    }
+
+    #[cfg(test)]
+    pub fn binary(lhs: ConstExpr, op: BinaryOp, rhs: ConstExpr) -> Self {
+        ConstExpr::Binary(Box::new(lhs), op, Box::new(rhs))
+    }
+}
+
+// Numbers and chars are kept as written (e.g.: 0x1F, 12L, 1.5f, '\n')
+#[derive(Debug, PartialEq)]
+pub enum Literal {
+    Bool(bool),
+    Int(String),
+    Float(String),
+    Char(String),
+    String(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UnaryOp {
+    Plus,
+    Minus,
+    Not,
+    BitNot,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BinaryOp {
+    Mul,
+    Div,
+    Rem,
+    Add,
+    Sub,
+    Shl,
+    Shr,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+    Eq,
+    Ne,
+    BitAnd,
+    BitXor,
+    BitOr,
+    And,
+    Or,
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Method {
     pub is_one_way: bool,
     pub name: String,
     pub return_type: Type,
     pub args: Vec<Arg>,
+    pub transaction_code: Option<u32>,
     pub docu: String,
     pub annotations: Vec<Annotation>,
+    pub location: NodeRange,
 }
 
 impl Method {
+    #[allow(clippy::too_many_arguments)]
     pub fn new<S: Into<String>>(
         is_one_way: bool,
         name: S,
         return_type: Type,
         args: Vec<Arg>,
+        transaction_code: Option<u32>,
         docu: String,
         annotations: Vec<Annotation>,
+        location: NodeRange,
     ) -> Self {
         Method {
             is_one_way,
             name: name.into(),
             return_type,
             args,
+            transaction_code,
             docu,
             annotations,
+            location,
         }
     }
 }
@@ -96,6 +205,7 @@ pub struct Arg {
     pub name: String,
     pub arg_type: Type,
     pub annotations: Vec<Annotation>,
+    pub location: NodeRange,
 }
 
 impl Arg {
@@ -104,12 +214,14 @@ impl Arg {
         name: S,
         arg_type: Type,
         annotations: Vec<Annotation>,
+        location: NodeRange,
     ) -> Self {
         Arg {
             direction,
             name: name.into(),
             arg_type,
             annotations,
+            location,
         }
     }
 
@@ -125,6 +237,7 @@ impl Arg {
             name: name.into(),
             arg_type,
             annotations: Vec::new(),
+            location: NodeRange::default(),
         }
     }
 
@@ -143,12 +256,46 @@ pub enum Direction {
 }
 
 #[derive(Debug, PartialEq)]
-pub struct Annotation(pub String);
+pub struct Annotation {
+    pub name: String,
+    pub params: Vec<AnnotationParam>,
+}
+
+impl Annotation {
+    pub fn new<S: Into<String>>(name: S, params: Vec<AnnotationParam>) -> Self {
+        Annotation {
+            name: name.into(),
+            params,
+        }
+    }
+
+    #[cfg(test)]
+    pub fn with_name<S: Into<String>>(name: S) -> Self {
+        Self::new(name, Vec::new())
+    }
+}
+
+// A single unnamed parameter (e.g.: @Foo("bar")) is named "value"
+#[derive(Debug, PartialEq)]
+pub struct AnnotationParam {
+    pub name: String,
+    pub value: ConstExpr,
+}
+
+impl AnnotationParam {
+    pub fn new<S: Into<String>>(name: S, value: ConstExpr) -> Self {
+        AnnotationParam {
+            name: name.into(),
+            value,
+        }
+    }
+}
 
 #[derive(Debug, PartialEq)]
 pub struct Type {
     pub name: String,
     pub generic_types: Vec<Type>,
+    pub array_dimensions: Vec<ArrayDimension>,
 }
 
 impl Type {
@@ -156,6 +303,7 @@ impl Type {
         Type {
             name: name.into(),
             generic_types,
+            array_dimensions: Vec::new(),
         }
     }
 
@@ -163,30 +311,55 @@ impl Type {
         Type {
             name: name.into(),
             generic_types: Vec::new(),
+            array_dimensions: Vec::new(),
+        }
+    }
+
+    pub fn new_array<S: Into<String>>(
+        name: S,
+        generic_types: Vec<Type>,
+        array_dimensions: Vec<ArrayDimension>,
+    ) -> Self {
+        Type {
+            name: name.into(),
+            generic_types,
+            array_dimensions,
         }
     }
 }
 
+#[derive(Debug, PartialEq)]
+pub enum ArrayDimension {
+    Dynamic,
+    Fixed(String),
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Member {
     pub name: String,
     pub member_type: Type,
+    pub value: Option<ConstExpr>,
     pub docu: String,
     pub annotations: Vec<Annotation>,
+    pub location: NodeRange,
 }
 
 impl Member {
     pub fn new<S: Into<String>>(
         name: S,
         member_type: Type,
+        value: Option<ConstExpr>,
         docu: String,
         annotations: Vec<Annotation>,
+        location: NodeRange,
     ) -> Self {
         Member {
             name: name.into(),
             member_type,
+            value,
             docu,
             annotations,
+            location,
         }
     }
 }
@@ -194,6 +367,7 @@ impl Member {
 #[derive(Debug, PartialEq)]
 pub struct EnumElement {
     pub name: String,
-    pub value: String,
+    pub value: Option<ConstExpr>,
     pub docu: String,
+    pub location: NodeRange,
 }
@@ -0,0 +1,434 @@
+// Lossless concrete syntax tree: unlike the ast, it keeps the whitespace and
+// the comments, so that the text of the tree is the text of the source
+// (even for invalid sources).
+//
+// The tree is shallow, it only knows statements (ended by ';', or by ',' in
+// an enum body) and blocks (delimited by '{' and '}').
+
+use std::fmt::Display;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TokenKind {
+    Whitespace,
+    LineComment,
+    // Including javadoc comments
+    BlockComment,
+    Identifier,
+    // Numbers are kept as written (e.g.: 0x1F, 12L, 1.5e-3f)
+    Number,
+    String,
+    Char,
+    // Single or multi-character symbol (e.g.: ';', '<<' or '&&')
+    Symbol,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub text: String,
+}
+
+impl Token {
+    pub fn new<S: Into<String>>(kind: TokenKind, text: S) -> Self {
+        Token {
+            kind,
+            text: text.into(),
+        }
+    }
+
+    pub fn is_trivia(&self) -> bool {
+        matches!(
+            self.kind,
+            TokenKind::Whitespace | TokenKind::LineComment | TokenKind::BlockComment
+        )
+    }
+
+    pub fn is_comment(&self) -> bool {
+        matches!(self.kind, TokenKind::LineComment | TokenKind::BlockComment)
+    }
+
+    pub fn is_symbol(&self, symbol: &str) -> bool {
+        self.kind == TokenKind::Symbol && self.text == symbol
+    }
+
+    pub fn count_newlines(&self) -> usize {
+        self.text.matches('\n').count()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NodeKind {
+    File,
+    // e.g.: package a.b; or interface IFoo { ... }
+    Statement,
+    // From '{' to '}' included
+    Block,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Node {
+    pub kind: NodeKind,
+    pub children: Vec<Element>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Element {
+    Node(Node),
+    Token(Token),
+}
+
+impl Node {
+    fn new(kind: NodeKind) -> Self {
+        Node {
+            kind,
+            children: Vec::new(),
+        }
+    }
+
+    pub fn tokens(&self) -> Vec<&Token> {
+        self.children
+            .iter()
+            .flat_map(|c| match c {
+                Element::Node(n) => n.tokens(),
+                Element::Token(t) => vec![t],
+            })
+            .collect()
+    }
+
+    // Tokens of the node which are not whitespace or comments
+    pub fn significant_tokens(&self) -> Vec<&Token> {
+        self.tokens()
+            .into_iter()
+            .filter(|t| !t.is_trivia())
+            .collect()
+    }
+
+    // Block of a statement declaring an item (e.g.: interface IFoo { ... })
+    pub fn get_block(&self) -> Option<&Node> {
+        self.children.iter().find_map(|c| match c {
+            Element::Node(n) if n.kind == NodeKind::Block => Some(n),
+            _ => None,
+        })
+    }
+
+    // First keyword of a statement, after its annotations (e.g.: "enum" for
+    // @Backing(type="int") enum Foo { ... })
+    pub fn get_keyword(&self) -> Option<&str> {
+        let tokens = self.significant_tokens();
+        let mut i = 0;
+
+        while i < tokens.len() && tokens[i].is_symbol("@") {
+            // Annotation name, then optional params
+            i += 2;
+            if i < tokens.len() && tokens[i].is_symbol("(") {
+                let mut depth = 0;
+                while i < tokens.len() {
+                    if tokens[i].is_symbol("(") {
+                        depth += 1;
+                    } else if tokens[i].is_symbol(")") {
+                        depth -= 1;
+                        if depth == 0 {
+                            i += 1;
+                            break;
+                        }
+                    }
+                    i += 1;
+                }
+            }
+        }
+
+        tokens
+            .get(i)
+            .filter(|t| t.kind == TokenKind::Identifier)
+            .map(|t| t.text.as_str())
+    }
+}
+
+impl Display for Node {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.tokens()
+            .iter()
+            .try_for_each(|t| write!(f, "{}", t.text))
+    }
+}
+
+// Build the tree of a source, this never fails: the errors are left to the
+// parser of the ast
+pub fn parse(source: &str) -> Node {
+    let tokens = tokenize(source);
+    let mut tokens = tokens.into_iter().peekable();
+
+    let mut file = Node::new(NodeKind::File);
+    parse_statements(&mut tokens, &mut file, ";");
+
+    // Unbalanced '}' at the top level
+    file.children.extend(tokens.map(Element::Token));
+
+    file
+}
+
+type Tokens = std::iter::Peekable<std::vec::IntoIter<Token>>;
+
+// Add the statements to the parent until its closing '}' (not consumed)
+fn parse_statements(tokens: &mut Tokens, parent: &mut Node, terminator: &str) {
+    while let Some(token) = tokens.peek() {
+        if token.is_trivia() {
+            let token = tokens.next().unwrap();
+            parent.children.push(Element::Token(token));
+        } else if token.is_symbol("}") {
+            return;
+        } else {
+            let mut statement = parse_statement(tokens, terminator);
+
+            // e.g.: the spaces after the last element of an enum
+            let trivia_count = statement
+                .children
+                .iter()
+                .rev()
+                .take_while(|c| matches!(c, Element::Token(t) if t.is_trivia()))
+                .count();
+            let trailing_trivia = statement
+                .children
+                .split_off(statement.children.len() - trivia_count);
+
+            parent.children.push(Element::Node(statement));
+            parent.children.extend(trailing_trivia);
+        }
+    }
+}
+
+fn parse_statement(tokens: &mut Tokens, terminator: &str) -> Node {
+    let mut statement = Node::new(NodeKind::Statement);
+    let mut has_value = false;
+    let mut paren_depth = 0;
+
+    while let Some(token) = tokens.peek() {
+        // Trailing whitespace and comments belong to the parent
+        if token.is_symbol("}") || (token.is_trivia() && ends_statement(&statement)) {
+            break;
+        }
+
+        let token = tokens.next().unwrap();
+        if token.is_symbol("(") {
+            paren_depth += 1;
+        } else if token.is_symbol(")") {
+            paren_depth -= 1;
+        } else if token.is_symbol("=") && paren_depth == 0 {
+            // Not an annotation param (e.g.: @Backing(type="int"))
+            has_value = true;
+        }
+
+        // An array value (e.g.: = {1, 2}) is not a block
+        if token.is_symbol("{") && !has_value {
+            let body_terminator = match statement.get_keyword() {
+                Some("enum") => ",",
+                _ => ";",
+            };
+
+            let mut block = Node::new(NodeKind::Block);
+            block.children.push(Element::Token(token));
+            parse_statements(tokens, &mut block, body_terminator);
+            if let Some(token) = tokens.next() {
+                block.children.push(Element::Token(token)); // '}'
+            }
+            statement.children.push(Element::Node(block));
+        } else if token.is_symbol("{") {
+            statement.children.push(Element::Token(token));
+            parse_array(tokens, &mut statement);
+        } else {
+            let is_end = token.is_symbol(terminator);
+            statement.children.push(Element::Token(token));
+            if is_end {
+                break;
+            }
+        }
+    }
+
+    statement
+}
+
+// Add the tokens of an array value until its closing '}' (consumed)
+fn parse_array(tokens: &mut Tokens, statement: &mut Node) {
+    let mut depth = 1;
+
+    for token in tokens {
+        if token.is_symbol("{") {
+            depth += 1;
+        } else if token.is_symbol("}") {
+            depth -= 1;
+        }
+
+        statement.children.push(Element::Token(token));
+        if depth == 0 {
+            return;
+        }
+    }
+}
+
+// A block ends a statement if it is not followed by ';' (e.g.: the body of
+// an interface)
+fn ends_statement(statement: &Node) -> bool {
+    matches!(statement.children.last(), Some(Element::Node(n)) if n.kind == NodeKind::Block)
+}
+
+const SYMBOLS: [&str; 8] = ["<<", ">>", "<=", ">=", "==", "!=", "&&", "||"];
+
+pub fn tokenize(source: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut rest = source;
+
+    while let Some(c) = rest.chars().next() {
+        let (kind, len) = if c.is_whitespace() {
+            (
+                TokenKind::Whitespace,
+                find_end(rest, |c| !c.is_whitespace()),
+            )
+        } else if rest.starts_with("//") {
+            (TokenKind::LineComment, find_end(rest, |c| c == '\n'))
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            let len = comment.find("*/").map_or(rest.len(), |pos| pos + 4);
+            (TokenKind::BlockComment, len)
+        } else if c.is_alphabetic() || c == '_' {
+            let len = find_end(rest, |c| !(c.is_alphanumeric() || c == '_'));
+            (TokenKind::Identifier, len)
+        } else if c.is_ascii_digit() {
+            (TokenKind::Number, number_len(rest))
+        } else if c == '"' || c == '\'' {
+            let kind = if c == '"' {
+                TokenKind::String
+            } else {
+                TokenKind::Char
+            };
+            (kind, quoted_len(rest, c))
+        } else {
+            let len = SYMBOLS
+                .iter()
+                .find(|s| rest.starts_with(*s))
+                .map_or(c.len_utf8(), |s| s.len());
+            (TokenKind::Symbol, len)
+        };
+
+        tokens.push(Token::new(kind, &rest[..len]));
+        rest = &rest[len..];
+    }
+
+    tokens
+}
+
+// Length of the text until the first char matching the predicate
+fn find_end<P: Fn(char) -> bool>(text: &str, predicate: P) -> usize {
+    text.find(predicate).unwrap_or(text.len())
+}
+
+// Examples:
+// - 42, 42L, 0x2A, 255u8
+// - 1.5f, 1e-3, 2.5E+10d
+fn number_len(text: &str) -> usize {
+    let is_hex = text.starts_with("0x") || text.starts_with("0X");
+    let mut previous = '0';
+
+    for (i, c) in text.char_indices() {
+        let is_exponent_sign = (c == '+' || c == '-') && !is_hex && "eE".contains(previous);
+        if !(c.is_alphanumeric() || c == '_' || c == '.' || is_exponent_sign) {
+            return i;
+        }
+        previous = c;
+    }
+
+    text.len()
+}
+
+// Length of a string or char literal, quotes included
+fn quoted_len(text: &str, quote: char) -> usize {
+    let mut escaped = false;
+
+    for (i, c) in text.char_indices().skip(1) {
+        if c == quote && !escaped {
+            return i + 1;
+        }
+        if c == '\n' {
+            return i; // unterminated
+        }
+        escaped = c == '\\' && !escaped;
+    }
+
+    text.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lossless() {
+        let source = r#"package a.b ; // package
+import a.c.*;
+
+/** IFoo */
+@Annotation(value = "x\"y")
+interface IFoo {
+    const int[] A = { 1 << 2, 0x1F };
+    enum Bar { X = 1e-3, Y, }
+    void foo(in Map<String, List<String>> arg) = 3;
+    /* unterminated"#;
+
+        let file = parse(source);
+        assert_eq!(file.to_string(), source);
+
+        let broken_source = "}} interface { '\n \"abc";
+        assert_eq!(parse(broken_source).to_string(), broken_source);
+    }
+
+    #[test]
+    fn test_statements() {
+        let file = parse("package a; interface I { const int[] A = {1}; void f(); } // end");
+        let statements: Vec<&Node> = file
+            .children
+            .iter()
+            .filter_map(|c| match c {
+                Element::Node(n) => Some(n),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(statements.len(), 2);
+        assert_eq!(statements[0].to_string(), "package a;");
+        assert_eq!(statements[1].get_keyword(), Some("interface"));
+        assert!(matches!(
+            file.children.last(),
+            Some(Element::Token(t)) if t.kind == TokenKind::LineComment
+        ));
+
+        let block = statements[1].get_block().unwrap();
+        let members: Vec<String> = block
+            .children
+            .iter()
+            .filter_map(|c| match c {
+                Element::Node(n) => Some(n.to_string()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(members, vec!["const int[] A = {1};", "void f();"]);
+    }
+
+    #[test]
+    fn test_enum_statements() {
+        let file = parse("@Backing(type=\"int\") enum E { A = 1, B }");
+        let statement = match &file.children[0] {
+            Element::Node(n) => n,
+            _ => panic!("statement expected"),
+        };
+
+        assert_eq!(statement.get_keyword(), Some("enum"));
+        assert_eq!(
+            statement
+                .get_block()
+                .unwrap()
+                .children
+                .iter()
+                .filter(|c| matches!(c, Element::Node(_)))
+                .count(),
+            2
+        );
+    }
+}
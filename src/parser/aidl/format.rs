@@ -0,0 +1,487 @@
+// Canonical formatting of AIDL files, based on the lossless tree (see cst):
+// - 4 spaces of indentation, one statement per line
+// - a blank line around the items, at most one blank line elsewhere
+// - single spaces between tokens, except in generics, arrays and annotations
+//   (e.g.: Map<String, int[]> or @Backing(type="int"))
+// - comments are kept where they were (on their own line or at the end of
+//   the line)
+// Long lines are not wrapped.
+
+use super::cst::{self, Element, Node, NodeKind, Token, TokenKind};
+use crate::diagnostic::Diagnostic;
+
+const INDENT: &str = "    ";
+
+pub fn format(source: &str) -> Result<String, Box<Diagnostic>> {
+    // Formatting an invalid file could change its meaning
+    super::parse(source)?;
+
+    Ok(format_file(&cst::parse(source)))
+}
+
+pub fn format_file(file: &Node) -> String {
+    let mut formatter = Formatter::default();
+    let (_, entries) = collect_entries(&file.children, false);
+    formatter.format_entries(&entries, 0, true);
+
+    formatter.out
+}
+
+// Statement or comment on its own line
+enum EntryElement<'a> {
+    Statement(&'a Node),
+    Comment(&'a Token),
+}
+
+struct Entry<'a> {
+    element: EntryElement<'a>,
+    newlines_before: usize,
+    // Comments at the end of the line (e.g.: int a; // the a)
+    trailing_comments: Vec<&'a Token>,
+}
+
+// Used to add blank lines between the different kinds of statements
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Category {
+    Package,
+    Import,
+    // e.g.: parcelable Foo cpp_header "Foo.h";
+    Declaration,
+    // Item with a body (e.g.: interface IFoo { ... })
+    Item,
+    Member,
+}
+
+impl Category {
+    fn of_statement(statement: &Node) -> Self {
+        match statement.get_keyword() {
+            Some("package") => Category::Package,
+            Some("import") => Category::Import,
+            _ if statement.get_block().is_some() => Category::Item,
+            Some("interface") | Some("parcelable") | Some("enum") | Some("union") => {
+                Category::Declaration
+            }
+            _ => Category::Member,
+        }
+    }
+}
+
+// The comments at the end of the opening line of a block (e.g.: after '{')
+// are returned separately
+fn collect_entries<'a>(
+    children: &'a [Element],
+    has_opening: bool,
+) -> (Vec<&'a Token>, Vec<Entry<'a>>) {
+    let mut opening_comments = Vec::new();
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut newlines = 0;
+
+    for child in children {
+        match child {
+            Element::Token(t) if t.kind == TokenKind::Whitespace => {
+                newlines += t.count_newlines();
+            }
+            Element::Token(t) if t.is_comment() => {
+                if newlines == 0 && !entries.is_empty() {
+                    entries.last_mut().unwrap().trailing_comments.push(t);
+                } else if newlines == 0 && has_opening {
+                    opening_comments.push(t);
+                } else {
+                    entries.push(Entry {
+                        element: EntryElement::Comment(t),
+                        newlines_before: newlines,
+                        trailing_comments: Vec::new(),
+                    });
+                    newlines = 0;
+                }
+            }
+            Element::Node(n) => {
+                entries.push(Entry {
+                    element: EntryElement::Statement(n),
+                    newlines_before: newlines,
+                    trailing_comments: Vec::new(),
+                });
+                newlines = 0;
+            }
+            // Only valid files are formatted
+            Element::Token(_) => (),
+        }
+    }
+
+    (opening_comments, entries)
+}
+
+#[derive(Default)]
+struct Formatter {
+    out: String,
+}
+
+impl Formatter {
+    fn format_entries(&mut self, entries: &[Entry], indent: usize, is_file: bool) {
+        // A comment right before a statement is part of it (e.g.: javadoc)
+        let attached: Vec<bool> = (0..entries.len())
+            .map(|i| {
+                matches!(entries[i].element, EntryElement::Comment(_))
+                    && entries.get(i + 1).is_some_and(|e| e.newlines_before <= 1)
+            })
+            .collect();
+
+        let mut categories = vec![None; entries.len()];
+        for i in (0..entries.len()).rev() {
+            categories[i] = match entries[i].element {
+                EntryElement::Statement(s) => Some(Category::of_statement(s)),
+                EntryElement::Comment(_) if attached[i] => categories[i + 1],
+                EntryElement::Comment(_) => None,
+            };
+        }
+
+        for (i, entry) in entries.iter().enumerate() {
+            if i > 0 {
+                let is_group_start = !attached[i - 1];
+                let blank_line = entry.newlines_before >= 2
+                    || (is_group_start
+                        && needs_blank_line(categories[i - 1], categories[i], is_file));
+                if blank_line {
+                    self.out.push('\n');
+                }
+            }
+
+            self.write_indent(indent);
+            match entry.element {
+                EntryElement::Statement(s) => self.format_statement(s, indent),
+                EntryElement::Comment(c) => self.format_comment(c, indent),
+            }
+            for c in &entry.trailing_comments {
+                self.out.push(' ');
+                self.out.push_str(c.text.trim_end());
+            }
+            self.out.push('\n');
+        }
+    }
+
+    fn format_statement(&mut self, statement: &Node, indent: usize) {
+        let mut spacer = Spacer::default();
+        let mut line_start = true;
+        let mut after_comment = false;
+
+        for child in &statement.children {
+            match child {
+                // Annotations stay on their own line (e.g.: @VintfStability)
+                Element::Token(t) if t.kind == TokenKind::Whitespace => {
+                    if spacer.ends_annotation() && t.count_newlines() > 0 && !line_start {
+                        self.out.push('\n');
+                        self.write_indent(indent);
+                        line_start = true;
+                    }
+                }
+                Element::Token(t) if t.kind == TokenKind::LineComment => {
+                    if !line_start {
+                        self.out.push(' ');
+                    }
+                    self.out.push_str(t.text.trim_end());
+                    self.out.push('\n');
+                    self.write_indent(indent + 2);
+                    line_start = true;
+                }
+                Element::Token(t) if t.kind == TokenKind::BlockComment => {
+                    if !line_start {
+                        self.out.push(' ');
+                    }
+                    self.out.push_str(&t.text);
+                    line_start = false;
+                    after_comment = true;
+                }
+                Element::Token(t) => {
+                    // e.g.: f(int a /* x */, int b)
+                    let closes = [",", ";", ")"].iter().any(|s| t.is_symbol(s));
+                    let space = spacer.space_before(t) || (after_comment && !closes);
+                    if !line_start && space {
+                        self.out.push(' ');
+                    }
+                    spacer.advance(t);
+                    self.out.push_str(&t.text);
+                    line_start = false;
+                    after_comment = false;
+                }
+                Element::Node(block) => {
+                    if !line_start {
+                        self.out.push(' ');
+                    }
+                    self.format_block(block, indent);
+                    line_start = false;
+                }
+            }
+        }
+    }
+
+    fn format_block(&mut self, block: &Node, indent: usize) {
+        debug_assert_eq!(block.kind, NodeKind::Block);
+
+        // Without '{' and '}'
+        let inner = &block.children[1..block.children.len() - 1];
+        let (opening_comments, entries) = collect_entries(inner, true);
+
+        self.out.push('{');
+        for c in &opening_comments {
+            self.out.push(' ');
+            self.out.push_str(c.text.trim_end());
+        }
+
+        if entries.is_empty() && opening_comments.is_empty() {
+            self.out.push('}');
+            return;
+        }
+
+        self.out.push('\n');
+        self.format_entries(&entries, indent + 1, false);
+        self.write_indent(indent);
+        self.out.push('}');
+    }
+
+    // The lines of javadoc comments are aligned on the first one
+    fn format_comment(&mut self, comment: &Token, indent: usize) {
+        for (i, line) in comment.text.trim_end().lines().enumerate() {
+            if i == 0 {
+                self.out.push_str(line.trim_end());
+                continue;
+            }
+
+            self.out.push('\n');
+            let trimmed_line = line.trim();
+            if trimmed_line.starts_with('*') {
+                self.write_indent(indent);
+                self.out.push(' ');
+                self.out.push_str(trimmed_line);
+            } else {
+                self.out.push_str(line.trim_end());
+            }
+        }
+    }
+
+    fn write_indent(&mut self, indent: usize) {
+        for _ in 0..indent {
+            self.out.push_str(INDENT);
+        }
+    }
+}
+
+// Blank lines between the groups of statements, and around the items
+fn needs_blank_line(previous: Option<Category>, current: Option<Category>, is_file: bool) -> bool {
+    match (previous, current) {
+        (Some(Category::Item), Some(_)) | (Some(_), Some(Category::Item)) => true,
+        (Some(p), Some(c)) => is_file && p != c,
+        _ => false,
+    }
+}
+
+// Decides where the spaces go between the tokens of a statement
+#[derive(Default)]
+struct Spacer<'a> {
+    previous: Option<&'a Token>,
+    previous_is_unary: bool,
+    previous_opens_generic: bool,
+    // e.g.: @Backing(
+    previous_is_annotation_name: bool,
+    previous_closes_annotation: bool,
+    annotation_depth: usize,
+    generic_depth: usize,
+    // After '=' (e.g.: const value or default value)
+    has_value: bool,
+}
+
+impl<'a> Spacer<'a> {
+    fn space_before(&self, token: &Token) -> bool {
+        let previous = match self.previous {
+            Some(previous) => previous,
+            None => return false,
+        };
+
+        // e.g.: @JavaDerive(toString=true)
+        if self.annotation_depth > 0 && (token.is_symbol("=") || previous.is_symbol("=")) {
+            return false;
+        }
+
+        let glued_to_previous = ["@", "(", "[", ".", "{"]
+            .iter()
+            .any(|s| previous.is_symbol(s))
+            || self.previous_is_unary
+            || self.previous_opens_generic;
+        let glued_to_next = [",", ";", ")", "]", ".", "[", "}"]
+            .iter()
+            .any(|s| token.is_symbol(s))
+            || self.opens_generic(token)
+            || self.closes_generic(token)
+            || (token.is_symbol("(") && previous.kind == TokenKind::Identifier);
+
+        !(glued_to_previous || glued_to_next)
+    }
+
+    fn advance(&mut self, token: &'a Token) {
+        self.previous_is_unary = ["-", "+", "~", "!"].iter().any(|s| token.is_symbol(s))
+            && self.previous.is_none_or(|p| {
+                p.kind == TokenKind::Symbol && ![")", "]", "}"].iter().any(|s| p.is_symbol(s))
+            });
+        self.previous_opens_generic = self.opens_generic(token);
+
+        if self.previous_opens_generic {
+            self.generic_depth += 1;
+        } else if self.closes_generic(token) {
+            self.generic_depth = self.generic_depth.saturating_sub(token.text.len());
+        }
+
+        self.previous_closes_annotation = false;
+        if self.annotation_depth > 0 {
+            if token.is_symbol("(") {
+                self.annotation_depth += 1;
+            } else if token.is_symbol(")") {
+                self.annotation_depth -= 1;
+                self.previous_closes_annotation = self.annotation_depth == 0;
+            }
+        } else if self.previous_is_annotation_name && token.is_symbol("(") {
+            self.annotation_depth = 1;
+        } else if token.is_symbol("=") {
+            self.has_value = true;
+        }
+
+        self.previous_is_annotation_name =
+            token.kind == TokenKind::Identifier && self.previous.is_some_and(|p| p.is_symbol("@"));
+        self.previous = Some(token);
+    }
+
+    // After the name of an annotation without params, or after its ')'
+    fn ends_annotation(&self) -> bool {
+        self.previous_is_annotation_name || self.previous_closes_annotation
+    }
+
+    // Less-than operators are only found in values
+    fn opens_generic(&self, token: &Token) -> bool {
+        token.is_symbol("<") && !self.has_value && self.annotation_depth == 0
+    }
+
+    fn closes_generic(&self, token: &Token) -> bool {
+        (token.is_symbol(">") || token.is_symbol(">>")) && self.generic_depth > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format() -> Result<(), Box<dyn std::error::Error>> {
+        let source = r#"// Copyright
+package   a.b ;
+import a.c.IBar;
+import a.c.Baz ;
+/**
+     * JavaDoc of IFoo
+     */
+@Annotation(  value = "x" )
+interface IFoo{
+    const int A=1<<2 ; // the A
+    const int [ ] B = { 1 , -2 };
+
+
+
+    oneway void foo ( in Map < String,List<String >> arg , out int [ ] result)=3;
+    /** Docu of Nested */
+    parcelable Nested {}
+    @Backing(type = "byte") enum E { X = 1 , Y,
+        // Z comment
+        Z }
+}
+"#;
+
+        let expected = r#"// Copyright
+package a.b;
+
+import a.c.IBar;
+import a.c.Baz;
+
+/**
+ * JavaDoc of IFoo
+ */
+@Annotation(value="x")
+interface IFoo {
+    const int A = 1 << 2; // the A
+    const int[] B = {1, -2};
+
+    oneway void foo(in Map<String, List<String>> arg, out int[] result) = 3;
+
+    /** Docu of Nested */
+    parcelable Nested {}
+
+    @Backing(type="byte") enum E {
+        X = 1,
+        Y,
+        // Z comment
+        Z
+    }
+}
+"#;
+
+        let formatted = format(source)?;
+        assert_eq!(formatted, expected);
+        assert_eq!(format(&formatted)?, formatted);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_keeps_tokens() -> Result<(), Box<dyn std::error::Error>> {
+        let source =
+            "package a;\nparcelable P cpp_header \"p.h\";\nparcelable Q { int a = 1 > 2 ? 1 : 0; }";
+        let significant_texts = |text: &str| -> Vec<String> {
+            cst::tokenize(text)
+                .into_iter()
+                .filter(|t| !t.is_trivia())
+                .map(|t| t.text)
+                .collect()
+        };
+
+        // Not a valid file (the ternary operator is not supported)
+        assert!(format(source).is_err());
+
+        let source =
+            "package a;\nparcelable P cpp_header \"p.h\";\nparcelable Q { int a = 1 > 2; }";
+        let formatted = format(source)?;
+        assert_eq!(significant_texts(&formatted), significant_texts(source));
+        assert_eq!(
+            formatted,
+            "package a;\n\nparcelable P cpp_header \"p.h\";\n\nparcelable Q {\n    int a = 1 > 2;\n}\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_trailing_comments() -> Result<(), Box<dyn std::error::Error>> {
+        // At the end of the file
+        let source = "package a;\ninterface I { void f(); }\n// end\n";
+        assert_eq!(
+            format(source)?,
+            "package a;\n\ninterface I {\n    void f();\n}\n// end\n"
+        );
+
+        // At the end of a body
+        let source = "package a;\nenum E { A, B,\n // c\n}";
+        assert_eq!(
+            format(source)?,
+            "package a;\n\nenum E {\n    A,\n    B,\n    // c\n}\n"
+        );
+        let source = "package a;\nparcelable P { int a; // the a\n /* end */ }\nunion U { int a;\n// end\n}\n";
+        assert_eq!(
+            format(source)?,
+            "package a;\n\nparcelable P {\n    int a; // the a\n    /* end */\n}\n\nunion U {\n    int a;\n    // end\n}\n"
+        );
+
+        // Inside an arg list
+        let source = "package a;\ninterface I { void f(in int a /* x */, int b); }\n";
+        assert_eq!(
+            format(source)?,
+            "package a;\n\ninterface I {\n    void f(in int a /* x */, int b);\n}\n"
+        );
+
+        Ok(())
+    }
+}
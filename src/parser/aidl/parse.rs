@@ -2,36 +2,78 @@ use std::cell::RefCell;
 
 use nom::{
     branch::alt,
-    bytes::complete::{is_not, tag, take_until},
+    bytes::complete::{is_not, tag, tag_no_case, take_until},
     character::complete::{
-        alpha1, alphanumeric1, char, digit0, digit1, line_ending, multispace0, multispace1,
-        not_line_ending, space0,
+        alpha1, alphanumeric1, anychar, char, digit0, digit1, hex_digit1, line_ending, multispace0,
+        multispace1, none_of, not_line_ending, one_of, space0,
     },
-    combinator::{all_consuming, cut, map, opt, recognize},
+    combinator::{all_consuming, cut, eof, map, map_res, not, opt, recognize, value},
+    error::context,
     multi::{many0, separated_list0, separated_list1},
     sequence::{delimited, pair, preceded, terminated, tuple},
-    IResult,
+    Slice,
 };
 use nom_locate::LocatedSpan;
 
+use crate::diagnostic::{Diagnostic, SourcePosition, SourceRange};
 use crate::parser::aidl::ast::{
-    Annotation, Arg, Const, Direction, EnumElement, File, InterfaceElement, Item, Member, Method,
-    Type,
+    Annotation, AnnotationParam, Arg, ArrayDimension, BinaryOp, Const, ConstExpr, Direction,
+    EnumElement, File, InterfaceElement, Item, Literal, Member, Method, ParcelableElement, Type,
+    UnaryOp,
 };
-use crate::parser::error::ParseContentError;
+use crate::parser::error::{SyntaxError, SyntaxErrorKind};
+use crate::parser::NodeRange;
 
-pub type Span<'a> = LocatedSpan<&'a str>;
+// The extra data are the errors collected while recovering (see parse_with_recovery),
+// with their offset in the input
+pub type Span<'a> = LocatedSpan<&'a str, Option<&'a RefCell<Vec<SyntaxError<usize>>>>>;
 
-pub fn parse(input: &str) -> Result<File, ParseContentError> {
-    let input = Span::new(input);
+type IResult<I, O, E = SyntaxError<I>> = nom::IResult<I, O, E>;
+
+// Span without error recovery
+fn new_span(input: &str) -> Span<'_> {
+    Span::new_extra(input, None)
+}
+
+pub fn parse(source: &str) -> Result<File, Box<Diagnostic>> {
+    let input = new_span(source);
 
     match all_consuming(parse_aidl)(input) {
         Ok((_, file)) => Ok(file),
-        Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) => Err(e.into()),
+        Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) => Err(Box::new(
+            e.map_input(|i| i.location_offset()).to_diagnostic(source),
+        )),
         Err(nom::Err::Incomplete(_)) => unreachable!(),
     }
 }
 
+// Same as parse but, instead of stopping at the first error, invalid items
+// and members are skipped (up to the next ';' or '}') and all the errors are
+// returned together with the (partial) file
+pub fn parse_with_recovery(source: &str) -> (Option<File>, Vec<Diagnostic>) {
+    let errors = RefCell::new(Vec::new());
+    let input = Span::new_extra(source, Some(&errors));
+
+    let file = match all_consuming(parse_aidl)(input) {
+        Ok((_, file)) => Some(file),
+        Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) => {
+            errors
+                .borrow_mut()
+                .push(e.map_input(|i| i.location_offset()));
+            None
+        }
+        Err(nom::Err::Incomplete(_)) => unreachable!(),
+    };
+
+    let diagnostics = errors
+        .into_inner()
+        .iter()
+        .map(|e| e.to_diagnostic(source))
+        .collect();
+
+    (file, diagnostics)
+}
+
 // AIDL file:
 // - 0+ <comment>
 // - <package>
@@ -39,7 +81,7 @@ pub fn parse(input: &str) -> Result<File, ParseContentError> {
 // - 0+ <item>
 fn parse_aidl(input: Span) -> IResult<Span, File> {
     let (input, _) = many0(ws(parse_comment))(input)?;
-    let (input, package) = ws(parse_package)(input)?;
+    let (input, package) = ws(context("package declaration", parse_package))(input)?;
 
     fn find_imports(
         input: Span,
@@ -47,7 +89,7 @@ fn parse_aidl(input: Span) -> IResult<Span, File> {
     ) -> IResult<Span, RefCell<Vec<String>>> {
         let (try_input, t) = tuple((
             opt(ws(parse_comment)),
-            opt(ws(parse_import)),
+            opt(ws(context("import", parse_import))),
             opt(ws(parse_forward_declaration)),
         ))(input)?;
 
@@ -67,14 +109,19 @@ fn parse_aidl(input: Span) -> IResult<Span, File> {
     let (input, imports) = find_imports(input, RefCell::new(Vec::new()))?;
     let imports = imports.into_inner();
 
-    let (input, items) = many0(ws(parse_item))(input)?;
+    let (input, items) = many0(ws(recoverable(preceded(
+        not(pair(many0(ws(parse_comment)), eof)),
+        cut(alt((parse_item, parse_invalid_element))),
+    ))))(input)?;
+    let (input, _) = many0(ws(parse_comment))(input)?;
 
     Ok((
         input,
         File {
+            path: None,
             package,
             imports,
-            items,
+            items: items.into_iter().flatten().collect(),
         },
     ))
 }
@@ -95,23 +142,28 @@ fn parse_package_name(input: Span) -> IResult<Span, Span> {
     recognize(separated_list1(char('.'), identifier))(input)
 }
 
-// Example:
+// Examples:
 // - import <package_name>;
+// - import <package_name>.*;
 fn parse_import(input: Span) -> IResult<Span, Span> {
-    let (input, (_, pkg_name, _)) =
-        tuple((ws_plus(tag("import")), ws(parse_package_name), char(';')))(input)?;
+    let (input, (_, pkg_name, _)) = tuple((
+        ws_plus(tag("import")),
+        ws(recognize(pair(parse_package_name, opt(tag(".*"))))),
+        char(';'),
+    ))(input)?;
 
     Ok((input, pkg_name))
 }
 
-// Forward declaration (ignored)
+// Forward declaration (ignored, see parse_unstructured_parcelable for
+// parcelables)
 // Examples:
 // - interface InterfaceName;
-// - parcelable ParcelableName;
-// - enum ParcelableName;
+// - enum EnumName;
+// - union UnionName;
 fn parse_forward_declaration(input: Span) -> IResult<Span, ()> {
     let (input, _) = recognize(tuple((
-        ws_plus(alt((tag("interface"), tag("parcelable"), tag("enum")))),
+        ws_plus(alt((tag("interface"), tag("enum"), tag("union")))),
         ws(identifier),
         char(';'),
     )))(input)?;
@@ -123,15 +175,23 @@ fn parse_forward_declaration(input: Span) -> IResult<Span, ()> {
 // - <interface>
 // - <parcelable>
 // - <enum>
-// TODO:  enum
+// - <union>
 fn parse_item(input: Span) -> IResult<Span, Item> {
-    alt((parse_interface, parse_parcelable, parse_enum))(input)
+    alt((
+        context("interface declaration", parse_interface),
+        context("parcelable declaration", parse_unstructured_parcelable),
+        context("parcelable declaration", parse_parcelable),
+        context("enum declaration", parse_enum),
+        context("union declaration", parse_union),
+    ))(input)
 }
 
 // Examples:
 // - interface interfaceName { <const>; <method>; <method>; <const>; }
+// - interface interfaceName { <const>; <method>; <item> }
 fn parse_interface(input: Span) -> IResult<Span, Item> {
     let (input, opt_docu) = opt(extract_javadoc)(input)?;
+    let start = input;
 
     let (input, annotations) = many0(ws_plus(parse_annotation))(input)?;
     let (input, _) = ws_plus(tag("interface"))(input)?;
@@ -139,21 +199,28 @@ fn parse_interface(input: Span) -> IResult<Span, Item> {
 
     let (input, interface_element) = delimited(
         ws(char('{')),
-        many0(ws(parse_interface_element)),
-        ws(char('}')),
+        many0(ws(recoverable(parse_interface_element))),
+        pair(many0(ws(parse_comment)), ws(char('}'))),
     )(input)?;
 
     let mut consts = Vec::new();
     let mut methods = Vec::new();
-
-    interface_element.into_iter().for_each(|e| match e {
-        InterfaceElement::Const(c) => {
-            consts.push(c);
-        }
-        InterfaceElement::Method(m) => {
-            methods.push(m);
-        }
-    });
+    let mut nested_items = Vec::new();
+
+    interface_element
+        .into_iter()
+        .flatten()
+        .for_each(|e| match e {
+            InterfaceElement::Const(c) => {
+                consts.push(c);
+            }
+            InterfaceElement::Method(m) => {
+                methods.push(m);
+            }
+            InterfaceElement::Item(i) => {
+                nested_items.push(i);
+            }
+        });
 
     Ok((
         input,
@@ -162,50 +229,150 @@ fn parse_interface(input: Span) -> IResult<Span, Item> {
             docu: opt_docu.unwrap_or_default(),
             consts,
             methods,
+            nested_items,
             annotations,
+            location: location(start, input),
         },
     ))
 }
 
 // Examples:
 // - parcelable parcelableName { <member>; <member>; }
+// - parcelable parcelableName { <member>; <item> }
+// - parcelable parcelableName<A, B> { <member>; <member>; }
 fn parse_parcelable(input: Span) -> IResult<Span, Item> {
     let (input, opt_docu) = opt(extract_javadoc)(input)?;
+    let start = input;
 
     let (input, annotations) = many0(ws_plus(parse_annotation))(input)?;
     let (input, _) = ws_plus(tag("parcelable"))(input)?;
     let (input, parcelable_name) = cut(ws(identifier))(input)?;
+    let (input, opt_type_params) = opt(parse_type_params)(input)?;
 
-    let (input, members) = delimited(
+    let (input, parcelable_elements) = delimited(
         ws(char('{')),
-        many0(ws(parse_member)),
-        pair(opt(parse_comment), ws(char('}'))),
+        many0(ws(recoverable(parse_parcelable_element))),
+        pair(many0(ws(parse_comment)), ws(char('}'))),
     )(input)?;
 
+    let mut members = Vec::new();
+    let mut nested_items = Vec::new();
+
+    parcelable_elements
+        .into_iter()
+        .flatten()
+        .for_each(|e| match e {
+            ParcelableElement::Member(m) => {
+                members.push(m);
+            }
+            ParcelableElement::Item(i) => {
+                nested_items.push(i);
+            }
+        });
+
     Ok((
         input,
         Item::Parcelable {
             name: parcelable_name.to_string(),
+            type_params: opt_type_params.unwrap_or_default(),
             docu: opt_docu.unwrap_or_default(),
             members,
+            nested_items,
+            annotations,
+            location: location(start, input),
+        },
+    ))
+}
+
+// Examples:
+// - parcelable ParcelableName;
+// - parcelable ParcelableName cpp_header "a/b/Name.h" ndk_header "aidl/a/b/Name.h";
+// - parcelable ParcelableName rust_type "a::b::Name";
+fn parse_unstructured_parcelable(input: Span) -> IResult<Span, Item> {
+    let (input, opt_docu) = opt(extract_javadoc)(input)?;
+    let start = input;
+
+    let (input, annotations) = many0(ws_plus(parse_annotation))(input)?;
+    let (input, _) = ws_plus(tag("parcelable"))(input)?;
+    let (input, parcelable_name) = ws(identifier)(input)?;
+    let (input, native_types) = many0(ws(pair(
+        ws_plus(alt((
+            tag("cpp_header"),
+            tag("ndk_header"),
+            tag("rust_type"),
+        ))),
+        delimited(char('"'), take_until("\""), char('"')),
+    )))(input)?;
+    let (input, _) = char(';')(input)?;
+
+    let native_type = |name: &str| {
+        native_types
+            .iter()
+            .find(|(n, _)| **n == name)
+            .map(|(_, t)| t.to_string())
+    };
+
+    Ok((
+        input,
+        Item::UnstructuredParcelable {
+            name: parcelable_name.to_string(),
+            docu: opt_docu.unwrap_or_default(),
+            cpp_header: native_type("cpp_header"),
+            ndk_header: native_type("ndk_header"),
+            rust_type: native_type("rust_type"),
             annotations,
+            location: location(start, input),
         },
     ))
 }
 
+// Examples:
+// - <A>
+// - <A, B>
+fn parse_type_params(input: Span) -> IResult<Span, Vec<String>> {
+    let (input, type_params) = delimited(
+        ws(char('<')),
+        separated_list1(char(','), ws(identifier)),
+        ws(char('>')),
+    )(input)?;
+
+    Ok((input, type_params.iter().map(|p| p.to_string()).collect()))
+}
+
+// Examples:
+// - <member>
+// - <item>
+fn parse_parcelable_element(input: Span) -> IResult<Span, ParcelableElement> {
+    alt((
+        map(parse_item, ParcelableElement::Item),
+        map(
+            context("member declaration", parse_member),
+            ParcelableElement::Member,
+        ),
+        parse_invalid_element,
+    ))(input)
+}
+
 // Examples:
 // - enum enumName { <enum_element>, <enum_element> }
 fn parse_enum(input: Span) -> IResult<Span, Item> {
     let (input, opt_docu) = opt(extract_javadoc)(input)?;
+    let start = input;
 
     let (input, annotations) = many0(ws_plus(parse_annotation))(input)?;
     let (input, _) = ws_plus(tag("enum"))(input)?;
     let (input, enum_name) = cut(ws(identifier))(input)?;
 
-    let (input, elements) = delimited(
+    let (input, elements) = preceded(
         ws(char('{')),
-        separated_list1(char(','), ws(parse_enum_element)),
-        ws(pair(opt(ws(char(','))), char('}'))),
+        cut(terminated(
+            separated_list1(char(','), ws(parse_enum_element)),
+            ws(tuple((
+                opt(ws(char(','))),
+                many0(ws(parse_comment)),
+                char('}'),
+            ))),
+        )),
     )(input)?;
 
     Ok((
@@ -215,6 +382,38 @@ fn parse_enum(input: Span) -> IResult<Span, Item> {
             docu: opt_docu.unwrap_or_default(),
             elements,
             annotations,
+            location: location(start, input),
+        },
+    ))
+}
+
+// Examples:
+// - union unionName { <member>; <member>; }
+fn parse_union(input: Span) -> IResult<Span, Item> {
+    let (input, opt_docu) = opt(extract_javadoc)(input)?;
+    let start = input;
+
+    let (input, annotations) = many0(ws_plus(parse_annotation))(input)?;
+    let (input, _) = ws_plus(tag("union"))(input)?;
+    let (input, union_name) = cut(ws(identifier))(input)?;
+
+    let (input, members) = delimited(
+        ws(char('{')),
+        many0(ws(recoverable(alt((
+            context("member declaration", parse_member),
+            parse_invalid_element,
+        ))))),
+        pair(many0(ws(parse_comment)), ws(char('}'))),
+    )(input)?;
+
+    Ok((
+        input,
+        Item::Union {
+            name: union_name.to_string(),
+            docu: opt_docu.unwrap_or_default(),
+            members: members.into_iter().flatten().collect(),
+            annotations,
+            location: location(start, input),
         },
     ))
 }
@@ -222,40 +421,32 @@ fn parse_enum(input: Span) -> IResult<Span, Item> {
 // Examples:
 // - ELEMENT
 // - ELEMENT = 3
-// - ELEMENT = "elementValue"
+// - ELEMENT = 1 << OTHER_ELEMENT
 fn parse_enum_element(input: Span) -> IResult<Span, EnumElement> {
     let (input, opt_docu) = opt(extract_javadoc)(input)?;
+    let start = input;
 
-    let (input, (name, opt_value)) = tuple((
-        identifier,
-        opt(preceded(ws(char('=')), parse_enum_element_value)),
-    ))(input)?;
+    let (input, (name, value)) =
+        tuple((identifier, opt(preceded(ws(char('=')), parse_const_expr))))(input)?;
 
     Ok((
         input,
         EnumElement {
             name: name.to_string(),
-            value: opt_value
-                .and_then(|v| Some(v.to_string()))
-                .unwrap_or_default(),
+            value,
             docu: opt_docu.unwrap_or_default(),
+            location: location(start, input),
         },
     ))
 }
 
-fn parse_enum_element_value(input: Span) -> IResult<Span, Span> {
-    alt((
-        recognize(pair(opt(char('-')), digit1)),
-        delimited(char('"'), take_until("\""), char('"')),
-    ))(input)
-}
-
 // Examples:
 // - <const>
 // - <method>
+// - <item>
 fn parse_interface_element(input: Span) -> IResult<Span, InterfaceElement> {
     // Const
-    match parse_const(input) {
+    match context("constant declaration", parse_const)(input) {
         Err(nom::Err::Failure(e)) => return Err(nom::Err::Failure(e)),
         Err(nom::Err::Error(_)) => (),
         Err(nom::Err::Incomplete(_)) => unreachable!(),
@@ -265,7 +456,7 @@ fn parse_interface_element(input: Span) -> IResult<Span, InterfaceElement> {
     }
 
     // Interface
-    match parse_method(input) {
+    match context("method declaration", parse_method)(input) {
         Err(nom::Err::Failure(e)) => return Err(nom::Err::Failure(e)),
         Err(nom::Err::Error(_)) => (),
         Err(nom::Err::Incomplete(_)) => unreachable!(),
@@ -274,79 +465,371 @@ fn parse_interface_element(input: Span) -> IResult<Span, InterfaceElement> {
         }
     }
 
+    // Nested item
+    match parse_item(input) {
+        Err(nom::Err::Failure(e)) => return Err(nom::Err::Failure(e)),
+        Err(nom::Err::Error(_)) => (),
+        Err(nom::Err::Incomplete(_)) => unreachable!(),
+        Ok((input, i)) => {
+            return Ok((input, InterfaceElement::Item(i)));
+        }
+    }
+
+    parse_invalid_element(input)
+}
+
+// Anything else than the end of the parent ('}') => failure
+fn parse_invalid_element<O>(input: Span) -> IResult<Span, O> {
     // (ignored) comments
     let (input, _) = many0(ws(parse_comment))(input)?;
 
     // Invalid input (up to the next ';' or '}') => failure
     let (_, input) = is_not("};")(input)?;
-    Err(nom::Err::Failure(nom::error::make_error(
+    Err(nom::Err::Failure(SyntaxError::new(
         input,
-        nom::error::ErrorKind::Many1,
+        SyntaxErrorKind::UnexpectedInput,
     )))
 }
 
+// When recovering (see parse_with_recovery), a failure is recorded and the
+// invalid input skipped up to the end of the current item/member (i.e. the
+// next ';' or '}' at the same level), otherwise the failure is returned
+fn recoverable<'a, O, F>(mut parser: F) -> impl FnMut(Span<'a>) -> IResult<Span<'a>, Option<O>>
+where
+    F: FnMut(Span<'a>) -> IResult<Span<'a>, O>,
+{
+    move |input: Span<'a>| match parser(input) {
+        Ok((rest, output)) => Ok((rest, Some(output))),
+        Err(nom::Err::Failure(e)) if input.extra.is_some() => match skip_invalid_input(input) {
+            Some(rest) => {
+                report_failure(e)?;
+                Ok((rest, None))
+            }
+            None => Err(nom::Err::Failure(e)),
+        },
+        Err(e) => Err(e),
+    }
+}
+
+// Record the failure when recovering, otherwise return it
+fn report_failure(error: SyntaxError<Span>) -> Result<(), nom::Err<SyntaxError<Span>>> {
+    match error.input.extra {
+        Some(errors) => {
+            errors
+                .borrow_mut()
+                .push(error.map_input(|i| i.location_offset()));
+            Ok(())
+        }
+        None => Err(nom::Err::Failure(error)),
+    }
+}
+
+// Skip up to (including) the next ';' or balanced '}', stopping before a
+// '}' closing the parent
+// Returns None if nothing can be skipped
+fn skip_invalid_input(input: Span) -> Option<Span> {
+    let mut depth = 0;
+    let mut end = input.len();
+
+    for (pos, c) in input.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' if depth == 0 => {
+                // A '}' at the very beginning cannot belong to a parent
+                end = if pos == 0 { 1 } else { pos };
+                break;
+            }
+            '}' if depth == 1 => {
+                end = pos + 1;
+                break;
+            }
+            '}' => depth -= 1,
+            ';' if depth == 0 => {
+                end = pos + 1;
+                break;
+            }
+            _ => (),
+        }
+    }
+
+    if end == 0 {
+        None
+    } else {
+        Some(input.slice(end..))
+    }
+}
+
 // Examples:
 // - const Type constName = value
 fn parse_const(input: Span) -> IResult<Span, Const> {
     let (input, opt_docu) = opt(extract_javadoc)(input)?;
-    let (input, (annotations, _, const_type, const_name, _, const_value, _)) = tuple((
-        many0(ws_plus(parse_annotation)),
-        ws_plus(tag("const")),
+    let start = input;
+    let (input, (annotations, _)) =
+        tuple((many0(ws_plus(parse_annotation)), ws_plus(tag("const"))))(input)?;
+    let (input, (const_type, const_name, _, const_value, _)) = cut(tuple((
         ws_plus(parse_type),
         ws(identifier),
         ws(char('=')),
-        ws(parse_const_value),
+        ws(parse_const_expr),
         char(';'),
-    ))(input)?;
+    )))(input)?;
 
     Ok((
         input,
         Const::new(
             *const_name,
             const_type,
-            *const_value,
+            const_value,
             opt_docu.unwrap_or_default(),
             annotations,
+            location(start, input),
         ),
     ))
 }
 
+// Constant expression, with the same operator precedence as in Java
 // Examples:
 // - 123
 // - "a string value"
-fn parse_const_value(input: Span) -> IResult<Span, Span> {
+// - (1 << 4) | FLAG_A
+// - { 1, 2, 3 }
+pub(crate) fn parse_const_expr(input: Span) -> IResult<Span, ConstExpr> {
+    parse_logical_or_expr(input)
+}
+
+fn parse_logical_or_expr(input: Span) -> IResult<Span, ConstExpr> {
+    parse_binary_expr(input, parse_logical_and_expr, |i| {
+        value(BinaryOp::Or, tag("||"))(i)
+    })
+}
+
+fn parse_logical_and_expr(input: Span) -> IResult<Span, ConstExpr> {
+    parse_binary_expr(input, parse_bit_or_expr, |i| {
+        value(BinaryOp::And, tag("&&"))(i)
+    })
+}
+
+fn parse_bit_or_expr(input: Span) -> IResult<Span, ConstExpr> {
+    parse_binary_expr(input, parse_bit_xor_expr, |i| {
+        value(BinaryOp::BitOr, terminated(char('|'), not(char('|'))))(i)
+    })
+}
+
+fn parse_bit_xor_expr(input: Span) -> IResult<Span, ConstExpr> {
+    parse_binary_expr(input, parse_bit_and_expr, |i| {
+        value(BinaryOp::BitXor, char('^'))(i)
+    })
+}
+
+fn parse_bit_and_expr(input: Span) -> IResult<Span, ConstExpr> {
+    parse_binary_expr(input, parse_equality_expr, |i| {
+        value(BinaryOp::BitAnd, terminated(char('&'), not(char('&'))))(i)
+    })
+}
+
+fn parse_equality_expr(input: Span) -> IResult<Span, ConstExpr> {
+    parse_binary_expr(input, parse_relational_expr, |i| {
+        alt((
+            value(BinaryOp::Eq, tag("==")),
+            value(BinaryOp::Ne, tag("!=")),
+        ))(i)
+    })
+}
+
+fn parse_relational_expr(input: Span) -> IResult<Span, ConstExpr> {
+    parse_binary_expr(input, parse_shift_expr, |i| {
+        alt((
+            value(BinaryOp::Le, tag("<=")),
+            value(BinaryOp::Ge, tag(">=")),
+            value(BinaryOp::Lt, terminated(char('<'), not(char('<')))),
+            value(BinaryOp::Gt, terminated(char('>'), not(char('>')))),
+        ))(i)
+    })
+}
+
+fn parse_shift_expr(input: Span) -> IResult<Span, ConstExpr> {
+    parse_binary_expr(input, parse_additive_expr, |i| {
+        alt((
+            value(BinaryOp::Shl, tag("<<")),
+            value(BinaryOp::Shr, tag(">>")),
+        ))(i)
+    })
+}
+
+fn parse_additive_expr(input: Span) -> IResult<Span, ConstExpr> {
+    parse_binary_expr(input, parse_multiplicative_expr, |i| {
+        alt((
+            value(BinaryOp::Add, char('+')),
+            value(BinaryOp::Sub, char('-')),
+        ))(i)
+    })
+}
+
+fn parse_multiplicative_expr(input: Span) -> IResult<Span, ConstExpr> {
+    parse_binary_expr(input, parse_unary_expr, |i| {
+        alt((
+            value(BinaryOp::Mul, char('*')),
+            value(BinaryOp::Div, char('/')),
+            value(BinaryOp::Rem, char('%')),
+        ))(i)
+    })
+}
+
+// Left-associative sequence of operands separated by operators of the same
+// precedence (e.g.: a + b - c)
+fn parse_binary_expr<'a>(
+    input: Span<'a>,
+    operand: fn(Span<'a>) -> IResult<Span<'a>, ConstExpr>,
+    operator: fn(Span<'a>) -> IResult<Span<'a>, BinaryOp>,
+) -> IResult<Span<'a>, ConstExpr> {
+    let (input, first) = operand(input)?;
+    let (input, rest) = many0(pair(ws(operator), operand))(input)?;
+
+    let expr = rest.into_iter().fold(first, |lhs, (op, rhs)| {
+        ConstExpr::Binary(Box::new(lhs), op, Box::new(rhs))
+    });
+
+    Ok((input, expr))
+}
+
+// Examples:
+// - -1
+// - ~FLAG_A
+// - !(A && B)
+fn parse_unary_expr(input: Span) -> IResult<Span, ConstExpr> {
     alt((
-        recognize(tuple((
-            digit1,
-            opt(preceded(char('.'), digit0)),
-            opt(char('f')),
-        ))),
-        delimited(char('"'), take_until("\""), char('"')),
-        tag("{}"),
+        map(
+            pair(
+                terminated(
+                    alt((
+                        value(UnaryOp::Plus, char('+')),
+                        value(UnaryOp::Minus, char('-')),
+                        value(UnaryOp::Not, char('!')),
+                        value(UnaryOp::BitNot, char('~')),
+                    )),
+                    multispace0,
+                ),
+                parse_unary_expr,
+            ),
+            |(op, expr)| ConstExpr::Unary(op, Box::new(expr)),
+        ),
+        parse_primary_expr,
+    ))(input)
+}
+
+// Examples:
+// - (<const_expr>)
+// - { <const_expr>, <const_expr> }
+// - <literal>
+// - CONST_NAME
+// - ItemName.CONST_NAME
+fn parse_primary_expr(input: Span) -> IResult<Span, ConstExpr> {
+    alt((
+        delimited(
+            ws(char('(')),
+            parse_const_expr,
+            preceded(multispace0, char(')')),
+        ),
+        map(
+            delimited(
+                ws(char('{')),
+                separated_list0(ws(char(',')), parse_const_expr),
+                preceded(multispace0, pair(opt(ws(char(','))), char('}'))),
+            ),
+            ConstExpr::Array,
+        ),
+        map(parse_literal, ConstExpr::Literal),
+        map(parse_package_name, |name| match *name {
+            "true" => ConstExpr::Literal(Literal::Bool(true)),
+            "false" => ConstExpr::Literal(Literal::Bool(false)),
+            _ => ConstExpr::Reference(name.to_string()),
+        }),
     ))(input)
 }
 
+// Examples:
+// - 123, 0x7F, 017, 12L, 255u8
+// - 1.5, 2.f, 1e-3
+// - 'c', '\n'
+// - "a string value"
+fn parse_literal(input: Span) -> IResult<Span, Literal> {
+    let int_suffix = || opt(alt((tag("u8"), tag("l"), tag("L"))));
+
+    alt((
+        map(
+            recognize(tuple((tag_no_case("0x"), hex_digit1, int_suffix()))),
+            |s: Span| Literal::Int(s.to_string()),
+        ),
+        map(
+            recognize(tuple((
+                digit1,
+                alt((
+                    recognize(tuple((
+                        char('.'),
+                        digit0,
+                        opt(parse_float_exponent),
+                        opt(one_of("fFdD")),
+                    ))),
+                    recognize(pair(parse_float_exponent, opt(one_of("fFdD")))),
+                    recognize(one_of("fFdD")),
+                )),
+            ))),
+            |s: Span| Literal::Float(s.to_string()),
+        ),
+        map(recognize(pair(digit1, int_suffix())), |s: Span| {
+            Literal::Int(s.to_string())
+        }),
+        map(
+            delimited(
+                char('\''),
+                recognize(alt((preceded(char('\\'), anychar), none_of("\\'")))),
+                char('\''),
+            ),
+            |s: Span| Literal::Char(s.to_string()),
+        ),
+        map(
+            delimited(
+                char('"'),
+                recognize(many0(alt((
+                    recognize(preceded(char('\\'), anychar)),
+                    is_not("\\\""),
+                )))),
+                char('"'),
+            ),
+            |s: Span| Literal::String(s.to_string()),
+        ),
+    ))(input)
+}
+
+fn parse_float_exponent(input: Span) -> IResult<Span, Span> {
+    recognize(tuple((one_of("eE"), opt(one_of("+-")), digit1)))(input)
+}
+
 // Examples:
 // - Type memberName;
+// - Type memberName = <const_expr>;
 fn parse_member(input: Span) -> IResult<Span, Member> {
     let (input, opt_docu) = opt(extract_javadoc)(input)?;
-    let (input, (annotations, member_type, member_name, _opt_value, _)) = tuple((
+    let start = input;
+    let (input, (annotations, member_type, member_name)) = tuple((
         many0(ws_plus(parse_annotation)),
         ws_plus(parse_type),
         ws(identifier),
-        opt(ws(preceded(char('='), ws(parse_const_value)))),
+    ))(input)?;
+    let (input, (opt_value, _)) = cut(pair(
+        opt(ws(preceded(char('='), ws(parse_const_expr)))),
         char(';'),
     ))(input)?;
 
-    // TODO: value!
-
     Ok((
         input,
         Member::new(
             *member_name,
             member_type,
+            opt_value,
             opt_docu.unwrap_or_default(),
             annotations,
+            location(start, input),
         ),
     ))
 }
@@ -356,16 +839,21 @@ fn parse_member(input: Span) -> IResult<Span, Member> {
 // - ReturnType methodName(<args>) = 123
 fn parse_method(input: Span) -> IResult<Span, Method> {
     let (input, opt_docu) = opt(extract_javadoc)(input)?;
+    let start = input;
 
-    let (input, (annotations, oneway_opt, method_type, method_name, args, _, _)) = tuple((
+    let (input, (annotations, oneway_opt, method_type, method_name, _)) = tuple((
         many0(ws_plus(parse_annotation)),
         opt(ws_plus(tag("oneway"))),
         ws_plus(parse_type),
         ws(identifier),
-        delimited(ws(char('(')), parse_args, ws(char(')'))),
-        opt(tuple((ws(char('=')), ws(digit1)))),
-        char(';'),
+        ws(char('(')),
     ))(input)?;
+    let (input, (args, _, transaction_code, _)) = cut(tuple((
+        parse_args,
+        ws(char(')')),
+        opt(preceded(ws(char('=')), ws(parse_transaction_code))),
+        char(';'),
+    )))(input)?;
 
     Ok((
         input,
@@ -374,18 +862,32 @@ fn parse_method(input: Span) -> IResult<Span, Method> {
             *method_name,
             method_type,
             args,
+            transaction_code,
             opt_docu.unwrap_or_default(),
             annotations,
+            location(start, input),
         ),
     ))
 }
 
+fn parse_transaction_code(input: Span) -> IResult<Span, u32> {
+    map_res(digit1, |code: Span| code.parse::<u32>())(input)
+}
+
 // Examples:
 // -
 // - <arg>
 // - <arg>, <arg>, <arg>
+// - <arg> /* comment */, <arg>
 fn parse_args(input: Span) -> IResult<Span, Vec<Arg>> {
-    separated_list0(ws(char(',')), ws(parse_arg))(input)
+    let comments = || many0(ws(parse_comment));
+    terminated(
+        separated_list0(
+            ws(char(',')),
+            delimited(comments(), ws(parse_arg), comments()),
+        ),
+        comments(),
+    )(input)
 }
 
 // Examples:
@@ -393,6 +895,7 @@ fn parse_args(input: Span) -> IResult<Span, Vec<Arg>> {
 // - <direction> <type>
 // - <direction> <type> argName
 fn parse_arg(input: Span) -> IResult<Span, Arg> {
+    let start = input;
     let (input, (annotations, arg_direction, arg_type, opt_arg_name)) = tuple((
         many0(ws_plus(parse_annotation)),
         opt(ws_plus(parse_direction)),
@@ -407,6 +910,7 @@ fn parse_arg(input: Span) -> IResult<Span, Arg> {
             opt_arg_name.as_deref().unwrap_or(&"").to_string(),
             arg_type,
             annotations,
+            location(start, input),
         ),
     ))
 }
@@ -428,68 +932,88 @@ fn parse_direction(input: Span) -> IResult<Span, Direction> {
 
 // Examples:
 // - typeName
-// - <generic>
+// - typeName<subType1, subType2<subSubType>>
+// - typeName[]
+// - typeName[3][]
 fn parse_type(input: Span) -> IResult<Span, Type> {
-    alt((
-        parse_generic,
-        parse_custom_array,
-        map(parse_type_name, |s: String| Type::new(s, Vec::new())),
-    ))(input)
-}
-
-fn parse_custom_array(input: Span) -> IResult<Span, Type> {
-    let (input, name) = terminated(parse_package_name, preceded(space0, tag("[]")))(input)?;
-    let new_type = Type::new("Array", vec![Type::with_name(*name)]);
+    let (input, (name, opt_generic_types, array_dimensions)) = tuple((
+        parse_package_name,
+        opt(preceded(
+            multispace0,
+            delimited(char('<'), ws(parse_generic_args), char('>')),
+        )),
+        many0(preceded(space0, parse_array_dimension)),
+    ))(input)?;
 
-    Ok((input, new_type))
+    Ok((
+        input,
+        Type::new_array(
+            *name,
+            opt_generic_types.unwrap_or_default(),
+            array_dimensions,
+        ),
+    ))
 }
-//let unresolved_typeme = if unresolved_type.name.ends_with("[]") {
-//    let name = unresolved_type.name[0..unresolved_type.name.len() - 2].to_string();
-
-fn parse_type_name(input: Span) -> IResult<Span, String> {
-    let (input, (name, opt_array)) =
-        pair(parse_package_name, opt(preceded(space0, tag("[]"))))(input)?;
-    let s = match opt_array {
-        Some(_) => format!("{}[]", name),
-        None => name.to_string(),
-    };
 
-    Ok((input, s))
+fn parse_generic_args(input: Span) -> IResult<Span, Vec<Type>> {
+    separated_list1(char(','), ws(parse_type))(input)
 }
 
-// - typeName<subType1, subType2<subSubType>>
-fn parse_generic(input: Span) -> IResult<Span, Type> {
-    let (input, (name, generic_types)) = tuple((
-        ws(parse_type_name),
-        delimited(char('<'), ws(parse_generic_args), char('>')),
-    ))(input)?;
+// Examples:
+// - []
+// - [3]
+// - [SIZE]
+fn parse_array_dimension(input: Span) -> IResult<Span, ArrayDimension> {
+    let (input, opt_size) = delimited(char('['), ws(opt(is_not("]"))), char(']'))(input)?;
 
     Ok((
         input,
-        Type {
-            name: name.to_string(),
-            generic_types,
+        match opt_size {
+            Some(size) => ArrayDimension::Fixed(size.trim().to_string()),
+            None => ArrayDimension::Dynamic,
         },
     ))
 }
 
-fn parse_generic_args(input: Span) -> IResult<Span, Vec<Type>> {
-    separated_list1(char(','), ws(parse_type))(input)
-}
-
 // Examples:
 // - @AnnotationName
 // - @AnnotationName(Hello="World")
-fn parse_annotation(input: Span) -> IResult<Span, Annotation> {
-    let (input, annotation) = preceded(
+// - @AnnotationName(a=1, b={"x", "y"}, c=ItemName.CONST_NAME)
+// - @AnnotationName("value")
+pub(crate) fn parse_annotation(input: Span) -> IResult<Span, Annotation> {
+    let (input, (name, opt_params)) = preceded(
         char('@'),
-        recognize(pair(
+        pair(
             identifier,
-            opt(delimited(char('('), is_not(")"), char(')'))),
-        )),
+            opt(delimited(
+                char('('),
+                ws(opt(parse_annotation_params)),
+                char(')'),
+            )),
+        ),
     )(input)?;
 
-    Ok((input, Annotation(annotation.to_string())))
+    Ok((
+        input,
+        Annotation::new(*name, opt_params.flatten().unwrap_or_default()),
+    ))
+}
+
+// Examples:
+// - name=<const_expr>
+// - name1=<const_expr>, name2=<const_expr>
+// - <const_expr>
+fn parse_annotation_params(input: Span) -> IResult<Span, Vec<AnnotationParam>> {
+    alt((
+        separated_list1(ws(char(',')), parse_annotation_param),
+        map(parse_const_expr, |v| vec![AnnotationParam::new("value", v)]),
+    ))(input)
+}
+
+fn parse_annotation_param(input: Span) -> IResult<Span, AnnotationParam> {
+    let (input, (name, _, value)) = tuple((identifier, ws(char('=')), parse_const_expr))(input)?;
+
+    Ok((input, AnnotationParam::new(*name, value)))
 }
 
 // Examples:
@@ -497,11 +1021,30 @@ fn parse_annotation(input: Span) -> IResult<Span, Annotation> {
 // // ...
 fn parse_comment(input: Span) -> IResult<Span, Span> {
     alt((
-        delimited(tag("//"), preceded(space0, not_line_ending), line_ending),
+        delimited(
+            tag("//"),
+            preceded(space0, not_line_ending),
+            alt((line_ending, eof)),
+        ),
         delimited(tag("/*"), ws(take_until("*/")), tag("*/")),
     ))(input)
 }
 
+// Location of the input consumed from start to end, surrounding whitespace excluded
+fn location(start: Span, end: Span) -> NodeRange {
+    let consumed = &start.fragment()[..end.location_offset() - start.location_offset()];
+    let trimmed = consumed.trim_start();
+
+    let start_position =
+        SourcePosition::new(start.location_line() as usize, start.get_utf8_column())
+            .advanced_by(&consumed[..consumed.len() - trimmed.len()]);
+
+    NodeRange(SourceRange::new(
+        start_position,
+        start_position.advanced_by(trimmed.trim_end()),
+    ))
+}
+
 fn extract_javadoc(input: Span) -> IResult<Span, String> {
     let (input, comments) = many0(ws(recognize(parse_comment)))(input)?;
 
@@ -518,20 +1061,22 @@ fn extract_javadoc(input: Span) -> IResult<Span, String> {
 // Examples:
 // /** ... */
 // /** ... \n * ... \n * ... */
+//
+// The lines are kept (e.g.: for paragraphs), without their leading '*'
 fn parse_javadoc(input: Span) -> IResult<Span, String> {
     let (input, comment_str) = delimited(tag("/**"), take_until("*/"), tag("*/"))(input)?;
     let (_, lines) = separated_list0(
-        tuple((line_ending, space0, opt(char('*')), space0)),
+        tuple((line_ending, space0, opt(char('*')), opt(char(' ')))),
         not_line_ending,
     )(comment_str)?;
 
     let doc = lines
         .iter()
-        .map(|s| s.to_string())
-        .collect::<Vec<String>>()
-        .join(" ");
+        .map(|s| s.trim_end())
+        .collect::<Vec<&str>>()
+        .join("\n");
 
-    Ok((input, doc))
+    Ok((input, doc.trim().to_string()))
 }
 
 // Valid identifier
@@ -540,6 +1085,9 @@ pub fn identifier(input: Span) -> IResult<Span, Span> {
         alt((alpha1, tag("_"))),
         many0(alt((alphanumeric1, tag("_")))),
     ))(input)
+    .map_err(|e| {
+        e.map(|_: SyntaxError<Span>| SyntaxError::new(input, SyntaxErrorKind::ExpectedIdentifier))
+    })
 }
 
 // Ignore leading/trailing spaces
@@ -581,12 +1129,12 @@ mod tests {
 
     #[test]
     fn test_package() -> Result<(), Box<dyn std::error::Error>> {
-        let input = Span::new("package x;rest");
+        let input = new_span("package x;rest");
         let (input, pkg) = parse_package(input)?;
         assert_eq!(pkg, "x");
         assert_eq!(*input, "rest");
 
-        let input = Span::new("package x.y.z;rest");
+        let input = new_span("package x.y.z;rest");
         let (input, pkg) = parse_package(input)?;
         assert_eq!(pkg, "x.y.z");
         assert_eq!(*input, "rest");
@@ -596,17 +1144,29 @@ mod tests {
 
     #[test]
     fn test_import() -> Result<(), Box<dyn std::error::Error>> {
-        let input = Span::new("import x.y.z;rest");
+        let input = new_span("import x.y.z;rest");
         let (input, pkg) = parse_import(input)?;
         assert_eq!(*pkg, "x.y.z");
         assert_eq!(*input, "rest");
 
+        let input = new_span("import x.y.Outer.Inner;rest");
+        let (input, pkg) = parse_import(input)?;
+        assert_eq!(*pkg, "x.y.Outer.Inner");
+        assert_eq!(*input, "rest");
+
+        let input = new_span("import x.y.*;rest");
+        let (input, pkg) = parse_import(input)?;
+        assert_eq!(*pkg, "x.y.*");
+        assert_eq!(*input, "rest");
+
+        assert!(parse_import(new_span("import x.*.y;rest")).is_err());
+
         Ok(())
     }
 
     #[test]
     fn test_simple_type() -> Result<(), Box<dyn std::error::Error>> {
-        let input = Span::new("TypeName;rest");
+        let input = new_span("TypeName;rest");
         let (input, output_type) = parse_type(input)?;
 
         assert_eq!(output_type, Type::with_name("TypeName"),);
@@ -617,21 +1177,61 @@ mod tests {
 
     #[test]
     fn test_array_type() -> Result<(), Box<dyn std::error::Error>> {
-        let input = Span::new("ArrayTypeName [];rest");
+        let input = new_span("ArrayTypeName [];rest");
         let (input, output_type) = parse_type(input)?;
 
         assert_eq!(
             output_type,
-            Type::new("Array", vec![Type::with_name("ArrayTypeName")])
+            Type::new_array("ArrayTypeName", Vec::new(), vec![ArrayDimension::Dynamic])
         );
         assert_eq!(*input, ";rest");
 
         Ok(())
     }
 
+    #[test]
+    fn test_fixed_size_array_type() -> Result<(), Box<dyn std::error::Error>> {
+        let input = new_span("int[3][ SIZE ][];rest");
+        let (input, output_type) = parse_type(input)?;
+
+        assert_eq!(
+            output_type,
+            Type::new_array(
+                "int",
+                Vec::new(),
+                vec![
+                    ArrayDimension::Fixed("3".to_string()),
+                    ArrayDimension::Fixed("SIZE".to_string()),
+                    ArrayDimension::Dynamic
+                ]
+            )
+        );
+        assert_eq!(*input, ";rest");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generic_array_type() -> Result<(), Box<dyn std::error::Error>> {
+        let input = new_span("List<Foo>[] list;rest");
+        let (input, output_type) = parse_type(input)?;
+
+        assert_eq!(
+            output_type,
+            Type::new_array(
+                "List",
+                vec![Type::with_name("Foo")],
+                vec![ArrayDimension::Dynamic]
+            )
+        );
+        assert_eq!(*input, " list;rest");
+
+        Ok(())
+    }
+
     #[test]
     fn test_generic_type() -> Result<(), Box<dyn std::error::Error>> {
-        let input = Span::new("TestMap<Key,TestVec<V1, V2>>;rest");
+        let input = new_span("TestMap<Key,TestVec<V1, V2>>;rest");
         let (input, output_type) = parse_type(input)?;
 
         assert_eq!(
@@ -645,6 +1245,7 @@ mod tests {
                         vec![Type::with_name("V1"), Type::with_name("V2")]
                     )
                 ],
+                array_dimensions: Vec::new(),
             }
         );
         assert_eq!(*input, ";rest");
@@ -653,7 +1254,7 @@ mod tests {
     }
     #[test]
     fn test_arg_with_name() -> Result<(), Box<dyn std::error::Error>> {
-        let input = Span::new("TypeName Albert;rest");
+        let input = new_span("TypeName Albert;rest");
         let (input, arg) = parse_arg(input)?;
 
         assert_eq!(arg, Arg::with_name("Albert", Type::with_name("TypeName")));
@@ -664,7 +1265,7 @@ mod tests {
 
     #[test]
     fn test_arg_with_direction() -> Result<(), Box<dyn std::error::Error>> {
-        let input = Span::new("in TypeName;rest");
+        let input = new_span("in TypeName;rest");
         let (input, arg) = parse_arg(input)?;
 
         assert_eq!(
@@ -678,7 +1279,7 @@ mod tests {
 
     #[test]
     fn test_arg_with_direction_and_name() -> Result<(), Box<dyn std::error::Error>> {
-        let input = Span::new("inout TypeName<G> Roger;rest");
+        let input = new_span("inout TypeName<G> Roger;rest");
         let (input, arg) = parse_arg(input)?;
 
         assert_eq!(
@@ -696,7 +1297,7 @@ mod tests {
 
     #[test]
     fn test_arg_with_annotations() -> Result<(), Box<dyn std::error::Error>> {
-        let input = Span::new(
+        let input = new_span(
             r#"@Annotation1
             @Annotation2(AnnotationParam)
             TypeName Albert;rest"#,
@@ -710,9 +1311,16 @@ mod tests {
                 "Albert",
                 Type::with_name("TypeName"),
                 vec![
-                    Annotation("Annotation1".to_string()),
-                    Annotation("Annotation2(AnnotationParam)".to_string())
+                    Annotation::with_name("Annotation1"),
+                    Annotation::new(
+                        "Annotation2",
+                        vec![AnnotationParam::new(
+                            "value",
+                            ConstExpr::Reference("AnnotationParam".to_string())
+                        )]
+                    )
                 ],
+                NodeRange::default(),
             )
         );
         assert_eq!(*input, ";rest");
@@ -722,7 +1330,7 @@ mod tests {
 
     #[test]
     fn test_num_const() -> Result<(), Box<dyn std::error::Error>> {
-        let input = Span::new("const TypeName CONST_NAME = 123;rest");
+        let input = new_span("const TypeName CONST_NAME = 123;rest");
         let (input, the_const) = parse_const(input)?;
 
         assert_eq!(
@@ -730,9 +1338,10 @@ mod tests {
             Const::new(
                 "CONST_NAME",
                 Type::with_name("TypeName"),
-                "123",
+                ConstExpr::int("123"),
                 String::new(),
                 Vec::new(),
+                NodeRange::default(),
             )
         );
         assert_eq!(*input, "rest");
@@ -742,7 +1351,7 @@ mod tests {
 
     #[test]
     fn test_string_const() -> Result<(), Box<dyn std::error::Error>> {
-        let input = Span::new("const TypeName CONST_NAME = \"const_value\";rest");
+        let input = new_span("const TypeName CONST_NAME = \"const_value\";rest");
         let (input, the_const) = parse_const(input)?;
 
         assert_eq!(
@@ -750,9 +1359,10 @@ mod tests {
             Const::new(
                 "CONST_NAME",
                 Type::with_name("TypeName"),
-                "const_value",
+                ConstExpr::string("const_value"),
                 String::new(),
                 Vec::new(),
+                NodeRange::default(),
             )
         );
         assert_eq!(*input, "rest");
@@ -762,7 +1372,7 @@ mod tests {
 
     #[test]
     fn test_const_with_javadoc() -> Result<(), Box<dyn std::error::Error>> {
-        let input = Span::new(
+        let input = new_span(
             r#"
 /**
  * Const docu
@@ -776,9 +1386,10 @@ const TypeName CONST_NAME = 123;rest"#,
             Const::new(
                 "CONST_NAME",
                 Type::with_name("TypeName"),
-                "123",
-                " Const docu ".to_string(),
+                ConstExpr::int("123"),
+                "Const docu".to_string(),
                 Vec::new(),
+                NodeRange::default(),
             )
         );
         assert_eq!(*input, "rest");
@@ -788,7 +1399,7 @@ const TypeName CONST_NAME = 123;rest"#,
 
     #[test]
     fn test_const_with_annotation() -> Result<(), Box<dyn std::error::Error>> {
-        let input = Span::new("@AnnotationName const TypeName CONST_NAME = 123;rest");
+        let input = new_span("@AnnotationName const TypeName CONST_NAME = 123;rest");
         let (input, the_const) = parse_const(input)?;
 
         assert_eq!(
@@ -796,9 +1407,10 @@ const TypeName CONST_NAME = 123;rest"#,
             Const::new(
                 "CONST_NAME",
                 Type::with_name("TypeName"),
-                "123",
+                ConstExpr::int("123"),
                 String::new(),
-                vec![Annotation("AnnotationName".to_string())]
+                vec![Annotation::with_name("AnnotationName")],
+                NodeRange::default()
             )
         );
         assert_eq!(*input, "rest");
@@ -806,9 +1418,99 @@ const TypeName CONST_NAME = 123;rest"#,
         Ok(())
     }
 
+    #[test]
+    fn test_const_expr_precedence() -> Result<(), Box<dyn std::error::Error>> {
+        let input = new_span("(1 << 4) | 0x2 + FLAG_A;rest");
+        let (input, expr) = parse_const_expr(input)?;
+
+        assert_eq!(
+            expr,
+            ConstExpr::binary(
+                ConstExpr::binary(ConstExpr::int("1"), BinaryOp::Shl, ConstExpr::int("4")),
+                BinaryOp::BitOr,
+                ConstExpr::binary(
+                    ConstExpr::int("0x2"),
+                    BinaryOp::Add,
+                    ConstExpr::Reference("FLAG_A".to_string())
+                )
+            )
+        );
+        assert_eq!(*input, ";rest");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_const_expr_unary_and_literals() -> Result<(), Box<dyn std::error::Error>> {
+        let input = new_span("-1.5f;rest");
+        let (input, expr) = parse_const_expr(input)?;
+        assert_eq!(
+            expr,
+            ConstExpr::Unary(
+                UnaryOp::Minus,
+                Box::new(ConstExpr::Literal(Literal::Float("1.5f".to_string())))
+            )
+        );
+        assert_eq!(*input, ";rest");
+
+        let input = new_span("!false;rest");
+        let (input, expr) = parse_const_expr(input)?;
+        assert_eq!(
+            expr,
+            ConstExpr::Unary(
+                UnaryOp::Not,
+                Box::new(ConstExpr::Literal(Literal::Bool(false)))
+            )
+        );
+        assert_eq!(*input, ";rest");
+
+        let input = new_span("'\\n';rest");
+        let (input, expr) = parse_const_expr(input)?;
+        assert_eq!(expr, ConstExpr::Literal(Literal::Char("\\n".to_string())));
+        assert_eq!(*input, ";rest");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_const_expr_string_concat() -> Result<(), Box<dyn std::error::Error>> {
+        let input = new_span(r#""abc" + "def";rest"#);
+        let (input, expr) = parse_const_expr(input)?;
+
+        assert_eq!(
+            expr,
+            ConstExpr::binary(
+                ConstExpr::string("abc"),
+                BinaryOp::Add,
+                ConstExpr::string("def")
+            )
+        );
+        assert_eq!(*input, ";rest");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_const_expr_array() -> Result<(), Box<dyn std::error::Error>> {
+        let input = new_span("{1, 2, Other.VALUE};rest");
+        let (input, expr) = parse_const_expr(input)?;
+
+        assert_eq!(
+            expr,
+            ConstExpr::Array(vec![
+                ConstExpr::int("1"),
+                ConstExpr::int("2"),
+                ConstExpr::Reference("Other.VALUE".to_string()),
+            ])
+        );
+        assert_eq!(*input, ";rest");
+
+        Ok(())
+    }
+
     #[test]
     fn test_member() -> Result<(), Box<dyn std::error::Error>> {
-        let input = Span::new("TypeName memberName;rest");
+        let input = new_span("TypeName memberName;rest");
         let (input, member) = parse_member(input)?;
 
         assert_eq!(
@@ -816,8 +1518,10 @@ const TypeName CONST_NAME = 123;rest"#,
             Member::new(
                 "memberName",
                 Type::with_name("TypeName"),
+                None,
                 String::new(),
                 Vec::new(),
+                NodeRange::default(),
             )
         );
         assert_eq!(*input, "rest");
@@ -827,7 +1531,7 @@ const TypeName CONST_NAME = 123;rest"#,
 
     #[test]
     fn test_member_with_value() -> Result<(), Box<dyn std::error::Error>> {
-        let input = Span::new("TypeName memberName = \"memberValue\";rest");
+        let input = new_span("TypeName memberName = \"memberValue\";rest");
         let (input, member) = parse_member(input)?;
 
         assert_eq!(
@@ -835,8 +1539,10 @@ const TypeName CONST_NAME = 123;rest"#,
             Member::new(
                 "memberName",
                 Type::with_name("TypeName"),
+                Some(ConstExpr::string("memberValue")),
                 String::new(),
                 Vec::new(),
+                NodeRange::default(),
             )
         );
         assert_eq!(*input, "rest");
@@ -846,7 +1552,7 @@ const TypeName CONST_NAME = 123;rest"#,
 
     #[test]
     fn test_member_with_javadoc() -> Result<(), Box<dyn std::error::Error>> {
-        let input = Span::new(
+        let input = new_span(
             r#"
 /**
  * Member docu
@@ -860,8 +1566,10 @@ TypeName memberName;rest"#,
             Member::new(
                 "memberName",
                 Type::with_name("TypeName"),
-                " Member docu ".to_string(),
+                None,
+                "Member docu".to_string(),
                 Vec::new(),
+                NodeRange::default(),
             )
         );
         assert_eq!(*input, "rest");
@@ -871,7 +1579,7 @@ TypeName memberName;rest"#,
 
     #[test]
     fn test_member_with_annotation() -> Result<(), Box<dyn std::error::Error>> {
-        let input = Span::new("@AnnotationName TypeName memberName;rest");
+        let input = new_span("@AnnotationName TypeName memberName;rest");
         let (input, member) = parse_member(input)?;
 
         assert_eq!(
@@ -879,8 +1587,10 @@ TypeName memberName;rest"#,
             Member::new(
                 "memberName",
                 Type::with_name("TypeName"),
+                None,
                 String::new(),
-                vec![Annotation("AnnotationName".to_string())]
+                vec![Annotation::with_name("AnnotationName")],
+                NodeRange::default()
             )
         );
         assert_eq!(*input, "rest");
@@ -890,7 +1600,7 @@ TypeName memberName;rest"#,
 
     #[test]
     fn test_method_without_arg() -> Result<(), Box<dyn std::error::Error>> {
-        let input = Span::new("TypeName myMethod();rest");
+        let input = new_span("TypeName myMethod();rest");
         let (input, method) = parse_method(input)?;
 
         assert_eq!(
@@ -900,8 +1610,10 @@ TypeName memberName;rest"#,
                 "myMethod",
                 Type::with_name("TypeName"),
                 Vec::new(),
+                None,
                 String::new(),
                 Vec::new(),
+                NodeRange::default(),
             )
         );
         assert_eq!(*input, "rest");
@@ -911,7 +1623,7 @@ TypeName memberName;rest"#,
 
     #[test]
     fn test_method_with_1_arg() -> Result<(), Box<dyn std::error::Error>> {
-        let input = Span::new("TypeName myMethod(ArgType arg);rest");
+        let input = new_span("TypeName myMethod(ArgType arg);rest");
         let (input, method) = parse_method(input)?;
 
         assert_eq!(
@@ -921,8 +1633,10 @@ TypeName memberName;rest"#,
                 "myMethod",
                 Type::with_name("TypeName"),
                 vec![Arg::with_name("arg", Type::with_name("ArgType"))],
+                None,
                 String::new(),
                 Vec::new(),
+                NodeRange::default(),
             )
         );
         assert_eq!(*input, "rest");
@@ -932,7 +1646,7 @@ TypeName memberName;rest"#,
 
     #[test]
     fn test_method_with_3_args() -> Result<(), Box<dyn std::error::Error>> {
-        let input = Span::new("TypeName myMethod(ArgType1, ArgType2 arg2, ArgType3);rest");
+        let input = new_span("TypeName myMethod(ArgType1, ArgType2 arg2, ArgType3);rest");
         let (input, method) = parse_method(input)?;
 
         assert_eq!(
@@ -946,8 +1660,10 @@ TypeName memberName;rest"#,
                     Arg::with_name("arg2", Type::with_name("ArgType2")),
                     Arg::unnamed(Type::with_name("ArgType3"))
                 ],
+                None,
                 String::new(),
                 Vec::new(),
+                NodeRange::default(),
             )
         );
         assert_eq!(*input, "rest");
@@ -957,7 +1673,7 @@ TypeName memberName;rest"#,
 
     #[test]
     fn test_oneway_method() -> Result<(), Box<dyn std::error::Error>> {
-        let input = Span::new("oneway void myMethod();rest");
+        let input = new_span("oneway void myMethod();rest");
         let (input, method) = parse_method(input)?;
 
         assert_eq!(
@@ -967,8 +1683,10 @@ TypeName memberName;rest"#,
                 "myMethod",
                 Type::with_name("void"),
                 Vec::new(),
+                None,
                 String::new(),
                 Vec::new(),
+                NodeRange::default(),
             )
         );
         assert_eq!(*input, "rest");
@@ -978,7 +1696,7 @@ TypeName memberName;rest"#,
 
     #[test]
     fn test_method_with_value() -> Result<(), Box<dyn std::error::Error>> {
-        let input = Span::new("void myMethod() = 123;rest");
+        let input = new_span("void myMethod() = 123;rest");
         let (input, method) = parse_method(input)?;
 
         assert_eq!(
@@ -988,8 +1706,10 @@ TypeName memberName;rest"#,
                 "myMethod",
                 Type::with_name("void"),
                 Vec::new(),
+                Some(123),
                 String::new(),
                 Vec::new(),
+                NodeRange::default(),
             )
         );
         assert_eq!(*input, "rest");
@@ -999,7 +1719,7 @@ TypeName memberName;rest"#,
 
     #[test]
     fn test_method_with_javadoc() -> Result<(), Box<dyn std::error::Error>> {
-        let input = Span::new(
+        let input = new_span(
             r#"
 /**
  * Method docu
@@ -1015,8 +1735,10 @@ void myMethod() = 123;rest"#,
                 "myMethod",
                 Type::with_name("void"),
                 Vec::new(),
-                " Method docu ".to_string(), // TODO
+                Some(123),
+                "Method docu".to_string(),
                 Vec::new(),
+                NodeRange::default(),
             )
         );
         assert_eq!(*input, "rest");
@@ -1026,7 +1748,7 @@ void myMethod() = 123;rest"#,
 
     #[test]
     fn test_method_withannotation() -> Result<(), Box<dyn std::error::Error>> {
-        let input = Span::new("@AnnotationName TypeName myMethod();rest");
+        let input = new_span("@AnnotationName TypeName myMethod();rest");
         let (input, method) = parse_method(input)?;
 
         assert_eq!(
@@ -1036,8 +1758,10 @@ void myMethod() = 123;rest"#,
                 "myMethod",
                 Type::with_name("TypeName"),
                 Vec::new(),
+                None,
                 String::new(),
-                vec![Annotation("AnnotationName".to_string())]
+                vec![Annotation::with_name("AnnotationName")],
+                NodeRange::default()
             )
         );
         assert_eq!(*input, "rest");
@@ -1047,7 +1771,7 @@ void myMethod() = 123;rest"#,
 
     #[test]
     fn test_eol_comment() -> Result<(), Box<dyn std::error::Error>> {
-        let input = Span::new("// first comment\n// second comment\nrest");
+        let input = new_span("// first comment\n// second comment\nrest");
         let (input, comments) = many0(map(parse_comment, |c| *c))(input)?;
 
         assert_eq!(comments, vec!["first comment", "second comment"]);
@@ -1058,7 +1782,7 @@ void myMethod() = 123;rest"#,
 
     #[test]
     fn test_multiline_comment() -> Result<(), Box<dyn std::error::Error>> {
-        let input = Span::new("/* this is a multi\nline comment*/rest");
+        let input = new_span("/* this is a multi\nline comment*/rest");
         let (input, comment) = parse_comment(input)?;
 
         assert_eq!(*comment, "this is a multi\nline comment");
@@ -1067,21 +1791,20 @@ void myMethod() = 123;rest"#,
         Ok(())
     }
     #[test]
-    #[ignore]
     fn test_javadoc() -> Result<(), Box<dyn std::error::Error>> {
-        let input = Span::new("/** This is a javadoc\n * comment*/rest");
+        let input = new_span("/** This is a javadoc\n * comment*/rest");
         let (input, comment) = parse_javadoc(input)?;
 
-        assert_eq!(comment, " This is a javadoc comment".to_string());
+        assert_eq!(comment, "This is a javadoc\ncomment".to_string());
         assert_eq!(*input, "rest");
 
         let input =
-            Span::new("/**\n * JavaDoc title\n *\n * JavaDoc line1\n * JavaDoc line2\n */rest");
+            new_span("/**\n * JavaDoc title\n *\n * JavaDoc line1\n * JavaDoc line2\n */rest");
         let (input, comment) = parse_javadoc(input)?;
 
         assert_eq!(
             comment,
-            "JavaDoc title\n\nJavaDoc line1\nJAvaDoc line2".to_string()
+            "JavaDoc title\n\nJavaDoc line1\nJavaDoc line2".to_string()
         );
         assert_eq!(*input, "rest");
 
@@ -1090,18 +1813,55 @@ void myMethod() = 123;rest"#,
 
     #[test]
     fn test_annotation() -> Result<(), Box<dyn std::error::Error>> {
-        let input = Span::new("@AnnotationName;rest");
+        let input = new_span("@AnnotationName;rest");
+        let (input, annotation) = parse_annotation(input)?;
+
+        assert_eq!(annotation, Annotation::with_name("AnnotationName"));
+
+        assert_eq!(*input, ";rest");
+        let input = new_span("@AnnotationName(Hello=\"World\");rest");
         let (input, annotation) = parse_annotation(input)?;
 
-        assert_eq!(annotation, Annotation("AnnotationName".to_string()));
+        assert_eq!(
+            annotation,
+            Annotation::new(
+                "AnnotationName",
+                vec![AnnotationParam::new("Hello", ConstExpr::string("World"))]
+            )
+        );
+        assert_eq!(*input, ";rest");
+
+        let input = new_span(
+            r#"@JavaDerive(equals=true, toString = false, names={"a", "b"}, size=Foo.SIZE);rest"#,
+        );
+        let (input, annotation) = parse_annotation(input)?;
 
+        assert_eq!(
+            annotation,
+            Annotation::new(
+                "JavaDerive",
+                vec![
+                    AnnotationParam::new("equals", ConstExpr::Literal(Literal::Bool(true))),
+                    AnnotationParam::new("toString", ConstExpr::Literal(Literal::Bool(false))),
+                    AnnotationParam::new(
+                        "names",
+                        ConstExpr::Array(vec![ConstExpr::string("a"), ConstExpr::string("b")])
+                    ),
+                    AnnotationParam::new("size", ConstExpr::Reference("Foo.SIZE".to_string())),
+                ]
+            )
+        );
         assert_eq!(*input, ";rest");
-        let input = Span::new("@AnnotationName(Hello=\"World\");rest");
+
+        let input = new_span("@Descriptor(\"a.b.IFoo\");rest");
         let (input, annotation) = parse_annotation(input)?;
 
         assert_eq!(
             annotation,
-            Annotation("AnnotationName(Hello=\"World\")".to_string())
+            Annotation::new(
+                "Descriptor",
+                vec![AnnotationParam::new("value", ConstExpr::string("a.b.IFoo"))]
+            )
         );
         assert_eq!(*input, ";rest");
 
@@ -1110,7 +1870,7 @@ void myMethod() = 123;rest"#,
 
     #[test]
     fn test_interface() -> Result<(), Box<dyn std::error::Error>> {
-        let input = Span::new(
+        let input = new_span(
             r#"
             /**
              * JavaDoc of Potato
@@ -1136,21 +1896,23 @@ void myMethod() = 123;rest"#,
             interface,
             Item::Interface {
                 name: "Potato".to_string(),
-                docu: " JavaDoc of Potato ".to_string(), // TODO: without spaces
+                docu: "JavaDoc of Potato".to_string(),
                 consts: vec![
                     Const::new(
                         "const1",
                         Type::with_name("int"),
-                        "1",
-                        " const1 docu ".to_string(),
+                        ConstExpr::int("1"),
+                        "const1 docu".to_string(),
                         Vec::new(),
+                        NodeRange::default(),
                     ),
                     Const::new(
                         "const2",
                         Type::with_name("String"),
-                        "two",
+                        ConstExpr::string("two"),
                         String::new(),
-                        Vec::new()
+                        Vec::new(),
+                        NodeRange::default()
                     )
                 ],
                 methods: vec![
@@ -1159,19 +1921,25 @@ void myMethod() = 123;rest"#,
                         "method1",
                         Type::with_name("String"),
                         Vec::new(),
-                        " method1 docu ".to_string(),
+                        None,
+                        "method1 docu".to_string(),
                         Vec::new(),
+                        NodeRange::default(),
                     ),
                     Method::new(
                         false,
                         "method2",
                         Type::with_name("int"),
                         Vec::new(),
+                        None,
                         String::new(),
                         Vec::new(),
+                        NodeRange::default(),
                     ),
                 ],
+                nested_items: Vec::new(),
                 annotations: Vec::new(),
+                location: NodeRange::default(),
             }
         );
         assert_eq!(*input, "rest");
@@ -1180,7 +1948,7 @@ void myMethod() = 123;rest"#,
     }
     #[test]
     fn test_interface_with_annotation() -> Result<(), Box<dyn std::error::Error>> {
-        let input = Span::new(
+        let input = new_span(
             r#"
             @InterfaceAnnotation1
             @InterfaceAnnotation2
@@ -1196,10 +1964,12 @@ void myMethod() = 123;rest"#,
                 docu: String::new(),
                 consts: Vec::new(),
                 methods: Vec::new(),
+                nested_items: Vec::new(),
                 annotations: vec![
-                    Annotation("InterfaceAnnotation1".to_string()),
-                    Annotation("InterfaceAnnotation2".to_string())
+                    Annotation::with_name("InterfaceAnnotation1"),
+                    Annotation::with_name("InterfaceAnnotation2")
                 ],
+                location: NodeRange::default(),
             }
         );
 
@@ -1208,7 +1978,7 @@ void myMethod() = 123;rest"#,
 
     #[test]
     fn test_error_inside_interface() -> Result<(), Box<dyn std::error::Error>> {
-        let input = Span::new(
+        let input = new_span(
             r#"
             /**
              * JavaDoc of Potato
@@ -1231,9 +2001,52 @@ void myMethod() = 123;rest"#,
         Ok(())
     }
 
+    #[test]
+    fn test_interface_with_trailing_comments() -> Result<(), Box<dyn std::error::Error>> {
+        let input = new_span(
+            r#"
+            interface Potato {
+                String method1();   // end of line
+                // own line
+                /* block */
+            }rest"#,
+        );
+        let (input, interface) = parse_item(input)?;
+
+        assert_eq!(*input, "rest");
+        if let Item::Interface { methods, .. } = interface {
+            assert_eq!(methods.len(), 1);
+        } else {
+            assert!(false);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_interface_with_transaction_codes() -> Result<(), Box<dyn std::error::Error>> {
+        let input = new_span(
+            r#"interface Potato {
+                String method1() = 3;
+                int method2() = 1;
+            }rest"#,
+        );
+        let (input, interface) = parse_interface(input)?;
+
+        if let Item::Interface { methods, .. } = interface {
+            assert_eq!(methods[0].transaction_code, Some(3));
+            assert_eq!(methods[1].transaction_code, Some(1));
+        } else {
+            assert!(false);
+        }
+        assert_eq!(*input, "rest");
+
+        Ok(())
+    }
+
     #[test]
     fn test_parcelable() -> Result<(), Box<dyn std::error::Error>> {
-        let input = Span::new(
+        let input = new_span(
             r#"
             /**
              * JavaDoc of Tomato
@@ -1253,22 +2066,186 @@ void myMethod() = 123;rest"#,
             parcelable,
             Item::Parcelable {
                 name: "Tomato".to_string(),
-                docu: " JavaDoc of Tomato ".to_string(), // TODO: without spaces
+                type_params: Vec::new(),
+                docu: "JavaDoc of Tomato".to_string(),
                 members: vec![
                     Member::new(
                         "member1",
                         Type::with_name("int"),
-                        " member1 docu ".to_string(),
+                        None,
+                        "member1 docu".to_string(),
                         Vec::new(),
+                        NodeRange::default(),
                     ),
                     Member::new(
                         "member2",
                         Type::with_name("String"),
+                        None,
                         String::new(),
-                        Vec::new()
+                        Vec::new(),
+                        NodeRange::default()
                     )
                 ],
+                nested_items: Vec::new(),
                 annotations: Vec::new(),
+                location: NodeRange::default(),
+            }
+        );
+        assert_eq!(*input, "rest");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unstructured_parcelable() -> Result<(), Box<dyn std::error::Error>> {
+        let input = new_span(
+            r#"
+            /** JavaDoc of Leek */
+            @JavaOnlyStableParcelable
+            parcelable Leek cpp_header "veggies/Leek.h" rust_type "veggies::Leek";rest"#,
+        );
+        let (input, parcelable) = parse_item(input)?;
+
+        assert_eq!(
+            parcelable,
+            Item::UnstructuredParcelable {
+                name: "Leek".to_string(),
+                docu: "JavaDoc of Leek".to_string(),
+                cpp_header: Some("veggies/Leek.h".to_string()),
+                ndk_header: None,
+                rust_type: Some("veggies::Leek".to_string()),
+                annotations: vec![Annotation::new("JavaOnlyStableParcelable", Vec::new())],
+                location: NodeRange::default(),
+            }
+        );
+        assert_eq!(*input, "rest");
+
+        let (input, parcelable) = parse_item(new_span("parcelable Onion;rest"))?;
+        assert!(matches!(
+            parcelable,
+            Item::UnstructuredParcelable { ref name, cpp_header: None, .. } if name == "Onion"
+        ));
+        assert_eq!(*input, "rest");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generic_parcelable() -> Result<(), Box<dyn std::error::Error>> {
+        let input = new_span(
+            r#"
+            parcelable Pair<A, B> {
+                A first;
+                B second;
+            }rest"#,
+        );
+        let (input, parcelable) = parse_item(input)?;
+
+        assert_eq!(
+            parcelable,
+            Item::Parcelable {
+                name: "Pair".to_string(),
+                type_params: vec!["A".to_string(), "B".to_string()],
+                docu: String::new(),
+                members: vec![
+                    Member::new(
+                        "first",
+                        Type::with_name("A"),
+                        None,
+                        String::new(),
+                        Vec::new(),
+                        NodeRange::default()
+                    ),
+                    Member::new(
+                        "second",
+                        Type::with_name("B"),
+                        None,
+                        String::new(),
+                        Vec::new(),
+                        NodeRange::default()
+                    ),
+                ],
+                nested_items: Vec::new(),
+                annotations: Vec::new(),
+                location: NodeRange::default(),
+            }
+        );
+        assert_eq!(*input, "rest");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_nested_items() -> Result<(), Box<dyn std::error::Error>> {
+        let input = new_span(
+            r#"
+            interface Garden {
+                parcelable Bed {
+                    enum Soil { SAND, CLAY }
+                    Soil soil;
+                }
+                void plant(in Bed bed);
+            }rest"#,
+        );
+        let (input, interface) = parse_item(input)?;
+
+        assert_eq!(
+            interface,
+            Item::Interface {
+                name: "Garden".to_string(),
+                docu: String::new(),
+                consts: Vec::new(),
+                methods: vec![Method::new(
+                    false,
+                    "plant",
+                    Type::with_name("void"),
+                    vec![Arg::with_direction(
+                        Direction::In,
+                        "bed",
+                        Type::with_name("Bed")
+                    )],
+                    None,
+                    String::new(),
+                    Vec::new(),
+                    NodeRange::default(),
+                )],
+                nested_items: vec![Item::Parcelable {
+                    name: "Bed".to_string(),
+                    type_params: Vec::new(),
+                    docu: String::new(),
+                    members: vec![Member::new(
+                        "soil",
+                        Type::with_name("Soil"),
+                        None,
+                        String::new(),
+                        Vec::new(),
+                        NodeRange::default(),
+                    )],
+                    nested_items: vec![Item::Enum {
+                        name: "Soil".to_string(),
+                        docu: String::new(),
+                        elements: vec![
+                            EnumElement {
+                                name: "SAND".to_string(),
+                                docu: String::new(),
+                                value: None,
+                                location: NodeRange::default(),
+                            },
+                            EnumElement {
+                                name: "CLAY".to_string(),
+                                docu: String::new(),
+                                value: None,
+                                location: NodeRange::default(),
+                            },
+                        ],
+                        annotations: Vec::new(),
+                        location: NodeRange::default(),
+                    }],
+                    annotations: Vec::new(),
+                    location: NodeRange::default(),
+                }],
+                annotations: Vec::new(),
+                location: NodeRange::default(),
             }
         );
         assert_eq!(*input, "rest");
@@ -1278,7 +2255,7 @@ void myMethod() = 123;rest"#,
 
     #[test]
     fn test_enum() -> Result<(), Box<dyn std::error::Error>> {
-        let input = Span::new(
+        let input = new_span(
             r#"
             /**
              * JavaDoc of Paprika
@@ -1290,7 +2267,8 @@ void myMethod() = 123;rest"#,
                 ELEMENT1 = 3,
 
                 ELEMENT2 = "quattro",
-                ELEMENT3
+                ELEMENT3,
+                ELEMENT4 = ELEMENT1 << 2
             }rest"#,
         );
         let (input, enumeration) = parse_item(input)?;
@@ -1299,29 +2277,239 @@ void myMethod() = 123;rest"#,
             enumeration,
             Item::Enum {
                 name: "Paprika".to_string(),
-                docu: " JavaDoc of Paprika ".to_string(), // TODO: without spaces
+                docu: "JavaDoc of Paprika".to_string(),
                 elements: vec![
                     EnumElement {
                         name: "ELEMENT1".to_string(),
-                        docu: " element1 docu ".to_string(),
-                        value: "3".to_string(),
+                        docu: "element1 docu".to_string(),
+                        value: Some(ConstExpr::int("3")),
+                        location: NodeRange::default(),
                     },
                     EnumElement {
                         name: "ELEMENT2".to_string(),
                         docu: String::new(),
-                        value: "quattro".to_string(),
+                        value: Some(ConstExpr::string("quattro")),
+                        location: NodeRange::default(),
                     },
                     EnumElement {
                         name: "ELEMENT3".to_string(),
                         docu: String::new(),
-                        value: String::new(),
+                        value: None,
+                        location: NodeRange::default(),
+                    },
+                    EnumElement {
+                        name: "ELEMENT4".to_string(),
+                        docu: String::new(),
+                        value: Some(ConstExpr::binary(
+                            ConstExpr::Reference("ELEMENT1".to_string()),
+                            BinaryOp::Shl,
+                            ConstExpr::int("2")
+                        )),
+                        location: NodeRange::default(),
                     },
                 ],
                 annotations: Vec::new(),
+                location: NodeRange::default(),
+            }
+        );
+        assert_eq!(*input, "rest");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_union() -> Result<(), Box<dyn std::error::Error>> {
+        let input = new_span(
+            r#"
+            /**
+             * JavaDoc of Cucumber
+             */
+            union Cucumber {
+                /**
+                 * member1 docu
+                 */
+                int member1;
+
+                String member2; // inline comment
+            }rest"#,
+        );
+        let (input, union) = parse_item(input)?;
+
+        assert_eq!(
+            union,
+            Item::Union {
+                name: "Cucumber".to_string(),
+                docu: "JavaDoc of Cucumber".to_string(),
+                members: vec![
+                    Member::new(
+                        "member1",
+                        Type::with_name("int"),
+                        None,
+                        "member1 docu".to_string(),
+                        Vec::new(),
+                        NodeRange::default(),
+                    ),
+                    Member::new(
+                        "member2",
+                        Type::with_name("String"),
+                        None,
+                        String::new(),
+                        Vec::new(),
+                        NodeRange::default()
+                    )
+                ],
+                annotations: Vec::new(),
+                location: NodeRange::default(),
             }
         );
         assert_eq!(*input, "rest");
 
         Ok(())
     }
+
+    #[test]
+    fn test_parse_with_recovery() -> Result<(), Box<dyn std::error::Error>> {
+        let (file, errors) = parse_with_recovery(
+            r#"
+            package a.b;
+
+            interface Potato {
+                String method1();
+                completely_unexpected;
+                int method2();
+            }
+
+            parcelable Tomato {
+                int member1;
+                int member2 = ;
+                parcelable Nested { String nested1; also unexpected }
+                String member3;
+            }
+
+            union Cucumber { garbage }
+
+            what is this { int a; }
+
+            enum Carrot { ORANGE }
+            "#,
+        );
+
+        let items = file.unwrap().items;
+        assert_eq!(items.len(), 4);
+
+        if let Item::Interface { ref methods, .. } = items[0] {
+            assert_eq!(methods.len(), 2);
+        } else {
+            assert!(false);
+        }
+
+        if let Item::Parcelable {
+            ref members,
+            ref nested_items,
+            ..
+        } = items[1]
+        {
+            assert_eq!(members.len(), 2);
+            assert_eq!(nested_items.len(), 1);
+        } else {
+            assert!(false);
+        }
+
+        assert!(matches!(items[2], Item::Union { .. }));
+        assert!(matches!(items[3], Item::Enum { .. }));
+        assert_eq!(errors.len(), 5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_diagnostics() -> Result<(), Box<dyn std::error::Error>> {
+        let source = "package a.b;\n\ninterface Potato {\n    String method1(int a) = 3\n}\n";
+        let diagnostic = parse(source).unwrap_err();
+        assert_eq!(diagnostic.code, crate::parser::error::EXPECTED_TOKEN);
+        assert_eq!(diagnostic.message, "expected ';' after method declaration");
+        assert_eq!(
+            diagnostic.range.unwrap().start,
+            crate::diagnostic::SourcePosition::new(4, 30)
+        );
+        assert_eq!(
+            diagnostic.source_line.as_deref(),
+            Some("    String method1(int a) = 3")
+        );
+
+        let (_, diagnostics) = parse_with_recovery(
+            "package a.b;\ninterface Potato {\n    const int A = ;\n    void method2();\n}\n",
+        );
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].range.unwrap().start.line, 3);
+        assert!(diagnostics[0].message.ends_with("in constant declaration"));
+
+        // The context is the one of the failing element, not of the last
+        // alternative tried (e.g.: "in union declaration")
+        let diagnostic = parse("package a.b;\ninterface IFoo {}\nfoo bar;\n").unwrap_err();
+        assert_eq!(diagnostic.code, crate::parser::error::UNEXPECTED_INPUT);
+        assert_eq!(diagnostic.message, "unexpected input 'foo bar'");
+        assert_eq!(diagnostic.range.unwrap().start.line, 3);
+
+        let diagnostic = parse("package a.b;\nenum E { A, B C }\n").unwrap_err();
+        assert_eq!(diagnostic.message, "expected '}' in enum declaration");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_locations() -> Result<(), Box<dyn std::error::Error>> {
+        let file = parse(
+            "package a.b;\n\n/** Docu */\ninterface Potato {\n    const int A = 1;\n    void method1(in int a, String b);\n}\n\nenum Carrot { ORANGE, PURPLE }\n",
+        )?;
+        let pos = |line, column| crate::diagnostic::SourcePosition::new(line, column);
+
+        if let Item::Interface {
+            ref consts,
+            ref methods,
+            ref location,
+            ..
+        } = file.items[0]
+        {
+            // The javadoc is not part of the location
+            assert_eq!((location.start, location.end), (pos(4, 1), pos(7, 2)));
+            assert_eq!(
+                (consts[0].location.start, consts[0].location.end),
+                (pos(5, 5), pos(5, 21))
+            );
+
+            let method = &methods[0];
+            assert_eq!(
+                (method.location.start, method.location.end),
+                (pos(6, 5), pos(6, 38))
+            );
+            assert_eq!(
+                (method.args[0].location.start, method.args[0].location.end),
+                (pos(6, 18), pos(6, 26))
+            );
+            assert_eq!(
+                (method.args[1].location.start, method.args[1].location.end),
+                (pos(6, 28), pos(6, 36))
+            );
+        } else {
+            assert!(false);
+        }
+
+        if let Item::Enum {
+            ref elements,
+            ref location,
+            ..
+        } = file.items[1]
+        {
+            assert_eq!((location.start, location.end), (pos(9, 1), pos(9, 31)));
+            assert_eq!(
+                (elements[1].location.start, elements[1].location.end),
+                (pos(9, 23), pos(9, 29))
+            );
+        } else {
+            assert!(false);
+        }
+
+        Ok(())
+    }
 }
@@ -1,27 +1,26 @@
 use strum_macros::EnumIter;
 
+use crate::model::TypeCategory;
+
 #[derive(Debug, EnumIter)]
 pub enum Primitive {
     Void,
     Bool,
-    BoolArray,
     Char,
-    CharArray,
     Byte,
-    ByteArray,
     Int,
-    IntArray,
     Long,
-    LongArray,
     Float,
-    FloatArray,
     Double,
-    DoubleArray,
     String,
+    CharSequence,
     Vector,
     Map,
-    Array,
     List,
+    IBinder,
+    FileDescriptor,
+    ParcelFileDescriptor,
+    ParcelableHolder,
 }
 
 impl Primitive {
@@ -29,24 +28,49 @@ impl Primitive {
         match self {
             Primitive::Void => "void",
             Primitive::Bool => "boolean",
-            Primitive::BoolArray => "boolean[]",
             Primitive::Char => "char",
-            Primitive::CharArray => "char[]",
             Primitive::Byte => "byte",
-            Primitive::ByteArray => "byte[]",
             Primitive::Int => "int",
-            Primitive::IntArray => "int[]",
             Primitive::Long => "long",
-            Primitive::LongArray => "long[]",
             Primitive::Float => "float",
-            Primitive::FloatArray => "float[]",
             Primitive::Double => "double",
-            Primitive::DoubleArray => "double[]",
             Primitive::String => "String",
+            Primitive::CharSequence => "CharSequence",
             Primitive::Vector => "Vector",
             Primitive::Map => "Map",
             Primitive::List => "List",
-            Primitive::Array => "Array",
+            Primitive::IBinder => "IBinder",
+            Primitive::FileDescriptor => "FileDescriptor",
+            Primitive::ParcelFileDescriptor => "ParcelFileDescriptor",
+            Primitive::ParcelableHolder => "ParcelableHolder",
+        }
+    }
+
+    pub fn get_category(&self) -> TypeCategory {
+        match self {
+            Primitive::Void => TypeCategory::Void,
+            Primitive::Bool
+            | Primitive::Char
+            | Primitive::Byte
+            | Primitive::Int
+            | Primitive::Long
+            | Primitive::Float
+            | Primitive::Double => TypeCategory::Primitive,
+            Primitive::String | Primitive::CharSequence => TypeCategory::String,
+            Primitive::Vector | Primitive::Map | Primitive::List => TypeCategory::Container,
+            Primitive::IBinder => TypeCategory::Binder,
+            Primitive::FileDescriptor | Primitive::ParcelFileDescriptor => {
+                TypeCategory::FileDescriptor
+            }
+            Primitive::ParcelableHolder => TypeCategory::Parcelable,
+        }
+    }
+
+    pub fn get_generic_arity(&self) -> usize {
+        match self {
+            Primitive::Vector | Primitive::List => 1,
+            Primitive::Map => 2,
+            _ => 0,
         }
     }
 }
@@ -1,52 +1,152 @@
 use std::path::PathBuf;
 
-use nom_locate::LocatedSpan;
+use nom::error::{ContextError, ErrorKind, FromExternalError, ParseError};
+
+use crate::diagnostic::{Diagnostic, Severity};
+
+// Error codes of the parse diagnostics
+pub const EXPECTED_TOKEN: &str = "P001";
+pub const UNEXPECTED_INPUT: &str = "P002";
+pub const INVALID_SYNTAX: &str = "P004";
+pub const UNSUPPORTED_VERSION: &str = "P005";
 
 #[derive(Debug)]
 pub struct ParseFileError {
     pub path: PathBuf,
-    pub content_error: ParseContentError,
+    pub diagnostics: Vec<Diagnostic>,
 }
 
 impl std::fmt::Display for ParseFileError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        // TODO: simple message!!!
-        write!(f, "Parse error: {:#?}", *self)
+        for diagnostic in &self.diagnostics {
+            writeln!(f, "{}", diagnostic)?;
+        }
+
+        Ok(())
     }
 }
 
 impl std::error::Error for ParseFileError {}
 
-#[derive(Debug)]
-pub struct ParseContentError {
-    line_number: u32,
-    column_number: usize,
-    section: String,
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SyntaxErrorKind {
+    ExpectedChar(char),
+    ExpectedIdentifier,
+    UnexpectedInput,
+    Nom(ErrorKind),
 }
 
-impl std::fmt::Display for ParseContentError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        // TODO: simple message!!!
-        write!(f, "Parse error: {:#?}", *self)
+// Parser error keeping what was expected and in which context (the innermost
+// one, e.g. "method declaration")
+#[derive(Debug, PartialEq)]
+pub struct SyntaxError<I> {
+    pub input: I,
+    pub kind: SyntaxErrorKind,
+    pub context: Option<&'static str>,
+}
+
+impl<I> SyntaxError<I> {
+    pub fn new(input: I, kind: SyntaxErrorKind) -> Self {
+        SyntaxError {
+            input,
+            kind,
+            context: None,
+        }
+    }
+
+    pub fn map_input<J, F: FnOnce(I) -> J>(self, f: F) -> SyntaxError<J> {
+        SyntaxError {
+            input: f(self.input),
+            kind: self.kind,
+            context: self.context,
+        }
+    }
+}
+
+impl<I> ParseError<I> for SyntaxError<I> {
+    fn from_error_kind(input: I, kind: ErrorKind) -> Self {
+        Self::new(input, SyntaxErrorKind::Nom(kind))
+    }
+
+    fn append(_input: I, _kind: ErrorKind, other: Self) -> Self {
+        other
+    }
+
+    fn from_char(input: I, c: char) -> Self {
+        Self::new(input, SyntaxErrorKind::ExpectedChar(c))
     }
 }
 
-impl std::error::Error for ParseContentError {}
+impl<I> ContextError<I> for SyntaxError<I> {
+    fn add_context(_input: I, context: &'static str, mut other: Self) -> Self {
+        if other.context.is_none() {
+            other.context = Some(context);
+        }
 
-impl<'a> From<nom::error::Error<LocatedSpan<&'a str>>> for ParseContentError {
-    fn from(error: nom::error::Error<LocatedSpan<&'a str>>) -> Self {
-        let span = error.input;
+        other
+    }
+}
 
-        ParseContentError {
-            line_number: span.location_line(),
-            column_number: span.get_column(),
-            section: {
-                if span.fragment().len() < 30 {
-                    span.fragment().to_string()
-                } else {
-                    format!("{}...", &span.fragment()[0..27])
-                }
-            },
+impl<I, E> FromExternalError<I, E> for SyntaxError<I> {
+    fn from_external_error(input: I, kind: ErrorKind, _e: E) -> Self {
+        Self::from_error_kind(input, kind)
+    }
+}
+
+// The input is the byte offset of the error in the source
+impl SyntaxError<usize> {
+    pub fn to_diagnostic(&self, source: &str) -> Diagnostic {
+        let mut offset = self.input.min(source.len());
+        if self.kind == SyntaxErrorKind::ExpectedChar(';') {
+            // Point right after the previous token rather than at the next line
+            offset = source[..offset].trim_end().len();
         }
+        let rest = &source[offset..];
+
+        let in_context = self
+            .context
+            .map(|c| format!(" in {}", c))
+            .unwrap_or_default();
+
+        // Length of the invalid input to underline
+        let token_length = rest
+            .find(|c: char| !c.is_alphanumeric() && c != '_')
+            .unwrap_or(rest.len())
+            .max(rest.chars().next().map(char::len_utf8).unwrap_or(0));
+        let statement_length = rest.find(&['\n', ';', '}'][..]).unwrap_or(rest.len());
+
+        let (code, message, length) = match self.kind {
+            SyntaxErrorKind::ExpectedChar(c) => {
+                let message = match self.context {
+                    Some(context) if c == ';' => format!("expected ';' after {}", context),
+                    _ => format!("expected '{}'{}", c, in_context),
+                };
+                (EXPECTED_TOKEN, message, token_length)
+            }
+            SyntaxErrorKind::ExpectedIdentifier => (
+                EXPECTED_TOKEN,
+                format!("expected an identifier{}", in_context),
+                token_length,
+            ),
+            SyntaxErrorKind::UnexpectedInput => (
+                UNEXPECTED_INPUT,
+                format!(
+                    "unexpected input '{}'{}",
+                    rest[..statement_length].trim_end(),
+                    in_context
+                ),
+                statement_length,
+            ),
+            SyntaxErrorKind::Nom(_) => (
+                INVALID_SYNTAX,
+                format!("invalid syntax{}", in_context),
+                token_length,
+            ),
+        };
+
+        let mut diagnostic = Diagnostic::new(Severity::Error, code, message);
+        diagnostic.set_location(source, offset, offset + length);
+
+        diagnostic
     }
 }
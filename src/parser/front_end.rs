@@ -0,0 +1,182 @@
+use std::path::Path;
+
+use crate::diagnostic::Diagnostic;
+use crate::model::Model;
+use crate::parser::{aidl, hidl, openapi, protobuf};
+
+// Reads the files of an IDL: they are parsed one by one, then the model is
+// created from all of them (the items of a file can depend on the items of
+// another one)
+pub trait FrontEnd {
+    // e.g.: ["aidl"]
+    fn get_extensions(&self) -> &[&'static str];
+
+    fn matches(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| self.get_extensions().contains(&ext))
+    }
+
+    // Keeps what could be parsed for create_model and returns the diagnostics
+    // of the file (empty if it is valid), or None if the file is not written
+    // in this IDL (e.g.: a YAML file which is not an OpenAPI document)
+    fn parse(&mut self, path: &str, source: &str) -> Option<Vec<Diagnostic>>;
+
+    // Model of the files parsed so far, which are then forgotten
+    fn create_model(&mut self) -> Model;
+}
+
+// Front-ends of the IDLs which can be loaded together, a file going to the
+// first front-end accepting it
+pub struct Registry {
+    front_ends: Vec<Box<dyn FrontEnd>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Registry {
+            front_ends: Vec::new(),
+        }
+    }
+
+    pub fn register(&mut self, front_end: Box<dyn FrontEnd>) {
+        self.front_ends.push(front_end);
+    }
+
+    pub fn matches(&self, path: &Path) -> bool {
+        self.front_ends.iter().any(|fe| fe.matches(path))
+    }
+
+    // Diagnostics of the file (e.g.: syntax errors), empty if no front-end
+    // accepted it
+    pub fn parse(&mut self, path: &Path, source: &str) -> Vec<Diagnostic> {
+        let path_string = path.to_string_lossy().to_string();
+
+        self.front_ends
+            .iter_mut()
+            .filter(|fe| fe.matches(path))
+            .find_map(|fe| fe.parse(&path_string, source))
+            .unwrap_or_default()
+    }
+
+    // The models of the front-ends are merged into one (the types are
+    // resolved separately, see Model::resolve_types)
+    pub fn create_model(&mut self) -> Model {
+        let mut model = Model::default();
+        self.front_ends
+            .iter_mut()
+            .for_each(|fe| model.merge(fe.create_model()));
+
+        model
+    }
+}
+
+// All the supported IDLs
+impl Default for Registry {
+    fn default() -> Self {
+        let mut registry = Registry::new();
+        registry.register(Box::new(aidl::AidlFrontEnd::default()));
+        registry.register(Box::new(protobuf::ProtobufFrontEnd::default()));
+        registry.register(Box::new(openapi::OpenApiFrontEnd::default()));
+        registry.register(Box::new(hidl::HidlFrontEnd::default()));
+
+        registry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Idl, Type};
+
+    #[test]
+    fn test_registry() {
+        let mut registry = Registry::default();
+
+        assert!(registry.matches(Path::new("a/b/IFoo.aidl")));
+        assert!(registry.matches(Path::new("foo.proto")));
+        assert!(registry.matches(Path::new("api.yml")));
+        assert!(registry.matches(Path::new("foo/1.0/IFoo.hal")));
+        assert!(!registry.matches(Path::new("README.md")));
+
+        let diagnostics = registry.parse(Path::new("IFoo.aidl"), "package a; interface IFoo {}");
+        assert!(diagnostics.is_empty());
+        let diagnostics = registry.parse(Path::new("foo.proto"), "package b; message Foo {}");
+        assert!(diagnostics.is_empty());
+        let diagnostics = registry.parse(Path::new("config.yaml"), "foo: bar");
+        assert!(diagnostics.is_empty());
+        let diagnostics = registry.parse(Path::new("bar.proto"), "package b; message {}");
+        assert_eq!(diagnostics.len(), 1);
+
+        let model = registry.create_model();
+        let idls: Vec<(&str, Idl)> = ["a.IFoo", "b.Foo"]
+            .iter()
+            .map(|key| (*key, model.items[*key].get_idl()))
+            .collect();
+        assert_eq!(idls, vec![("a.IFoo", Idl::Aidl), ("b.Foo", Idl::Protobuf)]);
+        assert_eq!(model.items.len(), 2);
+
+        // The parsed files are forgotten
+        assert!(registry.create_model().items.is_empty());
+    }
+
+    #[test]
+    fn test_standard_types_per_idl() {
+        let mut registry = Registry::default();
+        let sources = [
+            (
+                "IFoo.aidl",
+                "package a; parcelable P { String a; string b; uint32_t c; bytes d; }",
+            ),
+            (
+                "foo.proto",
+                "package b; message M { string a = 1; bytes b = 2; }",
+            ),
+            (
+                "types.hal",
+                "package c@1.0; struct S { string a; uint32_t b; };",
+            ),
+        ];
+        for (path, source) in sources {
+            assert!(registry.parse(Path::new(path), source).is_empty());
+        }
+
+        let mut model = registry.create_model();
+        let unresolved_types: Vec<String> = model
+            .resolve_types()
+            .iter()
+            .map(|d| d.key.clone())
+            .collect();
+
+        // The standard types of an IDL are not found from the other IDLs
+        let get_standard_types = |key: &str| -> Vec<Option<(Idl, String)>> {
+            model.items[key]
+                .get_members()
+                .iter()
+                .map(|m| match *m.arg.arg_type {
+                    Type::Standard(ref st) => Some((st.package.idl, st.name.clone())),
+                    _ => None,
+                })
+                .collect()
+        };
+        assert_eq!(
+            get_standard_types("a.P"),
+            vec![Some((Idl::Aidl, "String".to_string())), None, None, None]
+        );
+        assert_eq!(
+            get_standard_types("b.M"),
+            vec![
+                Some((Idl::Protobuf, "string".to_string())),
+                Some((Idl::Protobuf, "bytes".to_string()))
+            ]
+        );
+        assert_eq!(
+            get_standard_types("c@1.0.S"),
+            vec![
+                Some((Idl::Hidl, "string".to_string())),
+                Some((Idl::Hidl, "uint32_t".to_string()))
+            ]
+        );
+        assert_eq!(unresolved_types, vec!["a.P"; 3]);
+    }
+}
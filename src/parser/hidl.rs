@@ -0,0 +1,711 @@
+pub mod ast;
+mod parse;
+mod scalar;
+
+use crate::diagnostic::Diagnostic;
+use crate::model::{self, Model};
+use crate::parser::aidl::{create_model_annotations, create_model_expression};
+use crate::parser::front_end::FrontEnd;
+pub use parse::parse;
+use scalar::Scalar;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use strum::IntoEnumIterator;
+
+#[derive(Default)]
+pub struct HidlFrontEnd {
+    files: Vec<ast::File>,
+}
+
+impl FrontEnd for HidlFrontEnd {
+    fn get_extensions(&self) -> &[&'static str] {
+        &["hal"]
+    }
+
+    fn parse(&mut self, path: &str, source: &str) -> Option<Vec<Diagnostic>> {
+        match parse(source) {
+            Ok(mut file) => {
+                file.path = Some(path.to_string());
+                self.files.push(file);
+                Some(Vec::new())
+            }
+            Err(diagnostic) => Some(vec![*diagnostic]),
+        }
+    }
+
+    fn create_model(&mut self) -> Model {
+        create_model(std::mem::take(&mut self.files))
+    }
+}
+
+// The items keep their HIDL kind (typedefs are structs with a single value
+// member) and are keyed by their versioned package (e.g.:
+// android.hardware.foo@1.0.IFoo), so that several versions can be browsed
+pub fn create_model(files: Vec<ast::File>) -> Model {
+    let mut model = Model::default();
+
+    // Root package
+    let root_pkg = Rc::new(model::Package::new("", model::Idl::Hidl));
+
+    // Keys of all the declared items, to resolve the type names
+    let mut keys = HashSet::new();
+    files
+        .iter()
+        .for_each(|file| collect_keys(&file.package, &file.items, &mut keys));
+
+    // Types of the enums (a scalar or the enum they extend), to find the
+    // scalars backing the enums
+    let no_backing_types = HashMap::new();
+    let mut backing_types = HashMap::new();
+    files.iter().for_each(|file| {
+        let context = create_context(file, &keys, &no_backing_types);
+        collect_backing_types(&context, &file.package, &file.items, &mut backing_types);
+    });
+
+    // Go through files
+    files.into_iter().for_each(|mut file| {
+        let items = std::mem::take(&mut file.items);
+        let context = create_context(&file, &keys, &backing_types);
+        add_model_items(&mut model, &context, None, items);
+    });
+
+    // Create standard types
+    let standard_types = Scalar::iter()
+        .map(|s| {
+            model::StandardType::new(
+                s.get_name(),
+                s.get_category(),
+                s.get_generic_arity(),
+                root_pkg.clone(),
+            )
+        })
+        .map(|st| (st.get_key().clone(), Rc::new(st)))
+        .collect();
+    model
+        .standard_types
+        .insert(model::Idl::Hidl, standard_types);
+
+    // The types are resolved separately (see Model::resolve_types)
+    model
+}
+
+// What the items of a file share
+struct Context<'a> {
+    pkg: Rc<model::Package>,
+    path: Option<&'a str>,
+    imports: &'a [String],
+    keys: &'a HashSet<model::Key>,
+    backing_types: &'a HashMap<model::Key, String>,
+}
+
+fn create_context<'a>(
+    file: &'a ast::File,
+    keys: &'a HashSet<model::Key>,
+    backing_types: &'a HashMap<model::Key, String>,
+) -> Context<'a> {
+    Context {
+        pkg: Rc::new(model::Package::new(&file.package, model::Idl::Hidl)),
+        path: file.path.as_deref(),
+        imports: &file.imports,
+        keys,
+        backing_types,
+    }
+}
+
+impl Context<'_> {
+    // HIDL names, the first matching rule wins (names not found are kept as
+    // written):
+    // 1. fully qualified names (e.g.: a.b@1.0::Foo, or @1.0::Foo for another
+    //    version of the same package)
+    // 2. items nested in the scope or in one of its outer items, then the
+    //    items of the package
+    // 3. imported items (e.g.: import a.b@1.0::IFoo; or @1.0::IFoo; for
+    //    IFoo.Bar) and items of imported packages (e.g.: import a.b@1.0; or
+    //    a.b@1.0::types;)
+    fn resolve_name(&self, name: &str, scope: &str) -> String {
+        if Scalar::iter().any(|s| s.get_name() == name) {
+            return name.to_string();
+        }
+
+        if let Some((pkg, qualified_name)) = name.split_once("::") {
+            let key = match pkg.strip_prefix('@') {
+                Some(version) => format!("{}@{}.{}", self.get_pkg_name(), version, qualified_name),
+                None => format!("{}.{}", pkg, qualified_name),
+            };
+
+            return if self.keys.contains(&key) {
+                key
+            } else {
+                name.to_string()
+            };
+        }
+
+        let mut current_scope = Some(scope);
+        while let Some(scope) = current_scope {
+            let key = format!("{}.{}", scope, name);
+            if self.keys.contains(&key) {
+                return key;
+            }
+
+            current_scope = match scope == self.pkg.path {
+                true => None,
+                false => scope.rfind('.').map(|pos| &scope[..pos]),
+            };
+        }
+
+        let first_name = name.split('.').next().unwrap_or_default();
+        self.imports
+            .iter()
+            .filter_map(|import| match import.split_once("::") {
+                None if import.contains('@') => Some(format!("{}.{}", import, name)),
+                Some((pkg, "types")) => Some(format!("{}.{}", pkg, name)),
+                Some((pkg, imported_name)) if imported_name == first_name => {
+                    match pkg.strip_prefix('@') {
+                        Some(version) => {
+                            Some(format!("{}@{}.{}", self.get_pkg_name(), version, name))
+                        }
+                        None => Some(format!("{}.{}", pkg, name)),
+                    }
+                }
+                // Items of the same package (e.g.: import IFooCallback;) are
+                // already found
+                _ => None,
+            })
+            .find(|key| self.keys.contains(key))
+            .unwrap_or_else(|| name.to_string())
+    }
+
+    // e.g.: android.hardware.foo for android.hardware.foo@1.0
+    fn get_pkg_name(&self) -> &str {
+        self.pkg.path.split('@').next().unwrap_or_default()
+    }
+
+    // The scalar of an enum following the enums it extends (e.g.: uint32_t
+    // for enum Foo : @1.0::Foo and enum Foo : uint32_t in @1.0)
+    fn get_backing_scalar(&self, type_name: &str) -> String {
+        let mut type_name = type_name;
+        let mut visited = HashSet::new();
+
+        while let Some(parent_type) = self.backing_types.get(type_name) {
+            if !visited.insert(type_name) {
+                break;
+            }
+            type_name = parent_type;
+        }
+
+        type_name.to_string()
+    }
+}
+
+// Same keys as the model items (e.g.: a.b@1.0.IFoo.Bar)
+fn collect_keys(scope: &str, items: &[ast::Item], keys: &mut HashSet<model::Key>) {
+    items.iter().for_each(|item| {
+        let key = format!("{}.{}", scope, get_name(item));
+        collect_keys(&key, get_nested_items(item), keys);
+        keys.insert(key);
+    });
+}
+
+fn collect_backing_types(
+    context: &Context,
+    scope: &str,
+    items: &[ast::Item],
+    backing_types: &mut HashMap<model::Key, String>,
+) {
+    items.iter().for_each(|item| {
+        let key = format!("{}.{}", scope, get_name(item));
+        if let ast::Item::Enum { backing_type, .. } = item {
+            backing_types.insert(key.clone(), context.resolve_name(&backing_type.name, scope));
+        }
+        collect_backing_types(context, &key, get_nested_items(item), backing_types);
+    });
+}
+
+fn get_name(item: &ast::Item) -> &str {
+    match item {
+        ast::Item::Interface { name, .. }
+        | ast::Item::Struct { name, .. }
+        | ast::Item::Union { name, .. }
+        | ast::Item::Enum { name, .. }
+        | ast::Item::Typedef { name, .. } => name,
+    }
+}
+
+fn get_nested_items(item: &ast::Item) -> &[ast::Item] {
+    match item {
+        ast::Item::Interface { nested_items, .. }
+        | ast::Item::Struct { nested_items, .. }
+        | ast::Item::Union { nested_items, .. } => nested_items,
+        ast::Item::Enum { .. } | ast::Item::Typedef { .. } => &[],
+    }
+}
+
+fn add_model_items(
+    model: &mut Model,
+    context: &Context,
+    parent: Option<&model::Key>,
+    ast_items: Vec<ast::Item>,
+) {
+    ast_items.into_iter().for_each(|item| match item {
+        ast::Item::Interface {
+            name,
+            docu,
+            extends,
+            methods,
+            nested_items,
+            annotations,
+            location,
+        } => {
+            let key = model::item_key(&context.pkg, parent, &name);
+            let interface = model::Interface::new(
+                context.pkg.clone(),
+                parent.cloned(),
+                Vec::new(),
+                name,
+                extends
+                    .iter()
+                    .map(|e| {
+                        Rc::new(create_model_type(
+                            context,
+                            &context.resolve_name(e, &key),
+                            &[],
+                        ))
+                    })
+                    .collect(),
+                model::Javadoc::parse(&docu),
+                Vec::new(),
+                methods
+                    .iter()
+                    .enumerate()
+                    .map(|(index, m)| create_model_method(index, context, &key, m))
+                    .collect(),
+                create_model_annotations(&annotations),
+                model::Location::new(context.path, location.0),
+            );
+
+            model.insert_item(model::Item::Interface(interface));
+            add_model_items(model, context, Some(&key), nested_items);
+        }
+        ast::Item::Struct {
+            name,
+            docu,
+            fields,
+            nested_items,
+            annotations,
+            location,
+        } => {
+            let key = model::item_key(&context.pkg, parent, &name);
+            let strukt = model::Struct::new(
+                context.pkg.clone(),
+                parent.cloned(),
+                Vec::new(),
+                name,
+                Vec::new(),
+                model::Javadoc::parse(&docu),
+                create_model_members(context, &key, &fields),
+                create_model_annotations(&annotations),
+                model::Location::new(context.path, location.0),
+            );
+
+            model.insert_item(model::Item::Struct(strukt));
+            add_model_items(model, context, Some(&key), nested_items);
+        }
+        ast::Item::Union {
+            name,
+            docu,
+            is_safe,
+            fields,
+            nested_items,
+            annotations,
+            location,
+        } => {
+            let key = model::item_key(&context.pkg, parent, &name);
+            let mut model_annotations = create_model_annotations(&annotations);
+            if is_safe {
+                model_annotations.push(model::Annotation::new("safe_union", Vec::new()));
+            }
+
+            let union = model::Union::new(
+                context.pkg.clone(),
+                parent.cloned(),
+                Vec::new(),
+                name,
+                model::Javadoc::parse(&docu),
+                create_model_members(context, &key, &fields),
+                model_annotations,
+                model::Location::new(context.path, location.0),
+            );
+
+            model.insert_item(model::Item::Union(union));
+            add_model_items(model, context, Some(&key), nested_items);
+        }
+        ast::Item::Enum {
+            name,
+            docu,
+            values,
+            annotations,
+            location,
+            ..
+        } => {
+            let key = model::item_key(&context.pkg, parent, &name);
+            let mut model_annotations = create_model_annotations(&annotations);
+
+            // An enum extending another one is backed by the same scalar
+            let backing_type_name = context.get_backing_scalar(&key);
+            if let Some(extended_type_name) = context
+                .backing_types
+                .get(&key)
+                .filter(|t| context.keys.contains(*t))
+            {
+                model_annotations.push(model::Annotation::new(
+                    "extends",
+                    vec![model::AnnotationParam::new(
+                        "value",
+                        model::Expression::Reference(extended_type_name.clone()),
+                    )],
+                ));
+            }
+
+            let enumeration = model::Enum::new(
+                context.pkg.clone(),
+                parent.cloned(),
+                Vec::new(),
+                name,
+                model::Javadoc::parse(&docu),
+                Rc::new(create_model_type(context, &backing_type_name, &[])),
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(index, v)| model::EnumElement {
+                        name: v.name.clone(),
+                        value: v.value.as_ref().map(create_model_expression),
+                        computed_value: None,
+                        index,
+                        docu: model::Javadoc::parse(&v.docu),
+                        location: model::Location::new(context.path, v.location.0),
+                    })
+                    .collect(),
+                model_annotations,
+                model::Location::new(context.path, location.0),
+            );
+
+            model.insert_item(model::Item::Enum(enumeration));
+        }
+        // Example:
+        // - typedef vec<uint8_t> Bytes; => struct Bytes { vec<uint8_t> value; }
+        ast::Item::Typedef {
+            name,
+            docu,
+            aliased_type,
+            annotations,
+            location,
+        } => {
+            let key = model::item_key(&context.pkg, parent, &name);
+            let location = model::Location::new(context.path, location.0);
+
+            let member = model::Member::new(
+                create_model_arg(
+                    context,
+                    &key,
+                    &aliased_type,
+                    model::Direction::Unspecified,
+                    "value",
+                    Vec::new(),
+                    location.clone(),
+                ),
+                None,
+                None,
+                0,
+                model::Javadoc::default(),
+                Vec::new(),
+                location.clone(),
+            );
+
+            let strukt = model::Struct::new(
+                context.pkg.clone(),
+                parent.cloned(),
+                Vec::new(),
+                name,
+                Vec::new(),
+                model::Javadoc::parse(&docu),
+                vec![member],
+                create_model_annotations(&annotations),
+                location,
+            );
+
+            model.insert_item(model::Item::Struct(strukt));
+        }
+    });
+}
+
+fn create_model_members(
+    context: &Context,
+    scope: &model::Key,
+    fields: &[ast::Field],
+) -> Vec<model::Member> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(index, f)| {
+            let location = model::Location::new(context.path, f.location.0);
+
+            model::Member::new(
+                create_model_arg(
+                    context,
+                    scope,
+                    &f.field_type,
+                    model::Direction::Unspecified,
+                    &f.name,
+                    Vec::new(),
+                    location.clone(),
+                ),
+                None,
+                None,
+                index,
+                model::Javadoc::parse(&f.docu),
+                create_model_annotations(&f.annotations),
+                location,
+            )
+        })
+        .collect()
+}
+
+// Examples:
+// - oneway notify(Event event); => oneway void notify(in Event event)
+// - get() generates (Foo foo); => Foo get()
+// - get() generates (Status status, Foo foo);
+//   => void get(out Status status, out Foo foo)
+fn create_model_method(
+    index: usize,
+    context: &Context,
+    scope: &model::Key,
+    method: &ast::Method,
+) -> model::Method {
+    let location = model::Location::new(context.path, method.location.0);
+
+    let create_args = |fields: &[ast::Field], direction: model::Direction| {
+        fields
+            .iter()
+            .map(|f| {
+                create_model_arg(
+                    context,
+                    scope,
+                    &f.field_type,
+                    direction,
+                    &f.name,
+                    create_model_annotations(&f.annotations),
+                    model::Location::new(context.path, f.location.0),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut args = create_args(&method.args, model::Direction::In);
+    let return_arg = match method.results.len() {
+        0 => create_model_arg(
+            context,
+            scope,
+            &ast::Type::new(Scalar::Void.get_name()),
+            model::Direction::Unspecified,
+            "",
+            Vec::new(),
+            location.clone(),
+        ),
+        1 => create_args(&method.results, model::Direction::Unspecified).remove(0),
+        _ => {
+            args.extend(create_args(&method.results, model::Direction::Out));
+            create_model_arg(
+                context,
+                scope,
+                &ast::Type::new(Scalar::Void.get_name()),
+                model::Direction::Unspecified,
+                "",
+                Vec::new(),
+                location.clone(),
+            )
+        }
+    };
+
+    model::Method::new(
+        method.is_oneway,
+        &method.name,
+        return_arg,
+        args,
+        model::TransactionCode::Implicit(index as u32),
+        index,
+        model::Javadoc::parse(&method.docu),
+        create_model_annotations(&method.annotations),
+        location,
+    )
+}
+
+// Generic args are args (e.g.: vec<Foo>), recursively
+fn create_model_arg(
+    context: &Context,
+    scope: &model::Key,
+    arg_type: &ast::Type,
+    direction: model::Direction,
+    name: &str,
+    annotations: Vec<model::Annotation>,
+    location: model::Location,
+) -> model::Arg {
+    let generic_args = arg_type
+        .generic_args
+        .iter()
+        .map(|t| {
+            create_model_arg(
+                context,
+                scope,
+                t,
+                model::Direction::Unspecified,
+                "",
+                Vec::new(),
+                location.clone(),
+            )
+        })
+        .collect();
+
+    model::Arg::new(
+        direction,
+        name.to_string(),
+        Rc::new(create_model_type(
+            context,
+            &context.resolve_name(&arg_type.name, scope),
+            &arg_type.array_dimensions,
+        )),
+        generic_args,
+        annotations,
+        location,
+    )
+}
+
+// Arrays have fixed dimensions (e.g.: uint8_t[16])
+fn create_model_type(
+    context: &Context,
+    type_name: &str,
+    array_dimensions: &[String],
+) -> model::Type {
+    let element_type =
+        model::Type::Unresolved(model::UnresolvedType::new(context.pkg.clone(), type_name));
+
+    if array_dimensions.is_empty() {
+        element_type
+    } else {
+        model::Type::Array(model::ArrayType::new(
+            Rc::new(element_type),
+            array_dimensions
+                .iter()
+                .map(|d| model::ArrayDimension::Fixed(d.clone()))
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inheritance() -> Result<(), Box<dyn std::error::Error>> {
+        let files = vec![
+            parse("package a@1.0;\ninterface IBase { ping(); };\n")?,
+            parse("package a@1.0;\ninterface IFoo extends IBase { foo(); bar(); };\n")?,
+            parse(
+                "package a@1.1;\nimport @1.0::IFoo;\n\
+                 interface IFoo extends @1.0::IFoo { baz(@1.0::IFoo foo); };\n",
+            )?,
+        ];
+        let mut model = create_model(files);
+        assert!(model.resolve_types().is_empty());
+
+        let foo = model.items["a@1.1.IFoo"].clone();
+        let interface = match *foo {
+            model::Item::Interface(ref i) => i,
+            _ => panic!("expected an interface"),
+        };
+
+        // Nearest super-interfaces first, numbered after the methods
+        let inherited_methods: Vec<(usize, String, model::Key)> = model
+            .find_inherited_methods(interface)
+            .iter()
+            .map(|m| (m.index, m.get_method().name.clone(), m.via.clone()))
+            .collect();
+        assert_eq!(
+            inherited_methods,
+            vec![
+                (1, "foo".to_string(), "a@1.0.IFoo".to_string()),
+                (2, "bar".to_string(), "a@1.0.IFoo".to_string()),
+                (3, "ping".to_string(), "a@1.0.IFoo".to_string()),
+            ]
+        );
+
+        // The super-interface is also used by baz
+        let dependencies: Vec<(model::DependencyKind, Vec<usize>, model::Key)> = model
+            .find_dependencies(&foo)
+            .into_iter()
+            .map(|(kind, indices, item)| {
+                let mut indices: Vec<usize> = indices.into_iter().collect();
+                indices.sort();
+                (kind, indices, item.get_key().clone())
+            })
+            .collect();
+        assert_eq!(
+            dependencies,
+            vec![(
+                model::DependencyKind::Inheritance,
+                vec![0, 1, 2, 3],
+                "a@1.0.IFoo".to_string()
+            )]
+        );
+
+        let references = model.find_references(&model.items["a@1.0.IBase"]);
+        assert_eq!(references.len(), 1);
+        assert_eq!(references[0].0, model::DependencyKind::Inheritance);
+        assert_eq!(references[0].2.get_key(), "a@1.0.IFoo");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_enum_backing_types() -> Result<(), Box<dyn std::error::Error>> {
+        let files = vec![parse(
+            "package a@1.0;\n\
+             enum E : uint8_t { A = 255, B };\n\
+             enum F : int16_t { A = -1, B = 32767, C };\n\
+             enum G : uint64_t { A = 0xffffffffffffffff };\n\
+             enum H : bool { A };\n",
+        )?];
+        let mut model = create_model(files);
+        model.resolve_types();
+
+        let diagnostics: Vec<(String, Option<usize>, String)> = model
+            .validate()
+            .into_iter()
+            .map(|d| (d.key, d.member_index, d.diagnostic.message))
+            .collect();
+        assert_eq!(
+            diagnostics,
+            vec![
+                (
+                    "a@1.0.E".to_string(),
+                    Some(1),
+                    "B = 256 does not fit in the backing type uint8_t".to_string()
+                ),
+                (
+                    "a@1.0.F".to_string(),
+                    Some(2),
+                    "C = 32768 does not fit in the backing type int16_t".to_string()
+                ),
+                (
+                    "a@1.0.H".to_string(),
+                    None,
+                    "bool cannot be the backing type of an enum, only int8_t, uint8_t, \
+                     int16_t, uint16_t, int32_t, uint32_t, int64_t or uint64_t"
+                        .to_string()
+                ),
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,120 @@
+// Annotations and constant expressions are written as in AIDL
+pub use crate::parser::aidl::ast::{Annotation, ConstExpr};
+use crate::parser::NodeRange;
+
+#[derive(Debug, PartialEq)]
+pub struct File {
+    // Path of the parsed file, when known
+    pub path: Option<String>,
+    // Versioned package (e.g.: android.hardware.foo@1.0)
+    pub package: String,
+    // e.g.: android.hidl.base@1.0::IBase, android.hardware.bar@1.0
+    pub imports: Vec<String>,
+    pub items: Vec<Item>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Item {
+    Interface {
+        name: String,
+        docu: String,
+        // As written (e.g.: @1.0::IFoo or android.hidl.base@1.0::IBase)
+        extends: Option<String>,
+        methods: Vec<Method>,
+        nested_items: Vec<Item>,
+        annotations: Vec<Annotation>,
+        location: NodeRange,
+    },
+    Struct {
+        name: String,
+        docu: String,
+        fields: Vec<Field>,
+        nested_items: Vec<Item>,
+        annotations: Vec<Annotation>,
+        location: NodeRange,
+    },
+    // Unions and safe unions (which know their active field)
+    Union {
+        name: String,
+        docu: String,
+        is_safe: bool,
+        fields: Vec<Field>,
+        nested_items: Vec<Item>,
+        annotations: Vec<Annotation>,
+        location: NodeRange,
+    },
+    Enum {
+        name: String,
+        docu: String,
+        // A scalar type (e.g.: uint32_t) or the enum extended by this one
+        // (e.g.: @1.0::Foo)
+        backing_type: Type,
+        values: Vec<EnumValue>,
+        annotations: Vec<Annotation>,
+        location: NodeRange,
+    },
+    Typedef {
+        name: String,
+        docu: String,
+        aliased_type: Type,
+        annotations: Vec<Annotation>,
+        location: NodeRange,
+    },
+}
+
+// Examples:
+// - uint8_t[16] (array dimensions as written)
+// - vec<IFoo.Bar>
+// - android.hardware.bar@1.0::IBar
+#[derive(Debug, PartialEq)]
+pub struct Type {
+    pub name: String,
+    pub generic_args: Vec<Type>,
+    pub array_dimensions: Vec<String>,
+}
+
+impl Type {
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Type {
+            name: name.into(),
+            generic_args: Vec::new(),
+            array_dimensions: Vec::new(),
+        }
+    }
+}
+
+// Struct or union field, method argument or result
+#[derive(Debug, PartialEq)]
+pub struct Field {
+    pub name: String,
+    pub docu: String,
+    pub field_type: Type,
+    pub annotations: Vec<Annotation>,
+    pub location: NodeRange,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct EnumValue {
+    pub name: String,
+    pub docu: String,
+    pub value: Option<ConstExpr>,
+    pub location: NodeRange,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Method {
+    pub name: String,
+    pub docu: String,
+    pub is_oneway: bool,
+    pub args: Vec<Field>,
+    // generates (...)
+    pub results: Vec<Field>,
+    pub annotations: Vec<Annotation>,
+    pub location: NodeRange,
+}
+
+pub enum ItemElement {
+    Field(Field),
+    Method(Method),
+    Item(Item),
+}
@@ -0,0 +1,858 @@
+use nom::{
+    branch::alt,
+    bytes::complete::{is_not, tag, take_until},
+    character::complete::{
+        alpha1, alphanumeric1, char, digit1, multispace0, multispace1, not_line_ending,
+    },
+    combinator::{all_consuming, cut, eof, map, not, opt, recognize, value},
+    error::context,
+    multi::{many0, separated_list0, separated_list1},
+    sequence::{delimited, pair, preceded, terminated, tuple},
+};
+
+use crate::diagnostic::{Diagnostic, SourcePosition, SourceRange};
+use crate::parser::aidl::{parse_annotation, parse_const_expr, Span};
+use crate::parser::error::{SyntaxError, SyntaxErrorKind};
+use crate::parser::hidl::ast::{
+    Annotation, EnumValue, Field, File, Item, ItemElement, Method, Type,
+};
+use crate::parser::NodeRange;
+
+type IResult<I, O, E = SyntaxError<I>> = nom::IResult<I, O, E>;
+
+pub fn parse(source: &str) -> Result<File, Box<Diagnostic>> {
+    let input = Span::new_extra(source, None);
+
+    match all_consuming(parse_hidl)(input) {
+        Ok((_, file)) => Ok(file),
+        Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) => Err(Box::new(
+            e.map_input(|i| i.location_offset()).to_diagnostic(source),
+        )),
+        Err(nom::Err::Incomplete(_)) => unreachable!(),
+    }
+}
+
+// HIDL file:
+// - <package>
+// - 0+ <import>
+// - 0+ <interface> or type declarations (e.g.: in types.hal)
+fn parse_hidl(input: Span) -> IResult<Span, File> {
+    let (input, package) = context("package declaration", cut(parse_package))(input)?;
+    let (input, imports) = many0(context("import", parse_import))(input)?;
+    let (input, items) = many0(preceded(
+        not(pair(sp, eof)),
+        cut(alt((
+            context("interface declaration", parse_interface),
+            parse_type_declaration,
+            parse_invalid_element,
+        ))),
+    ))(input)?;
+    let (input, _) = sp(input)?;
+
+    Ok((
+        input,
+        File {
+            path: None,
+            package,
+            imports,
+            items,
+        },
+    ))
+}
+
+// Example:
+// - package android.hardware.foo@1.0;
+fn parse_package(input: Span) -> IResult<Span, String> {
+    let (input, _) = ws(keyword("package"))(input)?;
+    let (input, package) = cut(terminated(ws(package_name), ws(char(';'))))(input)?;
+
+    Ok((input, package.to_string()))
+}
+
+// Examples:
+// - import android.hardware.bar@1.0;
+// - import android.hardware.bar@1.0::types;
+// - import android.hidl.base@1.0::IBase;
+// - import @1.0::IFoo;
+// - import IFooCallback;
+fn parse_import(input: Span) -> IResult<Span, String> {
+    let (input, _) = ws(keyword("import"))(input)?;
+    let (input, import) = cut(terminated(
+        ws(alt((
+            recognize(pair(package_name, opt(pair(tag("::"), full_identifier)))),
+            type_name,
+        ))),
+        ws(char(';')),
+    ))(input)?;
+
+    Ok((input, import.to_string()))
+}
+
+fn parse_type_declaration(input: Span) -> IResult<Span, Item> {
+    alt((
+        context("struct declaration", parse_struct),
+        context("union declaration", parse_union),
+        context("enum declaration", parse_enum),
+        context("typedef declaration", parse_typedef),
+    ))(input)
+}
+
+// Examples:
+// - interface IFoo { <method> <type declaration> ... };
+// - interface IFoo extends @1.0::IFoo { ... };
+fn parse_interface(input: Span) -> IResult<Span, Item> {
+    let (input, docu) = extract_docu(input)?;
+    let start = input;
+
+    let (input, annotations) = parse_annotations(input)?;
+    let (input, _) = ws(keyword("interface"))(input)?;
+    let (input, (name, extends, elements)) = cut(tuple((
+        ws(identifier),
+        opt(preceded(ws(keyword("extends")), cut(ws(type_name)))),
+        terminated(
+            delimited(
+                ws(char('{')),
+                many0(alt((
+                    map(parse_type_declaration, ItemElement::Item),
+                    map(
+                        context("method declaration", parse_method),
+                        ItemElement::Method,
+                    ),
+                    parse_invalid_element,
+                ))),
+                ws(char('}')),
+            ),
+            ws(char(';')),
+        ),
+    )))(input)?;
+
+    let mut methods = Vec::new();
+    let mut nested_items = Vec::new();
+    elements.into_iter().for_each(|e| match e {
+        ItemElement::Method(m) => methods.push(m),
+        ItemElement::Item(i) => nested_items.push(i),
+        ItemElement::Field(_) => (),
+    });
+
+    Ok((
+        input,
+        Item::Interface {
+            name: name.to_string(),
+            docu,
+            extends: extends.map(|e| e.to_string()),
+            methods,
+            nested_items,
+            annotations,
+            location: location(start, input),
+        },
+    ))
+}
+
+// Examples:
+// - getFoo(int32_t id) generates (Status status, Foo foo);
+// - oneway notify(vec<Event> events);
+fn parse_method(input: Span) -> IResult<Span, Method> {
+    let (input, docu) = extract_docu(input)?;
+    let start = input;
+
+    let (input, annotations) = parse_annotations(input)?;
+    let (input, (oneway, name, _)) =
+        tuple((opt(ws(keyword("oneway"))), ws(identifier), ws(char('('))))(input)?;
+    let (input, (args, _, results, _)) = cut(tuple((
+        parse_args,
+        ws(char(')')),
+        opt(preceded(
+            ws(keyword("generates")),
+            cut(delimited(ws(char('(')), parse_args, ws(char(')')))),
+        )),
+        ws(char(';')),
+    )))(input)?;
+
+    Ok((
+        input,
+        Method {
+            name: name.to_string(),
+            docu,
+            is_oneway: oneway.is_some(),
+            args,
+            results: results.unwrap_or_default(),
+            annotations,
+            location: location(start, input),
+        },
+    ))
+}
+
+// Example:
+// - int32_t id, vec<Foo> foos
+fn parse_args(input: Span) -> IResult<Span, Vec<Field>> {
+    separated_list0(ws(char(',')), parse_arg)(input)
+}
+
+fn parse_arg(input: Span) -> IResult<Span, Field> {
+    let (input, _) = sp(input)?;
+    let start = input;
+
+    let (input, (annotations, field_type, name)) =
+        tuple((parse_annotations, ws(parse_type), cut(ws(identifier))))(input)?;
+
+    Ok((
+        input,
+        Field {
+            name: name.to_string(),
+            docu: String::new(),
+            field_type,
+            annotations,
+            location: location(start, input),
+        },
+    ))
+}
+
+// Example:
+// - struct Foo { <field> <type declaration> ... };
+fn parse_struct(input: Span) -> IResult<Span, Item> {
+    let (input, docu) = extract_docu(input)?;
+    let start = input;
+
+    let (input, annotations) = parse_annotations(input)?;
+    let (input, _) = ws(keyword("struct"))(input)?;
+    let (input, (name, (fields, nested_items))) =
+        cut(pair(ws(identifier), parse_compound_body))(input)?;
+
+    Ok((
+        input,
+        Item::Struct {
+            name: name.to_string(),
+            docu,
+            fields,
+            nested_items,
+            annotations,
+            location: location(start, input),
+        },
+    ))
+}
+
+// Examples:
+// - union Foo { <field> <type declaration> ... };
+// - safe_union Foo { <field> <type declaration> ... };
+fn parse_union(input: Span) -> IResult<Span, Item> {
+    let (input, docu) = extract_docu(input)?;
+    let start = input;
+
+    let (input, annotations) = parse_annotations(input)?;
+    let (input, keyword) = ws(alt((keyword("union"), keyword("safe_union"))))(input)?;
+    let (input, (name, (fields, nested_items))) =
+        cut(pair(ws(identifier), parse_compound_body))(input)?;
+
+    Ok((
+        input,
+        Item::Union {
+            name: name.to_string(),
+            docu,
+            is_safe: *keyword.fragment() == "safe_union",
+            fields,
+            nested_items,
+            annotations,
+            location: location(start, input),
+        },
+    ))
+}
+
+// Fields and nested types of a struct or a union
+fn parse_compound_body(input: Span) -> IResult<Span, (Vec<Field>, Vec<Item>)> {
+    let (input, elements) = terminated(
+        delimited(
+            ws(char('{')),
+            many0(alt((
+                map(parse_type_declaration, ItemElement::Item),
+                map(
+                    context("field declaration", parse_field),
+                    ItemElement::Field,
+                ),
+                parse_invalid_element,
+            ))),
+            ws(char('}')),
+        ),
+        ws(char(';')),
+    )(input)?;
+
+    let mut fields = Vec::new();
+    let mut nested_items = Vec::new();
+    elements.into_iter().for_each(|e| match e {
+        ItemElement::Field(f) => fields.push(f),
+        ItemElement::Item(i) => nested_items.push(i),
+        ItemElement::Method(_) => (),
+    });
+
+    Ok((input, (fields, nested_items)))
+}
+
+// Examples:
+// - int32_t id;
+// - vec<uint8_t> data;
+// - IFoo.Bar[2] bars;
+fn parse_field(input: Span) -> IResult<Span, Field> {
+    let (input, docu) = extract_docu(input)?;
+    let start = input;
+
+    let (input, (annotations, field_type)) = pair(parse_annotations, ws(parse_type))(input)?;
+    let (input, name) = cut(ws(identifier))(input)?;
+    let end = input;
+    let (input, _) = cut(ws(char(';')))(input)?;
+
+    Ok((
+        input,
+        Field {
+            name: name.to_string(),
+            docu,
+            field_type,
+            annotations,
+            location: location(start, end),
+        },
+    ))
+}
+
+// Examples:
+// - enum Color : uint32_t { RED, GREEN = 2, BLUE = GREEN << 1, };
+// - enum Color : @1.0::Color { YELLOW };
+fn parse_enum(input: Span) -> IResult<Span, Item> {
+    let (input, docu) = extract_docu(input)?;
+    let start = input;
+
+    let (input, annotations) = parse_annotations(input)?;
+    let (input, _) = ws(keyword("enum"))(input)?;
+    let (input, (name, _, backing_type, values)) = cut(tuple((
+        ws(identifier),
+        ws(char(':')),
+        ws(parse_type),
+        terminated(
+            delimited(
+                ws(char('{')),
+                terminated(
+                    separated_list0(ws(char(',')), context("enum value", parse_enum_value)),
+                    opt(ws(char(','))),
+                ),
+                ws(char('}')),
+            ),
+            ws(char(';')),
+        ),
+    )))(input)?;
+
+    Ok((
+        input,
+        Item::Enum {
+            name: name.to_string(),
+            docu,
+            backing_type,
+            values,
+            annotations,
+            location: location(start, input),
+        },
+    ))
+}
+
+// Examples:
+// - RED
+// - BLUE = 1 << 2
+fn parse_enum_value(input: Span) -> IResult<Span, EnumValue> {
+    let (input, docu) = extract_docu(input)?;
+    let start = input;
+
+    let (input, name) = identifier(input)?;
+    let (input, value) = opt(preceded(ws(char('=')), cut(ws(parse_const_expr))))(input)?;
+
+    Ok((
+        input,
+        EnumValue {
+            name: name.to_string(),
+            docu,
+            value,
+            location: location(start, input),
+        },
+    ))
+}
+
+// Example:
+// - typedef vec<uint8_t> Bytes;
+fn parse_typedef(input: Span) -> IResult<Span, Item> {
+    let (input, docu) = extract_docu(input)?;
+    let start = input;
+
+    let (input, annotations) = parse_annotations(input)?;
+    let (input, _) = ws(keyword("typedef"))(input)?;
+    let (input, (aliased_type, name)) = cut(pair(ws(parse_type), ws(identifier)))(input)?;
+    let end = input;
+    let (input, _) = cut(ws(char(';')))(input)?;
+
+    Ok((
+        input,
+        Item::Typedef {
+            name: name.to_string(),
+            docu,
+            aliased_type,
+            annotations,
+            location: location(start, end),
+        },
+    ))
+}
+
+// Examples:
+// - uint32_t
+// - vec<vec<string>>
+// - uint8_t[16][2]
+// - android.hardware.bar@1.0::IBar
+fn parse_type(input: Span) -> IResult<Span, Type> {
+    let (input, name) = type_name(input)?;
+    let (input, generic_args) = opt(delimited(
+        ws(char('<')),
+        cut(separated_list1(ws(char(',')), ws(parse_type))),
+        cut(ws(char('>'))),
+    ))(input)?;
+    let (input, array_dimensions) = many0(delimited(
+        ws(char('[')),
+        cut(ws(recognize(parse_const_expr))),
+        cut(ws(char(']'))),
+    ))(input)?;
+
+    Ok((
+        input,
+        Type {
+            name: name.to_string(),
+            generic_args: generic_args.unwrap_or_default(),
+            array_dimensions: array_dimensions.iter().map(|d| d.to_string()).collect(),
+        },
+    ))
+}
+
+// Examples:
+// - @export(name="foo", value_prefix="FOO_")
+// - @callflow(next={"*"}) @entry
+fn parse_annotations(input: Span) -> IResult<Span, Vec<Annotation>> {
+    many0(ws(parse_annotation))(input)
+}
+
+// Anything else than the end of the parent ('}') => failure
+fn parse_invalid_element<O>(input: Span) -> IResult<Span, O> {
+    let (input, _) = sp(input)?;
+
+    // Invalid input (up to the next ';' or '}') => failure
+    let (_, input) = is_not("};")(input)?;
+    Err(nom::Err::Failure(SyntaxError::new(
+        input,
+        SyntaxErrorKind::UnexpectedInput,
+    )))
+}
+
+// Examples:
+// // ...
+// /* ... */
+fn parse_comment(input: Span) -> IResult<Span, Span> {
+    alt((
+        preceded(tag("//"), not_line_ending),
+        delimited(tag("/*"), take_until("*/"), tag("*/")),
+    ))(input)
+}
+
+// Comments right before a declaration (i.e. not detached by a blank line)
+fn extract_docu(mut input: Span) -> IResult<Span, String> {
+    let mut comments = Vec::new();
+
+    loop {
+        let (after_spaces, spaces) = multispace0(input)?;
+        if spaces.fragment().matches('\n').count() > 1 {
+            comments.clear();
+        }
+
+        match parse_comment(after_spaces) {
+            Ok((rest, comment)) => {
+                comments.push(comment);
+                input = rest;
+            }
+            Err(_) => return Ok((after_spaces, docu_text(&comments))),
+        }
+    }
+}
+
+// The lines are kept (e.g.: for paragraphs), without their leading '*' or '/'
+// (of /** ... */ or /// ...)
+fn docu_text(comments: &[Span]) -> String {
+    let lines: Vec<&str> = comments
+        .iter()
+        .flat_map(|c| c.fragment().lines())
+        .map(|line| {
+            let line = line.trim_start();
+            let line = line.strip_prefix(&['*', '/'][..]).unwrap_or(line);
+            line.strip_prefix(' ').unwrap_or(line).trim_end()
+        })
+        .collect();
+
+    lines.join("\n").trim().to_string()
+}
+
+// Location of the input consumed from start to end, trailing whitespace excluded
+fn location(start: Span, end: Span) -> NodeRange {
+    let consumed = &start.fragment()[..end.location_offset() - start.location_offset()];
+    let start_position =
+        SourcePosition::new(start.location_line() as usize, start.get_utf8_column());
+
+    NodeRange(SourceRange::new(
+        start_position,
+        start_position.advanced_by(consumed.trim_end()),
+    ))
+}
+
+// Examples:
+// - android.hardware.foo@1.0
+fn package_name(input: Span) -> IResult<Span, Span> {
+    recognize(tuple((full_identifier, char('@'), version)))(input)
+}
+
+// Example:
+// - 1.0
+fn version(input: Span) -> IResult<Span, Span> {
+    recognize(tuple((digit1, char('.'), digit1)))(input)
+}
+
+// Examples:
+// - Foo
+// - a.b.Foo
+fn full_identifier(input: Span) -> IResult<Span, Span> {
+    recognize(separated_list1(char('.'), identifier))(input)
+}
+
+// Examples:
+// - Foo
+// - IFoo.Bar
+// - @1.0::IFoo (same package, other version)
+// - android.hardware.bar@1.0::IBar.Baz
+fn type_name(input: Span) -> IResult<Span, Span> {
+    alt((
+        recognize(tuple((
+            opt(full_identifier),
+            char('@'),
+            version,
+            tag("::"),
+            full_identifier,
+        ))),
+        full_identifier,
+    ))(input)
+}
+
+// Valid identifier
+fn identifier(input: Span) -> IResult<Span, Span> {
+    recognize(pair(alt((alpha1, tag("_"))), many0(identifier_char)))(input).map_err(|e| {
+        e.map(|_: SyntaxError<Span>| SyntaxError::new(input, SyntaxErrorKind::ExpectedIdentifier))
+    })
+}
+
+fn identifier_char(input: Span) -> IResult<Span, Span> {
+    alt((alphanumeric1, tag("_")))(input)
+}
+
+// Keyword not followed by other identifier chars (e.g.: not the start of
+// structName for struct)
+fn keyword<'a>(word: &'static str) -> impl FnMut(Span<'a>) -> IResult<Span<'a>, Span<'a>> {
+    terminated(tag(word), not(identifier_char))
+}
+
+// Spaces and comments
+fn sp(input: Span) -> IResult<Span, ()> {
+    value(
+        (),
+        many0(alt((value((), multispace1), value((), parse_comment)))),
+    )(input)
+}
+
+// Ignore leading spaces and comments (the trailing ones document the next
+// declaration, see extract_docu)
+fn ws<'a, O, F>(f: F) -> impl FnMut(Span<'a>) -> IResult<Span<'a>, O>
+where
+    F: FnMut(Span<'a>) -> IResult<Span<'a>, O>,
+{
+    preceded(sp, f)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::aidl::ast::{AnnotationParam, BinaryOp, ConstExpr};
+
+    // Span without error recovery
+    fn span(input: &str) -> Span<'_> {
+        Span::new_extra(input, None)
+    }
+
+    fn field(name: &str, field_type: Type) -> Field {
+        Field {
+            name: name.to_string(),
+            docu: String::new(),
+            field_type,
+            annotations: Vec::new(),
+            location: NodeRange::default(),
+        }
+    }
+
+    fn generic_type(name: &str, generic_args: Vec<Type>) -> Type {
+        Type {
+            generic_args,
+            ..Type::new(name)
+        }
+    }
+
+    #[test]
+    fn test_file() -> Result<(), Box<dyn std::error::Error>> {
+        let file = parse(
+            r#"
+            /*
+             * License
+             */
+
+            package android.hardware.foo@1.1;
+
+            import @1.0::IFoo;
+            import IFooCallback;
+            import android.hardware.bar@1.0;
+            import android.hardware.bar@1.0::types;
+            import android.hidl.base@1.0::IBase;
+
+            interface IFoo extends @1.0::IFoo {};
+            "#,
+        )?;
+
+        assert_eq!(file.package, "android.hardware.foo@1.1");
+        assert_eq!(
+            file.imports,
+            vec![
+                "@1.0::IFoo",
+                "IFooCallback",
+                "android.hardware.bar@1.0",
+                "android.hardware.bar@1.0::types",
+                "android.hidl.base@1.0::IBase"
+            ]
+        );
+        assert_eq!(file.items.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_interface() -> Result<(), Box<dyn std::error::Error>> {
+        let input = span(
+            r#"/** Docu of IFoo */
+            interface IFoo extends android.hidl.base@1.0::IBase {
+                struct Inner {};
+
+                /** get docu */
+                get(int32_t id) generates (Status status, vec<Inner> inners);
+                @entry
+                oneway notify();
+            };rest"#,
+        );
+        let (input, interface) = parse_interface(input)?;
+
+        assert_eq!(
+            interface,
+            Item::Interface {
+                name: "IFoo".to_string(),
+                docu: "Docu of IFoo".to_string(),
+                extends: Some("android.hidl.base@1.0::IBase".to_string()),
+                methods: vec![
+                    Method {
+                        name: "get".to_string(),
+                        docu: "get docu".to_string(),
+                        is_oneway: false,
+                        args: vec![field("id", Type::new("int32_t"))],
+                        results: vec![
+                            field("status", Type::new("Status")),
+                            field("inners", generic_type("vec", vec![Type::new("Inner")])),
+                        ],
+                        annotations: Vec::new(),
+                        location: NodeRange::default(),
+                    },
+                    Method {
+                        name: "notify".to_string(),
+                        docu: String::new(),
+                        is_oneway: true,
+                        args: Vec::new(),
+                        results: Vec::new(),
+                        annotations: vec![Annotation::new("entry", Vec::new())],
+                        location: NodeRange::default(),
+                    },
+                ],
+                nested_items: vec![Item::Struct {
+                    name: "Inner".to_string(),
+                    docu: String::new(),
+                    fields: Vec::new(),
+                    nested_items: Vec::new(),
+                    annotations: Vec::new(),
+                    location: NodeRange::default(),
+                }],
+                annotations: Vec::new(),
+                location: NodeRange::default(),
+            }
+        );
+        assert_eq!(*input, "rest");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compound_types() -> Result<(), Box<dyn std::error::Error>> {
+        let (_, strukt) = parse_struct(span(
+            r#"struct Foo {
+                // name docu
+                string name;
+                uint8_t[16][2] uuids;
+                vec<vec<IFoo.Bar>> bars;
+                @1.0::Foo old;
+                safe_union Value { int32_t i; string s; };
+            };"#,
+        ))?;
+
+        assert_eq!(
+            strukt,
+            Item::Struct {
+                name: "Foo".to_string(),
+                docu: String::new(),
+                fields: vec![
+                    Field {
+                        docu: "name docu".to_string(),
+                        ..field("name", Type::new("string"))
+                    },
+                    field(
+                        "uuids",
+                        Type {
+                            array_dimensions: vec!["16".to_string(), "2".to_string()],
+                            ..Type::new("uint8_t")
+                        }
+                    ),
+                    field(
+                        "bars",
+                        generic_type(
+                            "vec",
+                            vec![generic_type("vec", vec![Type::new("IFoo.Bar")])]
+                        )
+                    ),
+                    field("old", Type::new("@1.0::Foo")),
+                ],
+                nested_items: vec![Item::Union {
+                    name: "Value".to_string(),
+                    docu: String::new(),
+                    is_safe: true,
+                    fields: vec![
+                        field("i", Type::new("int32_t")),
+                        field("s", Type::new("string")),
+                    ],
+                    nested_items: Vec::new(),
+                    annotations: Vec::new(),
+                    location: NodeRange::default(),
+                }],
+                annotations: Vec::new(),
+                location: NodeRange::default(),
+            }
+        );
+
+        let (_, typedef) = parse_typedef(span("typedef vec<uint8_t> Bytes;"))?;
+        assert_eq!(
+            typedef,
+            Item::Typedef {
+                name: "Bytes".to_string(),
+                docu: String::new(),
+                aliased_type: generic_type("vec", vec![Type::new("uint8_t")]),
+                annotations: Vec::new(),
+                location: NodeRange::default(),
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_enum() -> Result<(), Box<dyn std::error::Error>> {
+        let input = span(
+            r#"@export(name="foo_mode")
+            enum Mode : uint32_t {
+                A,
+                /** B docu */
+                B = 1 << 2,
+            };rest"#,
+        );
+        let (input, enumeration) = parse_enum(input)?;
+
+        assert_eq!(
+            enumeration,
+            Item::Enum {
+                name: "Mode".to_string(),
+                docu: String::new(),
+                backing_type: Type::new("uint32_t"),
+                values: vec![
+                    EnumValue {
+                        name: "A".to_string(),
+                        docu: String::new(),
+                        value: None,
+                        location: NodeRange::default(),
+                    },
+                    EnumValue {
+                        name: "B".to_string(),
+                        docu: "B docu".to_string(),
+                        value: Some(ConstExpr::binary(
+                            ConstExpr::int("1"),
+                            BinaryOp::Shl,
+                            ConstExpr::int("2")
+                        )),
+                        location: NodeRange::default(),
+                    },
+                ],
+                annotations: vec![Annotation::new(
+                    "export",
+                    vec![AnnotationParam::new("name", ConstExpr::string("foo_mode"))]
+                )],
+                location: NodeRange::default(),
+            }
+        );
+        assert_eq!(*input, "rest");
+
+        // Enum extending the one of a previous version
+        let (_, enumeration) = parse_enum(span("enum Mode : @1.0::Mode { C };"))?;
+        match enumeration {
+            Item::Enum { backing_type, .. } => assert_eq!(backing_type, Type::new("@1.0::Mode")),
+            _ => panic!("expected an enum"),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_diagnostics() -> Result<(), Box<dyn std::error::Error>> {
+        let diagnostic = parse("package a@1.0;\nstruct A {\n  int32_t a\n};\n").unwrap_err();
+        assert_eq!(diagnostic.code, crate::parser::error::EXPECTED_TOKEN);
+        assert_eq!(diagnostic.message, "expected ';' after field declaration");
+        assert_eq!(diagnostic.range.map(|r| r.start.line), Some(3));
+
+        let diagnostic = parse("package a;\n").unwrap_err();
+        assert_eq!(diagnostic.code, crate::parser::error::EXPECTED_TOKEN);
+
+        let diagnostic = parse("package a@1.0;\nfoo bar;\n").unwrap_err();
+        assert_eq!(diagnostic.code, crate::parser::error::UNEXPECTED_INPUT);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_locations() -> Result<(), Box<dyn std::error::Error>> {
+        let file = parse("package a@1.0;\n\n/** Foo */\nstruct Foo {\n  int32_t id;\n};\n")?;
+
+        match file.items.first() {
+            Some(Item::Struct {
+                fields, location, ..
+            }) => {
+                assert_eq!(location.start, SourcePosition::new(4, 1));
+                assert_eq!(location.end, SourcePosition::new(6, 3));
+                assert_eq!(fields[0].location.start, SourcePosition::new(5, 3));
+                assert_eq!(fields[0].location.end, SourcePosition::new(5, 13));
+            }
+            _ => panic!("expected a struct"),
+        }
+
+        Ok(())
+    }
+}
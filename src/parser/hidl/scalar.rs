@@ -0,0 +1,76 @@
+use strum_macros::EnumIter;
+
+use crate::model::TypeCategory;
+
+// Built-in types, including the generic ones (e.g.: vec<T>)
+#[derive(Debug, EnumIter)]
+pub enum Scalar {
+    Bool,
+    Int8,
+    Uint8,
+    Int16,
+    Uint16,
+    Int32,
+    Uint32,
+    Int64,
+    Uint64,
+    Float,
+    Double,
+    Pointer,
+    Bitfield,
+    String,
+    Handle,
+    Memory,
+    Vec,
+    FmqSync,
+    FmqUnsync,
+    Interface,
+    Void,
+}
+
+impl Scalar {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Scalar::Bool => "bool",
+            Scalar::Int8 => "int8_t",
+            Scalar::Uint8 => "uint8_t",
+            Scalar::Int16 => "int16_t",
+            Scalar::Uint16 => "uint16_t",
+            Scalar::Int32 => "int32_t",
+            Scalar::Uint32 => "uint32_t",
+            Scalar::Int64 => "int64_t",
+            Scalar::Uint64 => "uint64_t",
+            Scalar::Float => "float",
+            Scalar::Double => "double",
+            Scalar::Pointer => "pointer",
+            Scalar::Bitfield => "bitfield",
+            Scalar::String => "string",
+            Scalar::Handle => "handle",
+            Scalar::Memory => "memory",
+            Scalar::Vec => "vec",
+            Scalar::FmqSync => "fmq_sync",
+            Scalar::FmqUnsync => "fmq_unsync",
+            Scalar::Interface => "interface",
+            Scalar::Void => "void",
+        }
+    }
+
+    pub fn get_category(&self) -> TypeCategory {
+        match self {
+            Scalar::String => TypeCategory::String,
+            Scalar::Handle => TypeCategory::FileDescriptor,
+            Scalar::Memory | Scalar::FmqSync | Scalar::FmqUnsync => TypeCategory::Parcelable,
+            Scalar::Vec => TypeCategory::Container,
+            Scalar::Interface => TypeCategory::Binder,
+            Scalar::Void => TypeCategory::Void,
+            _ => TypeCategory::Primitive,
+        }
+    }
+
+    pub fn get_generic_arity(&self) -> usize {
+        match self {
+            Scalar::Bitfield | Scalar::Vec | Scalar::FmqSync | Scalar::FmqUnsync => 1,
+            _ => 0,
+        }
+    }
+}
@@ -0,0 +1,1007 @@
+pub mod ast;
+mod data_type;
+mod parse;
+
+use crate::diagnostic::Diagnostic;
+use crate::model::{self, Model};
+use crate::parser::front_end::FrontEnd;
+use data_type::DataType;
+pub use parse::parse;
+use std::path::{Component, Path, PathBuf};
+use std::rc::Rc;
+use strum::IntoEnumIterator;
+
+// The YAML and JSON files which are not OpenAPI documents are left to the
+// other front-ends
+#[derive(Default)]
+pub struct OpenApiFrontEnd {
+    documents: Vec<ast::Document>,
+}
+
+impl FrontEnd for OpenApiFrontEnd {
+    fn get_extensions(&self) -> &[&'static str] {
+        &["yaml", "yml", "json"]
+    }
+
+    fn parse(&mut self, path: &str, source: &str) -> Option<Vec<Diagnostic>> {
+        match parse(source) {
+            Ok(Some(mut document)) => {
+                document.path = Some(path.to_string());
+                self.documents.push(document);
+                Some(Vec::new())
+            }
+            Ok(None) => None,
+            Err(diagnostic) => Some(vec![*diagnostic]),
+        }
+    }
+
+    fn create_model(&mut self) -> Model {
+        create_model(std::mem::take(&mut self.documents))
+    }
+}
+
+// The schemas of the components are mapped to structs, enums and unions, and
+// the operations to the methods of an interface per tag (e.g.: PetApi)
+pub fn create_model(documents: Vec<ast::Document>) -> Model {
+    let mut model = Model::default();
+
+    // Root package
+    let root_pkg = Rc::new(model::Package::new("", model::Idl::OpenApi));
+
+    // The packages are named after the paths of the documents in their
+    // common directory, which stay unique
+    let root_dir = find_root_dir(&documents);
+
+    // Go through documents
+    documents.into_iter().for_each(|document| {
+        let package = match document.path {
+            Some(ref path) => create_package_name(Path::new(path), &root_dir),
+            None => create_package_name(Path::new(&document.title), Path::new("")),
+        };
+        let context = Context {
+            pkg: Rc::new(model::Package::new(package, model::Idl::OpenApi)),
+            path: document.path.as_deref(),
+            root_dir: &root_dir,
+        };
+
+        document.schemas.iter().for_each(|s| {
+            add_model_item(
+                &mut model,
+                &context,
+                None,
+                &s.name,
+                &s.schema,
+                model::Location::new(context.path, s.location.0),
+            );
+        });
+        add_model_interfaces(&mut model, &context, &document);
+    });
+
+    // Create standard types
+    let standard_types = DataType::iter()
+        .map(|dt| {
+            model::StandardType::new(
+                dt.get_name(),
+                dt.get_category(),
+                dt.get_generic_arity(),
+                root_pkg.clone(),
+            )
+        })
+        .map(|st| (st.get_key().clone(), Rc::new(st)))
+        .collect();
+    model
+        .standard_types
+        .insert(model::Idl::OpenApi, standard_types);
+
+    // The types are resolved separately (see Model::resolve_types)
+    model
+}
+
+// What the items of a document share
+struct Context<'a> {
+    pkg: Rc<model::Package>,
+    path: Option<&'a str>,
+    root_dir: &'a Path,
+}
+
+impl Context<'_> {
+    // The schemas of a document are in the package named after its file, which
+    // is how the other documents refer to them (relative to the directory of
+    // the referencing document).
+    // Examples:
+    // - #/components/schemas/Pet => petstore.Pet
+    // - common.yaml#/components/schemas/Error => common.Error
+    // - ../v1/common.yaml#/components/schemas/Error => v1.common.Error
+    // Other references (e.g.: to a whole file) are kept as written.
+    fn resolve_ref(&self, reference: &str) -> String {
+        let (file, pointer) = match reference.find('#') {
+            Some(pos) => (&reference[..pos], &reference[pos + 1..]),
+            None => (reference, ""),
+        };
+
+        match pointer.strip_prefix("/components/schemas/") {
+            Some(name) if !name.contains('/') => {
+                let name = name.replace("~1", "/").replace("~0", "~");
+                if file.is_empty() {
+                    format!("{}.{}", self.pkg.path, name)
+                } else {
+                    let directory = self.path.and_then(|p| Path::new(p).parent());
+                    let file_path = normalized(&directory.unwrap_or(Path::new("")).join(file));
+                    format!(
+                        "{}.{}",
+                        create_package_name(&file_path, self.root_dir),
+                        name
+                    )
+                }
+            }
+            _ => reference.to_string(),
+        }
+    }
+}
+
+// Examples (in the directory specs):
+// - specs/pet-store.v2.yaml => pet_store_v2
+// - specs/v1/common.yaml => v1.common
+fn create_package_name(path: &Path, root_dir: &Path) -> String {
+    let relative_path = path.strip_prefix(root_dir).unwrap_or(path);
+    let stem = relative_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default();
+    let stem = stem
+        .strip_suffix(".openapi")
+        .or_else(|| stem.strip_suffix(".oas"))
+        .unwrap_or(stem);
+
+    let sanitized = |name: &str| -> String {
+        name.chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect()
+    };
+    let directories = relative_path
+        .parent()
+        .into_iter()
+        .flat_map(Path::components)
+        .filter_map(|c| match c {
+            Component::Normal(name) => name.to_str(),
+            _ => None,
+        });
+
+    directories
+        .chain(std::iter::once(stem))
+        .map(sanitized)
+        .collect::<Vec<String>>()
+        .join(".")
+}
+
+// Deepest directory containing all the documents (e.g.: specs for
+// specs/v1/common.yaml and specs/v2/common.yaml)
+fn find_root_dir(documents: &[ast::Document]) -> PathBuf {
+    let mut directories = documents
+        .iter()
+        .filter_map(|d| d.path.as_deref())
+        .map(|path| normalized(Path::new(path).parent().unwrap_or(Path::new(""))));
+
+    let mut root_dir = directories.next().unwrap_or_default();
+    for directory in directories {
+        while !directory.starts_with(&root_dir) {
+            if !root_dir.pop() {
+                break;
+            }
+        }
+    }
+
+    root_dir
+}
+
+// Path without . and .. (e.g.: specs/v2/../v1/common.yaml => specs/v1/common.yaml)
+fn normalized(path: &Path) -> PathBuf {
+    let mut normalized_path = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => (),
+            Component::ParentDir if normalized_path.file_name().is_some() => {
+                normalized_path.pop();
+            }
+            _ => normalized_path.push(component),
+        }
+    }
+
+    normalized_path
+}
+
+// Examples:
+// - pet => Pet
+// - store-orders => StoreOrders
+// - /pets/{petId} => PetsPetId
+fn pascal_case(name: &str) -> String {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+// Example:
+// - PetStatus => petStatus
+fn camel_case(name: &str) -> String {
+    let name = pascal_case(name);
+    let mut chars = name.chars();
+
+    chars
+        .next()
+        .map(|first| first.to_lowercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+// A single schema of allOf is the schema itself (e.g.: allOf: [$ref: ...]
+// to add a description to a reference)
+fn effective_kind(schema: &ast::Schema) -> &ast::SchemaKind {
+    match schema.kind {
+        ast::SchemaKind::AllOf(ref schemas) if schemas.len() == 1 => effective_kind(&schemas[0]),
+        ref kind => kind,
+    }
+}
+
+// Schemas which are items (the others are aliases of a type)
+fn is_item(kind: &ast::SchemaKind) -> bool {
+    match kind {
+        ast::SchemaKind::Object {
+            properties,
+            additional_properties,
+        } => !properties.is_empty() || additional_properties.is_none(),
+        ast::SchemaKind::Enum { .. }
+        | ast::SchemaKind::AllOf(_)
+        | ast::SchemaKind::OneOf(_)
+        | ast::SchemaKind::AnyOf(_) => true,
+        _ => false,
+    }
+}
+
+// Examples:
+// - type: object => struct
+// - type: string, enum: [available, sold] => enum
+// - oneOf: [...] => union
+// - type: array, items: { $ref: ... } => struct with a value member
+fn add_model_item(
+    model: &mut Model,
+    context: &Context,
+    parent: Option<&model::Key>,
+    name: &str,
+    schema: &ast::Schema,
+    location: model::Location,
+) -> model::Key {
+    let key = model::item_key(&context.pkg, parent, name);
+    let docu = model::Javadoc::parse(&schema.description);
+    let annotations = create_model_annotations(schema.is_deprecated, &[]);
+
+    let item = match effective_kind(schema) {
+        ast::SchemaKind::Object {
+            properties,
+            additional_properties,
+        } if !properties.is_empty() || additional_properties.is_none() => {
+            let mut members: Vec<model::Member> = properties
+                .iter()
+                .enumerate()
+                .map(|(index, p)| {
+                    create_model_member(
+                        model,
+                        context,
+                        &key,
+                        index,
+                        &p.name,
+                        &p.schema,
+                        p.is_required,
+                        model::Location::new(context.path, p.location.0),
+                    )
+                })
+                .collect();
+
+            // Properties not declared (e.g.: additionalProperties: true)
+            if let Some(values) = additional_properties {
+                let values = ast::Schema::new(ast::SchemaKind::Object {
+                    properties: Vec::new(),
+                    additional_properties: Some(values.clone()),
+                });
+                members.push(create_model_member(
+                    model,
+                    context,
+                    &key,
+                    members.len(),
+                    "additionalProperties",
+                    &values,
+                    false,
+                    location.clone(),
+                ));
+            }
+
+            model::Item::Struct(model::Struct::new(
+                context.pkg.clone(),
+                parent.cloned(),
+                Vec::new(),
+                name,
+                Vec::new(),
+                docu,
+                members,
+                annotations,
+                location,
+            ))
+        }
+        ast::SchemaKind::Enum { type_name, values } => model::Item::Enum(model::Enum::new(
+            context.pkg.clone(),
+            parent.cloned(),
+            Vec::new(),
+            name,
+            docu,
+            Rc::new(create_model_type(context, type_name)),
+            values
+                .iter()
+                .enumerate()
+                .map(|(index, v)| create_model_enum_element(index, type_name, v, &location))
+                .collect(),
+            annotations,
+            location,
+        )),
+        ast::SchemaKind::AllOf(schemas) => {
+            let mut members = Vec::new();
+            for (index, s) in schemas.iter().enumerate() {
+                match effective_kind(s) {
+                    // The properties of the inline schemas are merged
+                    ast::SchemaKind::Object { properties, .. } if !properties.is_empty() => {
+                        properties.iter().for_each(|p| {
+                            let member = create_model_member(
+                                model,
+                                context,
+                                &key,
+                                members.len(),
+                                &p.name,
+                                &p.schema,
+                                p.is_required,
+                                model::Location::new(context.path, p.location.0),
+                            );
+                            members.push(member);
+                        })
+                    }
+                    // The referenced schemas are composed
+                    kind => {
+                        let mut member = create_model_member(
+                            model,
+                            context,
+                            &key,
+                            members.len(),
+                            &create_alternative_name(kind, index),
+                            s,
+                            true,
+                            location.clone(),
+                        );
+                        member
+                            .annotations
+                            .push(model::Annotation::new("allOf", Vec::new()));
+                        members.push(member);
+                    }
+                }
+            }
+
+            model::Item::Struct(model::Struct::new(
+                context.pkg.clone(),
+                parent.cloned(),
+                Vec::new(),
+                name,
+                Vec::new(),
+                docu,
+                members,
+                annotations,
+                location,
+            ))
+        }
+        ast::SchemaKind::OneOf(schemas) | ast::SchemaKind::AnyOf(schemas) => {
+            let members = schemas
+                .iter()
+                .enumerate()
+                .map(|(index, s)| {
+                    create_model_member(
+                        model,
+                        context,
+                        &key,
+                        index,
+                        &create_alternative_name(effective_kind(s), index),
+                        s,
+                        false,
+                        location.clone(),
+                    )
+                })
+                .collect();
+
+            let mut annotations = annotations;
+            if let ast::SchemaKind::AnyOf(_) = schema.kind {
+                annotations.push(model::Annotation::new("anyOf", Vec::new()));
+            }
+
+            model::Item::Union(model::Union::new(
+                context.pkg.clone(),
+                parent.cloned(),
+                Vec::new(),
+                name,
+                docu,
+                members,
+                annotations,
+                location,
+            ))
+        }
+        // Aliases (e.g.: Pets: { type: array, items: { $ref: Pet } })
+        _ => {
+            let member = create_model_member(
+                model,
+                context,
+                &key,
+                0,
+                "value",
+                schema,
+                true,
+                location.clone(),
+            );
+
+            model::Item::Struct(model::Struct::new(
+                context.pkg.clone(),
+                parent.cloned(),
+                Vec::new(),
+                name,
+                Vec::new(),
+                docu,
+                vec![member],
+                annotations,
+                location,
+            ))
+        }
+    };
+
+    model.insert_item(item)
+}
+
+// Examples:
+// - $ref: '#/components/schemas/Cat' => cat
+// - type: string => value1
+fn create_alternative_name(kind: &ast::SchemaKind, index: usize) -> String {
+    match kind {
+        ast::SchemaKind::Ref(reference) => match reference.rfind('/') {
+            Some(pos) => camel_case(&reference[pos + 1..]),
+            None => format!("value{}", index + 1),
+        },
+        _ => format!("value{}", index + 1),
+    }
+}
+
+// Examples:
+// - id: { type: integer, format: int64 } => @required @format("int64") integer id
+// - tags: { type: array, items: { type: string } } => string[] tags
+// - category: { type: object, ... } => Category category (nested struct)
+#[allow(clippy::too_many_arguments)]
+fn create_model_member(
+    model: &mut Model,
+    context: &Context,
+    scope: &model::Key,
+    index: usize,
+    name: &str,
+    schema: &ast::Schema,
+    is_required: bool,
+    location: model::Location,
+) -> model::Member {
+    let (member_type, generic_args) =
+        create_model_type_from_schema(model, context, scope, &pascal_case(name), schema, &location);
+
+    let mut annotations = Vec::new();
+    if is_required {
+        annotations.push(model::Annotation::new("required", Vec::new()));
+    }
+    if schema.is_nullable {
+        annotations.push(model::Annotation::new("nullable", Vec::new()));
+    }
+    annotations.extend(create_model_annotations(schema.is_deprecated, &[]));
+    if let ast::SchemaKind::Primitive {
+        format: Some(ref format),
+        ..
+    } = schema.kind
+    {
+        annotations.push(create_model_value_annotation("format", format));
+    }
+
+    model::Member::new(
+        model::Arg::new(
+            model::Direction::Unspecified,
+            name.to_string(),
+            Rc::new(member_type),
+            generic_args,
+            Vec::new(),
+            location.clone(),
+        ),
+        None,
+        None,
+        index,
+        model::Javadoc::parse(&schema.description),
+        annotations,
+        location,
+    )
+}
+
+// Type of a property, a parameter or a response, with its generic args (the
+// value type of a map); inline schemas which are items are nested in the scope
+// with the given name
+fn create_model_type_from_schema(
+    model: &mut Model,
+    context: &Context,
+    scope: &model::Key,
+    name: &str,
+    schema: &ast::Schema,
+    location: &model::Location,
+) -> (model::Type, Vec<model::Arg>) {
+    let kind = effective_kind(schema);
+
+    if is_item(kind) {
+        let key = add_model_item(model, context, Some(scope), name, schema, location.clone());
+        return (create_model_type(context, &key), Vec::new());
+    }
+
+    match kind {
+        ast::SchemaKind::Ref(reference) => (
+            create_model_type(context, &context.resolve_ref(reference)),
+            Vec::new(),
+        ),
+        ast::SchemaKind::Primitive { type_name, .. } => {
+            (create_model_type(context, type_name), Vec::new())
+        }
+        ast::SchemaKind::Array(items) => {
+            let (element_type, _) =
+                create_model_type_from_schema(model, context, scope, name, items, location);
+            (
+                model::Type::Array(model::ArrayType::new(
+                    Rc::new(element_type),
+                    vec![model::ArrayDimension::Dynamic],
+                )),
+                Vec::new(),
+            )
+        }
+        // e.g.: additionalProperties: { type: integer } => map<string, integer>
+        ast::SchemaKind::Object {
+            additional_properties: Some(values),
+            ..
+        } => {
+            let (value_type, _) = create_model_type_from_schema(
+                model,
+                context,
+                scope,
+                &format!("{}Value", name),
+                values,
+                location,
+            );
+            let create_arg = |the_type| {
+                model::Arg::new(
+                    model::Direction::Unspecified,
+                    String::new(),
+                    Rc::new(the_type),
+                    Vec::new(),
+                    Vec::new(),
+                    location.clone(),
+                )
+            };
+
+            (
+                create_model_type(context, DataType::Map.get_name()),
+                vec![
+                    create_arg(create_model_type(context, DataType::String.get_name())),
+                    create_arg(value_type),
+                ],
+            )
+        }
+        _ => (
+            create_model_type(context, DataType::Object.get_name()),
+            Vec::new(),
+        ),
+    }
+}
+
+// Examples:
+// - available => AVAILABLE = "available"
+// - 42 => VALUE_42 = 42
+// - -1 => VALUE_MINUS_1 = -1
+fn create_model_enum_element(
+    index: usize,
+    type_name: &str,
+    value: &str,
+    location: &model::Location,
+) -> model::EnumElement {
+    let is_integer = type_name == DataType::Integer.get_name();
+
+    let (name, literal) = if is_integer {
+        let name = match value.strip_prefix('-') {
+            Some(digits) => format!("VALUE_MINUS_{}", digits),
+            None => format!("VALUE_{}", value),
+        };
+        let literal = match value.strip_prefix('-') {
+            Some(digits) => model::Expression::Unary(
+                model::UnaryOp::Minus,
+                Box::new(model::Expression::Literal(model::Literal::Int(
+                    digits.to_string(),
+                ))),
+            ),
+            None => model::Expression::Literal(model::Literal::Int(value.to_string())),
+        };
+        (name, literal)
+    } else {
+        let name = value
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<&str>>()
+            .join("_")
+            .to_uppercase();
+        let name = match name.chars().next() {
+            Some(c) if c.is_alphabetic() => name,
+            _ => format!("VALUE_{}", name),
+        };
+        (
+            name,
+            model::Expression::Literal(model::Literal::String(value.to_string())),
+        )
+    };
+
+    model::EnumElement {
+        name,
+        value: Some(literal),
+        computed_value: None,
+        index,
+        docu: model::Javadoc::default(),
+        location: location.clone(),
+    }
+}
+
+// Operations are grouped by tag, in the order of the declared tags (the
+// operations without tags going to DefaultApi)
+fn add_model_interfaces(model: &mut Model, context: &Context, document: &ast::Document) {
+    let mut tags: Vec<&str> = document.tags.iter().map(|t| t.name.as_str()).collect();
+    document.operations.iter().for_each(|o| {
+        let tag = o.tags.first().map_or("default", String::as_str);
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    });
+
+    for tag in tags {
+        // An operation is a method of the interface of its first tag
+        let operations: Vec<&ast::Operation> = document
+            .operations
+            .iter()
+            .filter(|o| o.tags.first().map_or("default", String::as_str) == tag)
+            .collect();
+        if operations.is_empty() {
+            continue;
+        }
+
+        let name = format!("{}Api", pascal_case(tag));
+        let key = model::item_key(&context.pkg, None, &name);
+        let description = document
+            .tags
+            .iter()
+            .find(|t| t.name == tag)
+            .map_or("", |t| t.description.as_str());
+
+        let methods = operations
+            .iter()
+            .enumerate()
+            .map(|(index, o)| create_model_method(model, context, &key, index, o))
+            .collect();
+
+        let interface = model::Interface::new(
+            context.pkg.clone(),
+            None,
+            Vec::new(),
+            name,
+            Vec::new(),
+            model::Javadoc::parse(description),
+            Vec::new(),
+            methods,
+            Vec::new(),
+            model::Location::new(context.path, operations[0].location.0),
+        );
+        model.insert_item(model::Item::Interface(interface));
+    }
+}
+
+// Example:
+// - get /pets/{petId} (operationId: showPetById)
+//   => @get("/pets/{petId}") Pet showPetById(@path @required string petId)
+fn create_model_method(
+    model: &mut Model,
+    context: &Context,
+    scope: &model::Key,
+    index: usize,
+    operation: &ast::Operation,
+) -> model::Method {
+    let location = model::Location::new(context.path, operation.location.0);
+    let name = match operation.operation_id {
+        Some(ref id) => id.clone(),
+        None => camel_case(&format!("{} {}", operation.method, operation.path)),
+    };
+
+    // Primitives have no direction, the other arguments are "in"
+    let mut create_arg = |arg_name: &str, type_name: &str, schema: &ast::Schema, annotations| {
+        let (arg_type, generic_args) =
+            create_model_type_from_schema(model, context, scope, type_name, schema, &location);
+        let direction = match effective_kind(schema) {
+            ast::SchemaKind::Primitive { .. } => model::Direction::Unspecified,
+            _ => model::Direction::In,
+        };
+
+        model::Arg::new(
+            direction,
+            arg_name.to_string(),
+            Rc::new(arg_type),
+            generic_args,
+            annotations,
+            location.clone(),
+        )
+    };
+
+    let mut args: Vec<model::Arg> = operation
+        .parameters
+        .iter()
+        .map(|p| {
+            let mut annotations = vec![model::Annotation::new(&p.parameter_in, Vec::new())];
+            if p.is_required {
+                annotations.push(model::Annotation::new("required", Vec::new()));
+            }
+            annotations.extend(create_model_annotations(p.is_deprecated, &[]));
+
+            create_arg(
+                &p.name,
+                &format!("{}{}", pascal_case(&name), pascal_case(&p.name)),
+                &p.schema,
+                annotations,
+            )
+        })
+        .collect();
+
+    if let Some(ref body) = operation.request_body {
+        let mut annotations = vec![model::Annotation::new("body", Vec::new())];
+        if body.is_required {
+            annotations.push(model::Annotation::new("required", Vec::new()));
+        }
+
+        args.push(create_arg(
+            "body",
+            &format!("{}Request", pascal_case(&name)),
+            &body.schema,
+            annotations,
+        ));
+    }
+
+    let return_arg = match operation.response {
+        Some(ref schema) => {
+            let (return_type, generic_args) = create_model_type_from_schema(
+                model,
+                context,
+                scope,
+                &format!("{}Response", pascal_case(&name)),
+                schema,
+                &location,
+            );
+            model::Arg::new(
+                model::Direction::Unspecified,
+                String::new(),
+                Rc::new(return_type),
+                generic_args,
+                Vec::new(),
+                location.clone(),
+            )
+        }
+        None => model::Arg::new(
+            model::Direction::Unspecified,
+            String::new(),
+            Rc::new(create_model_type(context, DataType::Void.get_name())),
+            Vec::new(),
+            Vec::new(),
+            location.clone(),
+        ),
+    };
+
+    model::Method::new(
+        false,
+        &name,
+        return_arg,
+        args,
+        model::TransactionCode::Implicit(index as u32),
+        index,
+        model::Javadoc::parse(&operation.docu),
+        create_model_annotations(
+            operation.is_deprecated,
+            &[create_model_value_annotation(
+                &operation.method,
+                &operation.path,
+            )],
+        ),
+        location,
+    )
+}
+
+// Examples:
+// - deprecated: true => @deprecated
+fn create_model_annotations(
+    is_deprecated: bool,
+    annotations: &[model::Annotation],
+) -> Vec<model::Annotation> {
+    let mut annotations = annotations.to_vec();
+    if is_deprecated {
+        annotations.push(model::Annotation::new("deprecated", Vec::new()));
+    }
+
+    annotations
+}
+
+// Example:
+// - format: int64 => @format("int64")
+fn create_model_value_annotation(name: &str, value: &str) -> model::Annotation {
+    model::Annotation::new(
+        name,
+        vec![model::AnnotationParam::new(
+            "value",
+            model::Expression::Literal(model::Literal::String(value.to_string())),
+        )],
+    )
+}
+
+fn create_model_type(context: &Context, type_name: &str) -> model::Type {
+    model::Type::Unresolved(model::UnresolvedType::new(context.pkg.clone(), type_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_document(
+        path: &str,
+        source: &str,
+    ) -> Result<ast::Document, Box<dyn std::error::Error>> {
+        let mut document = parse(source)?.ok_or("not an OpenAPI document")?;
+        document.path = Some(path.to_string());
+        Ok(document)
+    }
+
+    fn get_member_type_keys(model: &Model, key: &str) -> Vec<Option<model::Key>> {
+        model.items[key]
+            .get_members()
+            .iter()
+            .map(|m| match *m.arg.arg_type {
+                model::Type::Item(ref item) => Some(item.get_key().clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn get_method_names(model: &Model, key: &str) -> Vec<String> {
+        match *model.items[key] {
+            model::Item::Interface(ref i) => i.methods.iter().map(|m| m.name.clone()).collect(),
+            _ => panic!("expected an interface"),
+        }
+    }
+
+    #[test]
+    fn test_packages_and_refs() -> Result<(), Box<dyn std::error::Error>> {
+        let documents = vec![
+            create_document(
+                "specs/common.yaml",
+                "openapi: 3.0.0\ncomponents:\n  schemas:\n    User: { type: object }\n",
+            )?,
+            create_document(
+                "specs/pet-store.v2.yaml",
+                r#"
+openapi: 3.0.0
+components:
+  schemas:
+    Pet:
+      properties:
+        owner: { $ref: 'common.yaml#/components/schemas/User' }
+        parent: { $ref: '#/components/schemas/Pet' }
+"#,
+            )?,
+        ];
+        let mut model = create_model(documents);
+        assert!(model.resolve_types().is_empty());
+
+        // Packages named after the files, which the references use
+        assert_eq!(
+            get_member_type_keys(&model, "pet_store_v2.Pet"),
+            vec![
+                Some("common.User".to_string()),
+                Some("pet_store_v2.Pet".to_string())
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_same_file_names() -> Result<(), Box<dyn std::error::Error>> {
+        let common = "openapi: 3.0.0\ncomponents:\n  schemas:\n    User: { type: object }\n";
+        let documents = vec![
+            create_document("specs/v1/common.yaml", common)?,
+            create_document("specs/v2/common.yaml", common)?,
+            create_document(
+                "specs/v2/pet.yaml",
+                r#"
+openapi: 3.0.0
+components:
+  schemas:
+    Pet:
+      properties:
+        owner: { $ref: 'common.yaml#/components/schemas/User' }
+        previous_owner: { $ref: '../v1/common.yaml#/components/schemas/User' }
+"#,
+            )?,
+        ];
+        let mut model = create_model(documents);
+        assert!(model.resolve_types().is_empty());
+        assert!(model.duplicate_items.is_empty());
+
+        // The references are relative to the referencing document
+        let mut keys: Vec<&String> = model.items.keys().collect();
+        keys.sort();
+        assert_eq!(keys, vec!["v1.common.User", "v2.common.User", "v2.pet.Pet"]);
+        assert_eq!(
+            get_member_type_keys(&model, "v2.pet.Pet"),
+            vec![
+                Some("v2.common.User".to_string()),
+                Some("v1.common.User".to_string())
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_interfaces() -> Result<(), Box<dyn std::error::Error>> {
+        let documents = vec![create_document(
+            "petstore.yaml",
+            r#"
+openapi: 3.0.0
+tags:
+  - name: store
+  - name: pet
+    description: Everything about your pets
+paths:
+  /pets:
+    get: { operationId: listPets, tags: [pet, store] }
+    post: { tags: [pet] }
+  /store/orders:
+    get: { operationId: listOrders, tags: [store] }
+  /health:
+    get: { operationId: health }
+"#,
+        )?];
+        let model = create_model(documents);
+
+        // An interface per tag, with the operations of their first tag
+        assert_eq!(
+            get_method_names(&model, "petstore.PetApi"),
+            vec!["listPets", "postPets"]
+        );
+        assert_eq!(
+            get_method_names(&model, "petstore.StoreApi"),
+            vec!["listOrders"]
+        );
+        assert_eq!(
+            get_method_names(&model, "petstore.DefaultApi"),
+            vec!["health"]
+        );
+        assert_eq!(
+            model.items["petstore.PetApi"].get_docu().blocks,
+            vec![model::Block::Paragraph(vec![model::Inline::Text(
+                "Everything about your pets".to_string()
+            )])]
+        );
+
+        Ok(())
+    }
+}
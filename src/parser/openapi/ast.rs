@@ -0,0 +1,117 @@
+use crate::parser::NodeRange;
+
+#[derive(Debug, PartialEq)]
+pub struct Document {
+    // Path of the parsed file, when known
+    pub path: Option<String>,
+    // e.g.: 3.0.3
+    pub version: String,
+    pub title: String,
+    pub tags: Vec<Tag>,
+    // components/schemas
+    pub schemas: Vec<NamedSchema>,
+    pub operations: Vec<Operation>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Tag {
+    pub name: String,
+    pub description: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct NamedSchema {
+    pub name: String,
+    pub schema: Schema,
+    pub location: NodeRange,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Schema {
+    pub description: String,
+    pub is_nullable: bool,
+    pub is_deprecated: bool,
+    pub kind: SchemaKind,
+}
+
+impl Schema {
+    pub fn new(kind: SchemaKind) -> Self {
+        Schema {
+            description: String::new(),
+            is_nullable: false,
+            is_deprecated: false,
+            kind,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SchemaKind {
+    // As written (e.g.: #/components/schemas/Pet or common.yaml#/components/schemas/Error)
+    Ref(String),
+    Object {
+        properties: Vec<Property>,
+        // Values of a map (e.g.: additionalProperties: { type: integer })
+        additional_properties: Option<Box<Schema>>,
+    },
+    Array(Box<Schema>),
+    // e.g.: string with enum: [available, sold]
+    Enum {
+        type_name: String,
+        values: Vec<String>,
+    },
+    // e.g.: integer with format: int64
+    Primitive {
+        type_name: String,
+        format: Option<String>,
+    },
+    AllOf(Vec<Schema>),
+    OneOf(Vec<Schema>),
+    AnyOf(Vec<Schema>),
+    // No type (any value)
+    Any,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Property {
+    pub name: String,
+    pub schema: Schema,
+    pub is_required: bool,
+    pub location: NodeRange,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Operation {
+    // e.g.: get
+    pub method: String,
+    // e.g.: /pets/{petId}
+    pub path: String,
+    pub operation_id: Option<String>,
+    // Summary and description
+    pub docu: String,
+    pub tags: Vec<String>,
+    pub parameters: Vec<Parameter>,
+    pub request_body: Option<RequestBody>,
+    // Schema of the first successful response with a content
+    pub response: Option<Schema>,
+    pub is_deprecated: bool,
+    pub location: NodeRange,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Parameter {
+    pub name: String,
+    // e.g.: path, query, header or cookie
+    pub parameter_in: String,
+    pub description: String,
+    pub is_required: bool,
+    pub is_deprecated: bool,
+    pub schema: Schema,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RequestBody {
+    pub description: String,
+    pub is_required: bool,
+    pub schema: Schema,
+}
@@ -0,0 +1,47 @@
+use strum_macros::EnumIter;
+
+use crate::model::TypeCategory;
+
+// Data types of the schemas (the formats, e.g. int64, are annotations), maps
+// (map<string, V>) and void for operations without response content
+#[derive(Debug, EnumIter)]
+pub enum DataType {
+    String,
+    Integer,
+    Number,
+    Boolean,
+    Object,
+    Map,
+    Void,
+}
+
+impl DataType {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            DataType::String => "string",
+            DataType::Integer => "integer",
+            DataType::Number => "number",
+            DataType::Boolean => "boolean",
+            DataType::Object => "object",
+            DataType::Map => "map",
+            DataType::Void => "void",
+        }
+    }
+
+    pub fn get_category(&self) -> TypeCategory {
+        match self {
+            DataType::String => TypeCategory::String,
+            DataType::Object => TypeCategory::Parcelable,
+            DataType::Map => TypeCategory::Container,
+            DataType::Void => TypeCategory::Void,
+            _ => TypeCategory::Primitive,
+        }
+    }
+
+    pub fn get_generic_arity(&self) -> usize {
+        match self {
+            DataType::Map => 2,
+            _ => 0,
+        }
+    }
+}
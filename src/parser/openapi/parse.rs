@@ -0,0 +1,824 @@
+use serde_yaml::{Mapping, Value};
+
+use crate::diagnostic::{Diagnostic, Severity, SourcePosition, SourceRange};
+use crate::parser::error::{INVALID_SYNTAX, UNSUPPORTED_VERSION};
+use crate::parser::openapi::ast::{
+    Document, NamedSchema, Operation, Parameter, Property, RequestBody, Schema, SchemaKind, Tag,
+};
+use crate::parser::NodeRange;
+
+// Maximum number of $ref followed to find a parameter, a request body or a
+// response (they may reference each other in a loop)
+const MAX_REF_DEPTH: usize = 8;
+
+const METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+// YAML or JSON document, None if it is not an OpenAPI document (e.g.: another
+// YAML file of the tree)
+pub fn parse(source: &str) -> Result<Option<Document>, Box<Diagnostic>> {
+    if !source.contains("openapi") {
+        return Ok(None);
+    }
+
+    // Other YAML or JSON files mentioning OpenAPI (e.g.: a CI configuration)
+    // are not reported when they are invalid
+    let root = match parse_value(source) {
+        Ok(root) => root,
+        Err(_) if !has_openapi_key(source) => return Ok(None),
+        Err(diagnostic) => return Err(diagnostic),
+    };
+
+    let version = match root.get("openapi").and_then(scalar_string) {
+        Some(version) => version,
+        None => return Ok(None),
+    };
+
+    let parser = Parser {
+        root: &root,
+        locator: Locator::new(source),
+    };
+
+    if !version.starts_with("3.") {
+        let mut diagnostic = Diagnostic::new(
+            Severity::Error,
+            UNSUPPORTED_VERSION,
+            format!("unsupported OpenAPI version {}", version),
+        );
+        if let Some((start, end)) = parser.locator.find(&["openapi"]) {
+            diagnostic.set_location(source, start, end);
+        }
+        diagnostic
+            .notes
+            .push("only OpenAPI 3.x documents are supported".to_string());
+        return Err(Box::new(diagnostic));
+    }
+
+    Ok(Some(Document {
+        path: None,
+        version,
+        title: text(root.get("info").and_then(|i| i.get("title"))),
+        tags: parser.tags(),
+        schemas: parser.schemas(),
+        operations: parser.operations(),
+    }))
+}
+
+// JSON is parsed as such, YAML parsers being picky about tabs
+fn parse_value(source: &str) -> Result<Value, Box<Diagnostic>> {
+    if source.trim_start().starts_with('{') {
+        serde_json::from_str(source).map_err(|e| {
+            let offset = offset_of(source, e.line(), e.column().max(1));
+            syntax_diagnostic(source, &e.to_string(), offset)
+        })
+    } else {
+        serde_yaml::from_str(source).map_err(|e| {
+            let offset = e
+                .location()
+                .and_then(|l| source.char_indices().nth(l.index()))
+                .map_or(source.len(), |(offset, _)| offset);
+            syntax_diagnostic(source, &e.to_string(), offset)
+        })
+    }
+}
+
+// Top-level openapi key, at the start of a line in YAML (e.g.: openapi: 3.0.0
+// or "openapi": "3.0.0" in JSON)
+fn has_openapi_key(source: &str) -> bool {
+    let is_json = source.trim_start().starts_with('{');
+
+    source.lines().any(|line| {
+        let line = if is_json {
+            line.trim_start().trim_start_matches('{').trim_start()
+        } else {
+            line
+        };
+
+        ["openapi", "\"openapi\"", "'openapi'"].iter().any(|key| {
+            line.strip_prefix(key)
+                .is_some_and(|rest| rest.trim_start().starts_with(':'))
+        })
+    })
+}
+
+fn syntax_diagnostic(source: &str, message: &str, offset: usize) -> Box<Diagnostic> {
+    // The location is given separately
+    let message = message.split(" at line ").next().unwrap_or(message);
+
+    let mut diagnostic = Diagnostic::new(
+        Severity::Error,
+        INVALID_SYNTAX,
+        format!("invalid document: {}", message),
+    );
+    let length = source[offset..].chars().next().map_or(0, char::len_utf8);
+    diagnostic.set_location(source, offset, offset + length);
+
+    Box::new(diagnostic)
+}
+
+// Byte offset of a position (line and column starting at 1)
+fn offset_of(source: &str, line: usize, column: usize) -> usize {
+    let line_offset: usize = source
+        .split('\n')
+        .take(line.saturating_sub(1))
+        .map(|l| l.len() + 1)
+        .sum();
+    let rest = &source[line_offset.min(source.len())..];
+
+    line_offset
+        + rest
+            .char_indices()
+            .nth(column - 1)
+            .map_or(rest.len(), |(offset, _)| offset)
+}
+
+// Creates the ast from the values of the document, the locations being found
+// in the source
+struct Parser<'a> {
+    root: &'a Value,
+    locator: Locator<'a>,
+}
+
+impl<'a> Parser<'a> {
+    fn tags(&self) -> Vec<Tag> {
+        sequence(self.root.get("tags"))
+            .iter()
+            .filter_map(|tag| {
+                Some(Tag {
+                    name: tag.get("name").and_then(scalar_string)?,
+                    description: text(tag.get("description")),
+                })
+            })
+            .collect()
+    }
+
+    // Example:
+    // - components: { schemas: { Pet: { type: object, ... } } }
+    fn schemas(&self) -> Vec<NamedSchema> {
+        let schemas = self.root.get("components").and_then(|c| c.get("schemas"));
+
+        entries(schemas)
+            .map(|(name, value)| {
+                let keys = ["components", "schemas", name];
+                NamedSchema {
+                    name: name.to_string(),
+                    schema: self.schema(value, &keys),
+                    location: self.locator.locate(&keys),
+                }
+            })
+            .collect()
+    }
+
+    // The keys lead to the schema in the document (e.g.: [components,
+    // schemas, Pet]), to locate its properties
+    fn schema(&self, value: &Value, keys: &[&str]) -> Schema {
+        // e.g.: type: integer, or type: [integer, "null"] (OpenAPI 3.1)
+        let types: Vec<&str> = match value.get("type") {
+            Some(Value::Sequence(types)) => types.iter().filter_map(Value::as_str).collect(),
+            Some(t) => t.as_str().into_iter().collect(),
+            None => Vec::new(),
+        };
+        let type_name = types.iter().find(|t| **t != "null").copied();
+
+        let kind = if let Some(reference) = value.get("$ref").and_then(Value::as_str) {
+            SchemaKind::Ref(reference.to_string())
+        } else if let Some(values) = value.get("enum").and_then(Value::as_sequence) {
+            SchemaKind::Enum {
+                type_name: type_name.unwrap_or("string").to_string(),
+                values: values.iter().filter_map(scalar_string).collect(),
+            }
+        } else if let Some(schemas) = value.get("allOf").and_then(Value::as_sequence) {
+            SchemaKind::AllOf(schemas.iter().map(|s| self.schema(s, keys)).collect())
+        } else if let Some(schemas) = value.get("oneOf").and_then(Value::as_sequence) {
+            SchemaKind::OneOf(schemas.iter().map(|s| self.schema(s, keys)).collect())
+        } else if let Some(schemas) = value.get("anyOf").and_then(Value::as_sequence) {
+            SchemaKind::AnyOf(schemas.iter().map(|s| self.schema(s, keys)).collect())
+        } else {
+            match type_name {
+                Some("array") => SchemaKind::Array(Box::new(match value.get("items") {
+                    Some(items) => self.schema(items, keys),
+                    None => Schema::new(SchemaKind::Any),
+                })),
+                Some("object") => self.object_schema(value, keys),
+                None if value.get("properties").is_some() => self.object_schema(value, keys),
+                Some(type_name) => SchemaKind::Primitive {
+                    type_name: type_name.to_string(),
+                    format: value.get("format").and_then(scalar_string),
+                },
+                None => SchemaKind::Any,
+            }
+        };
+
+        Schema {
+            description: text(value.get("description")),
+            is_nullable: types.contains(&"null")
+                || value.get("nullable").and_then(Value::as_bool) == Some(true),
+            is_deprecated: value.get("deprecated").and_then(Value::as_bool) == Some(true),
+            kind,
+        }
+    }
+
+    fn object_schema(&self, value: &Value, keys: &[&str]) -> SchemaKind {
+        let required: Vec<&str> = sequence(value.get("required"))
+            .iter()
+            .filter_map(Value::as_str)
+            .collect();
+
+        let properties = entries(value.get("properties"))
+            .map(|(name, property)| {
+                let property_keys = [keys, &["properties", name]].concat();
+                Property {
+                    name: name.to_string(),
+                    schema: self.schema(property, &property_keys),
+                    is_required: required.contains(&name),
+                    location: self.locator.locate(&property_keys),
+                }
+            })
+            .collect();
+
+        let additional_properties = match value.get("additionalProperties") {
+            Some(Value::Bool(true)) => Some(Box::new(Schema::new(SchemaKind::Any))),
+            Some(Value::Mapping(_)) => value
+                .get("additionalProperties")
+                .map(|v| Box::new(self.schema(v, keys))),
+            _ => None,
+        };
+
+        SchemaKind::Object {
+            properties,
+            additional_properties,
+        }
+    }
+
+    // Example:
+    // - paths: { /pets/{petId}: { get: { operationId: getPet, ... } } }
+    fn operations(&self) -> Vec<Operation> {
+        entries(self.root.get("paths"))
+            .flat_map(|(path, path_item)| {
+                METHODS.iter().filter_map(move |method| {
+                    let operation = path_item.get(*method)?;
+                    Some(self.operation(path, path_item, method, operation))
+                })
+            })
+            .collect()
+    }
+
+    fn operation(
+        &self,
+        path: &str,
+        path_item: &Value,
+        method: &str,
+        operation: &Value,
+    ) -> Operation {
+        // The parameters of the path apply to all its operations, unless
+        // overridden (i.e. with the same name and location)
+        let mut parameters: Vec<Parameter> = sequence(operation.get("parameters"))
+            .iter()
+            .filter_map(|p| self.parameter(p))
+            .collect();
+        let path_parameters: Vec<Parameter> = sequence(path_item.get("parameters"))
+            .iter()
+            .filter_map(|p| self.parameter(p))
+            .filter(|p| {
+                !parameters
+                    .iter()
+                    .any(|o| o.name == p.name && o.parameter_in == p.parameter_in)
+            })
+            .collect();
+        parameters.splice(0..0, path_parameters);
+
+        let request_body = operation
+            .get("requestBody")
+            .and_then(|b| self.resolve_ref(b))
+            .map(|body| RequestBody {
+                description: text(body.get("description")),
+                is_required: body.get("required").and_then(Value::as_bool) == Some(true),
+                schema: self
+                    .content_schema(body)
+                    .unwrap_or_else(|| Schema::new(SchemaKind::Any)),
+            });
+
+        // e.g.: 200, 2XX, or default when there is no successful response
+        let responses: Vec<(&str, &Value)> = entries(operation.get("responses")).collect();
+        let response = responses
+            .iter()
+            .filter(|(status, _)| status.starts_with('2'))
+            .chain(responses.iter().filter(|(status, _)| *status == "default"))
+            .filter_map(|(_, response)| self.resolve_ref(response))
+            .find_map(|response| self.content_schema(response));
+
+        let docu = [
+            text(operation.get("summary")),
+            text(operation.get("description")),
+        ]
+        .iter()
+        .filter(|t| !t.is_empty())
+        .cloned()
+        .collect::<Vec<String>>()
+        .join("\n\n");
+
+        Operation {
+            method: method.to_string(),
+            path: path.to_string(),
+            operation_id: operation.get("operationId").and_then(scalar_string),
+            docu,
+            tags: sequence(operation.get("tags"))
+                .iter()
+                .filter_map(scalar_string)
+                .collect(),
+            parameters,
+            request_body,
+            response,
+            is_deprecated: operation.get("deprecated").and_then(Value::as_bool) == Some(true),
+            location: self.locator.locate(&["paths", path, method]),
+        }
+    }
+
+    // Parameters referenced in another document are left out
+    fn parameter(&self, value: &Value) -> Option<Parameter> {
+        let parameter = self.resolve_ref(value)?;
+        let parameter_in = parameter.get("in").and_then(scalar_string)?;
+
+        Some(Parameter {
+            name: parameter.get("name").and_then(scalar_string)?,
+            description: text(parameter.get("description")),
+            // Path parameters are always required
+            is_required: parameter_in == "path"
+                || parameter.get("required").and_then(Value::as_bool) == Some(true),
+            is_deprecated: parameter.get("deprecated").and_then(Value::as_bool) == Some(true),
+            schema: match parameter.get("schema") {
+                Some(schema) => self.schema(schema, &[]),
+                None => self
+                    .content_schema(parameter)
+                    .unwrap_or_else(|| Schema::new(SchemaKind::Any)),
+            },
+            parameter_in,
+        })
+    }
+
+    // Schema of the JSON content, or of the first one
+    // Example:
+    // - content: { application/json: { schema: { $ref: ... } } }
+    fn content_schema(&self, value: &Value) -> Option<Schema> {
+        let content = value.get("content")?;
+        let media_type = content
+            .get("application/json")
+            .or_else(|| entries(Some(content)).next().map(|(_, m)| m))?;
+
+        media_type.get("schema").map(|s| self.schema(s, &[]))
+    }
+
+    // Follows the references within the document (e.g.:
+    // #/components/parameters/limit)
+    fn resolve_ref(&self, value: &'a Value) -> Option<&'a Value>
+    where
+        Self: 'a,
+    {
+        let mut value = value;
+
+        for _ in 0..MAX_REF_DEPTH {
+            let reference = match value.get("$ref").and_then(Value::as_str) {
+                Some(reference) => reference,
+                None => return Some(value),
+            };
+
+            value = reference
+                .strip_prefix("#/")?
+                .split('/')
+                .try_fold(self.root, |v, segment| {
+                    v.get(segment.replace("~1", "/").replace("~0", "~"))
+                })?;
+        }
+
+        None
+    }
+}
+
+// Where the keys of a document are declared, serde does not keep the
+// positions: each key is looked up in the lines indented deeper than its
+// parent key (flow styles, e.g. JSON on a single line, are located at their
+// outermost key found)
+struct Locator<'a> {
+    source: &'a str,
+    lines: Vec<Line<'a>>,
+}
+
+struct Line<'a> {
+    offset: usize,
+    indent: usize,
+    text: &'a str,
+}
+
+impl<'a> Locator<'a> {
+    fn new(source: &'a str) -> Self {
+        let mut offset = 0;
+        let mut lines = Vec::new();
+
+        for line in source.split('\n') {
+            let content = line.trim_end();
+            let text = content.trim_start();
+            if !text.is_empty() && !text.starts_with('#') {
+                lines.push(Line {
+                    offset,
+                    indent: content.len() - text.len(),
+                    text,
+                });
+            }
+            offset += line.len() + 1;
+        }
+
+        Locator { source, lines }
+    }
+
+    // Byte offsets of the line declaring the last key found
+    fn find(&self, keys: &[&str]) -> Option<(usize, usize)> {
+        let mut found: Option<(usize, &Line)> = None;
+
+        for key in keys {
+            let start = found.map_or(0, |(index, _)| index + 1);
+            let parent_indent = found.map(|(_, line)| line.indent);
+
+            let next = self.lines[start..]
+                .iter()
+                .enumerate()
+                .take_while(|(_, line)| parent_indent.is_none_or(|indent| line.indent > indent))
+                .find(|(_, line)| declares_key(line.text, key));
+
+            match next {
+                Some((index, line)) => found = Some((start + index, line)),
+                None => break,
+            }
+        }
+
+        found.map(|(_, line)| {
+            let start = line.offset + line.indent;
+            (start, start + line.text.len())
+        })
+    }
+
+    fn locate(&self, keys: &[&str]) -> NodeRange {
+        match self.find(keys) {
+            Some((start, end)) => NodeRange(SourceRange::new(
+                SourcePosition::from_offset(self.source, start),
+                SourcePosition::from_offset(self.source, end),
+            )),
+            None => NodeRange::default(),
+        }
+    }
+}
+
+// Examples:
+// - Pet:
+// - "Pet": {
+// - '/pets/{petId}':
+fn declares_key(text: &str, key: &str) -> bool {
+    ["\"", "'", ""]
+        .iter()
+        .filter_map(|quote| {
+            text.strip_prefix(quote)?
+                .strip_prefix(key)?
+                .strip_prefix(quote)
+        })
+        .any(|rest| rest.trim_start().starts_with(':'))
+}
+
+fn entries(value: Option<&Value>) -> impl Iterator<Item = (&str, &Value)> {
+    value
+        .and_then(Value::as_mapping)
+        .map(Mapping::iter)
+        .into_iter()
+        .flatten()
+        .filter_map(|(k, v)| Some((k.as_str()?, v)))
+}
+
+fn sequence(value: Option<&Value>) -> &[Value] {
+    value
+        .and_then(Value::as_sequence)
+        .map_or(&[], Vec::as_slice)
+}
+
+fn text(value: Option<&Value>) -> String {
+    value
+        .and_then(Value::as_str)
+        .map(|s| s.trim().to_string())
+        .unwrap_or_default()
+}
+
+// e.g.: 3.0.3, 42 or true
+fn scalar_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_document(source: &str) -> Result<Document, Box<dyn std::error::Error>> {
+        Ok(parse(source)?.ok_or("not an OpenAPI document")?)
+    }
+
+    fn primitive(type_name: &str, format: Option<&str>) -> Schema {
+        Schema::new(SchemaKind::Primitive {
+            type_name: type_name.to_string(),
+            format: format.map(|f| f.to_string()),
+        })
+    }
+
+    #[test]
+    fn test_document() -> Result<(), Box<dyn std::error::Error>> {
+        let document = parse_document(
+            r#"
+openapi: 3.0.3
+info:
+  title: Petstore
+tags:
+  - name: pet
+    description: Everything about your pets
+"#,
+        )?;
+
+        assert_eq!(document.version, "3.0.3");
+        assert_eq!(document.title, "Petstore");
+        assert_eq!(
+            document.tags,
+            vec![Tag {
+                name: "pet".to_string(),
+                description: "Everything about your pets".to_string(),
+            }]
+        );
+
+        // JSON
+        let document = parse_document(r#"{ "openapi": "3.1.0", "info": { "title": "X" } }"#)?;
+        assert_eq!(document.version, "3.1.0");
+
+        // Other YAML or JSON files
+        assert_eq!(parse("foo: bar\n")?, None);
+        assert_eq!(parse("openapi_version: 3\n")?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_schemas() -> Result<(), Box<dyn std::error::Error>> {
+        let document = parse_document(
+            r#"
+openapi: 3.1.0
+components:
+  schemas:
+    Pet:
+      description: A pet
+      required: [id]
+      properties:
+        id: { type: integer, format: int64 }
+        tag: { type: [string, "null"], deprecated: true }
+        owner: { $ref: 'common.yaml#/components/schemas/User' }
+        labels:
+          type: object
+          additionalProperties: true
+    Status:
+      enum: [available, sold]
+    Pets:
+      type: array
+      items: { $ref: '#/components/schemas/Pet' }
+    Animal:
+      oneOf:
+        - $ref: '#/components/schemas/Pet'
+        - type: string
+"#,
+        )?;
+
+        let names: Vec<&str> = document.schemas.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["Pet", "Status", "Pets", "Animal"]);
+
+        let mut tag = primitive("string", None);
+        tag.is_nullable = true;
+        tag.is_deprecated = true;
+        assert_eq!(
+            document.schemas[0].schema,
+            Schema {
+                description: "A pet".to_string(),
+                is_nullable: false,
+                is_deprecated: false,
+                kind: SchemaKind::Object {
+                    properties: vec![
+                        Property {
+                            name: "id".to_string(),
+                            schema: primitive("integer", Some("int64")),
+                            is_required: true,
+                            location: NodeRange::default(),
+                        },
+                        Property {
+                            name: "tag".to_string(),
+                            schema: tag,
+                            is_required: false,
+                            location: NodeRange::default(),
+                        },
+                        Property {
+                            name: "owner".to_string(),
+                            schema: Schema::new(SchemaKind::Ref(
+                                "common.yaml#/components/schemas/User".to_string()
+                            )),
+                            is_required: false,
+                            location: NodeRange::default(),
+                        },
+                        Property {
+                            name: "labels".to_string(),
+                            schema: Schema::new(SchemaKind::Object {
+                                properties: Vec::new(),
+                                additional_properties: Some(Box::new(Schema::new(SchemaKind::Any))),
+                            }),
+                            is_required: false,
+                            location: NodeRange::default(),
+                        },
+                    ],
+                    additional_properties: None,
+                },
+            }
+        );
+        assert_eq!(
+            document.schemas[1].schema,
+            Schema::new(SchemaKind::Enum {
+                type_name: "string".to_string(),
+                values: vec!["available".to_string(), "sold".to_string()],
+            })
+        );
+        assert_eq!(
+            document.schemas[2].schema,
+            Schema::new(SchemaKind::Array(Box::new(Schema::new(SchemaKind::Ref(
+                "#/components/schemas/Pet".to_string()
+            )))))
+        );
+        assert_eq!(
+            document.schemas[3].schema,
+            Schema::new(SchemaKind::OneOf(vec![
+                Schema::new(SchemaKind::Ref("#/components/schemas/Pet".to_string())),
+                primitive("string", None),
+            ]))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_operations() -> Result<(), Box<dyn std::error::Error>> {
+        let document = parse_document(
+            r#"
+openapi: 3.0.0
+paths:
+  /pets/{petId}:
+    parameters:
+      - $ref: '#/components/parameters/PetId'
+    get:
+      operationId: showPetById
+      summary: Info for a specific pet
+      description: Details
+      tags: [pets]
+      responses:
+        '404':
+          description: Not found
+        '200':
+          $ref: '#/components/responses/Pet'
+    put:
+      deprecated: true
+      parameters:
+        - { name: verbose, in: query, schema: { type: boolean } }
+      requestBody:
+        required: true
+        content:
+          application/xml:
+            schema: { type: string }
+          application/json:
+            schema: { $ref: '#/components/schemas/Pet' }
+      responses:
+        default:
+          description: Error
+          content:
+            application/json:
+              schema: { $ref: '#/components/schemas/Error' }
+components:
+  parameters:
+    PetId: { name: petId, in: path, schema: { type: string } }
+  responses:
+    Pet:
+      content:
+        application/json:
+          schema: { $ref: '#/components/schemas/Pet' }
+"#,
+        )?;
+
+        let pet = Schema::new(SchemaKind::Ref("#/components/schemas/Pet".to_string()));
+        let pet_id = Parameter {
+            name: "petId".to_string(),
+            parameter_in: "path".to_string(),
+            description: String::new(),
+            is_required: true,
+            is_deprecated: false,
+            schema: primitive("string", None),
+        };
+
+        assert_eq!(
+            document.operations,
+            vec![
+                Operation {
+                    method: "get".to_string(),
+                    path: "/pets/{petId}".to_string(),
+                    operation_id: Some("showPetById".to_string()),
+                    docu: "Info for a specific pet\n\nDetails".to_string(),
+                    tags: vec!["pets".to_string()],
+                    parameters: vec![pet_id],
+                    request_body: None,
+                    response: Some(pet.clone()),
+                    is_deprecated: false,
+                    location: NodeRange::default(),
+                },
+                Operation {
+                    method: "put".to_string(),
+                    path: "/pets/{petId}".to_string(),
+                    operation_id: None,
+                    docu: String::new(),
+                    tags: Vec::new(),
+                    parameters: vec![
+                        Parameter {
+                            name: "petId".to_string(),
+                            parameter_in: "path".to_string(),
+                            description: String::new(),
+                            is_required: true,
+                            is_deprecated: false,
+                            schema: primitive("string", None),
+                        },
+                        Parameter {
+                            name: "verbose".to_string(),
+                            parameter_in: "query".to_string(),
+                            description: String::new(),
+                            is_required: false,
+                            is_deprecated: false,
+                            schema: primitive("boolean", None),
+                        },
+                    ],
+                    request_body: Some(RequestBody {
+                        description: String::new(),
+                        is_required: true,
+                        schema: pet,
+                    }),
+                    response: Some(Schema::new(SchemaKind::Ref(
+                        "#/components/schemas/Error".to_string()
+                    ))),
+                    is_deprecated: true,
+                    location: NodeRange::default(),
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_diagnostics() -> Result<(), Box<dyn std::error::Error>> {
+        let diagnostic = parse("openapi: 3.0.0\ninfo:\n  title: [a,\n").unwrap_err();
+        assert_eq!(diagnostic.code, INVALID_SYNTAX);
+
+        let diagnostic = parse("{\n  \"openapi\": \"3.0.0\",\n  \"info\": }\n").unwrap_err();
+        assert_eq!(diagnostic.code, INVALID_SYNTAX);
+        assert_eq!(diagnostic.range.map(|r| r.start.line), Some(3));
+
+        // Other invalid files mentioning OpenAPI
+        assert_eq!(parse("# Generate the openapi client\njobs: [a,\n")?, None);
+        assert_eq!(parse("tools:\n  openapi: 3.0.0\n  x: [a,\n")?, None);
+        assert_eq!(parse("{ \"tools\": [\"openapi\" }")?, None);
+
+        let diagnostic = parse("swagger: '2.0'\nopenapi: '2.0'\n").unwrap_err();
+        assert_eq!(diagnostic.code, UNSUPPORTED_VERSION);
+        assert_eq!(diagnostic.range.map(|r| r.start.line), Some(2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_locations() -> Result<(), Box<dyn std::error::Error>> {
+        let document = parse_document(
+            "openapi: 3.0.0\ncomponents:\n  schemas:\n    Pet:\n      properties:\n        \"id\": { type: integer }\npaths:\n  /pets:\n    get: {}\n",
+        )?;
+
+        let pet = &document.schemas[0];
+        assert_eq!(pet.location.start, SourcePosition::new(4, 5));
+        assert_eq!(pet.location.end, SourcePosition::new(4, 9));
+        match pet.schema.kind {
+            SchemaKind::Object { ref properties, .. } => {
+                assert_eq!(properties[0].location.start, SourcePosition::new(6, 9));
+            }
+            _ => panic!("expected an object"),
+        }
+        assert_eq!(
+            document.operations[0].location.start,
+            SourcePosition::new(9, 5)
+        );
+
+        Ok(())
+    }
+}
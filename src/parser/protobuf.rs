@@ -0,0 +1,508 @@
+pub mod ast;
+mod parse;
+mod scalar;
+
+use crate::diagnostic::Diagnostic;
+use crate::model::{self, Model};
+use crate::parser::front_end::FrontEnd;
+pub use parse::parse;
+use scalar::Scalar;
+use std::collections::HashSet;
+use std::rc::Rc;
+use strum::IntoEnumIterator;
+
+#[derive(Default)]
+pub struct ProtobufFrontEnd {
+    files: Vec<ast::File>,
+}
+
+impl FrontEnd for ProtobufFrontEnd {
+    fn get_extensions(&self) -> &[&'static str] {
+        &["proto"]
+    }
+
+    fn parse(&mut self, path: &str, source: &str) -> Option<Vec<Diagnostic>> {
+        match parse(source) {
+            Ok(mut file) => {
+                file.path = Some(path.to_string());
+                self.files.push(file);
+                Some(Vec::new())
+            }
+            Err(diagnostic) => Some(vec![*diagnostic]),
+        }
+    }
+
+    fn create_model(&mut self) -> Model {
+        create_model(std::mem::take(&mut self.files))
+    }
+}
+
+// Messages are mapped to structs, enums to enums and services to interfaces
+pub fn create_model(files: Vec<ast::File>) -> Model {
+    let mut model = Model::default();
+
+    // Root package
+    let root_pkg = Rc::new(model::Package::new("", model::Idl::Protobuf));
+
+    // Keys of all the declared items, to resolve the type names
+    let mut keys = HashSet::new();
+    files
+        .iter()
+        .for_each(|file| collect_keys(&file.package, &file.items, &mut keys));
+
+    // Go through files
+    files.into_iter().for_each(|file| {
+        let context = Context {
+            pkg: Rc::new(model::Package::new(&file.package, model::Idl::Protobuf)),
+            path: file.path.as_deref(),
+            keys: &keys,
+        };
+
+        add_model_items(&mut model, &context, None, file.items);
+    });
+
+    // Create standard types
+    let standard_types = Scalar::iter()
+        .map(|s| {
+            model::StandardType::new(
+                s.get_name(),
+                s.get_category(),
+                s.get_generic_arity(),
+                root_pkg.clone(),
+            )
+        })
+        .map(|st| (st.get_key().clone(), Rc::new(st)))
+        .collect();
+    model
+        .standard_types
+        .insert(model::Idl::Protobuf, standard_types);
+
+    // The types are resolved separately (see Model::resolve_types)
+    model
+}
+
+// What the items of a file share
+struct Context<'a> {
+    pkg: Rc<model::Package>,
+    path: Option<&'a str>,
+    keys: &'a HashSet<model::Key>,
+}
+
+impl Context<'_> {
+    // Proto scoping rules: a name is looked up from the innermost scope
+    // (e.g.: a.b.Outer.Inner, then a.b.Outer, a.b and a) to the root, unless
+    // fully qualified (e.g.: .a.b.Foo). Names not found are kept as written.
+    fn resolve_name(&self, name: &str, scope: &str) -> String {
+        if let Some(qualified_name) = name.strip_prefix('.') {
+            return [qualified_name, name]
+                .iter()
+                .find(|key| self.keys.contains(**key))
+                .unwrap_or(&name)
+                .to_string();
+        }
+
+        if Scalar::iter().any(|s| s.get_name() == name) {
+            return name.to_string();
+        }
+
+        let mut current_scope = Some(scope);
+        while let Some(scope) = current_scope {
+            let key = format!("{}.{}", scope, name);
+            if self.keys.contains(&key) {
+                return key;
+            }
+
+            current_scope = scope.rfind('.').map(|pos| &scope[..pos]);
+        }
+
+        // Items of files without package
+        let key = format!(".{}", name);
+        if self.keys.contains(&key) {
+            return key;
+        }
+
+        name.to_string()
+    }
+}
+
+// Same keys as the model items (e.g.: a.b.Outer.Inner)
+fn collect_keys(scope: &str, items: &[ast::Item], keys: &mut HashSet<model::Key>) {
+    items.iter().for_each(|item| match item {
+        ast::Item::Message {
+            name, nested_items, ..
+        } => {
+            let key = format!("{}.{}", scope, name);
+            collect_keys(&key, nested_items, keys);
+            keys.insert(key);
+        }
+        ast::Item::Enum { name, .. } | ast::Item::Service { name, .. } => {
+            keys.insert(format!("{}.{}", scope, name));
+        }
+    });
+}
+
+fn add_model_items(
+    model: &mut Model,
+    context: &Context,
+    parent: Option<&model::Key>,
+    ast_items: Vec<ast::Item>,
+) {
+    ast_items.into_iter().for_each(|item| match item {
+        ast::Item::Message {
+            name,
+            docu,
+            fields,
+            nested_items,
+            options,
+            location,
+        } => {
+            let key = model::item_key(&context.pkg, parent, &name);
+            let strukt = model::Struct::new(
+                context.pkg.clone(),
+                parent.cloned(),
+                Vec::new(),
+                name,
+                Vec::new(),
+                model::Javadoc::parse(&docu),
+                fields
+                    .iter()
+                    .enumerate()
+                    .map(|(index, f)| create_model_member(index, context, &key, f))
+                    .collect(),
+                create_model_annotations(&options),
+                model::Location::new(context.path, location.0),
+            );
+
+            model.insert_item(model::Item::Struct(strukt));
+            add_model_items(model, context, Some(&key), nested_items);
+        }
+        ast::Item::Enum {
+            name,
+            docu,
+            values,
+            options,
+            location,
+        } => {
+            let enumeration = model::Enum::new(
+                context.pkg.clone(),
+                parent.cloned(),
+                Vec::new(),
+                name,
+                model::Javadoc::parse(&docu),
+                Rc::new(model::Type::Unresolved(model::UnresolvedType::new(
+                    context.pkg.clone(),
+                    Scalar::Int32.get_name(),
+                ))),
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(index, v)| create_model_enum_element(index, context, v))
+                    .collect(),
+                create_model_annotations(&options),
+                model::Location::new(context.path, location.0),
+            );
+            model.insert_item(model::Item::Enum(enumeration));
+        }
+        ast::Item::Service {
+            name,
+            docu,
+            rpcs,
+            options,
+            location,
+        } => {
+            let key = model::item_key(&context.pkg, parent, &name);
+            let interface = model::Interface::new(
+                context.pkg.clone(),
+                parent.cloned(),
+                Vec::new(),
+                name,
+                Vec::new(),
+                model::Javadoc::parse(&docu),
+                Vec::new(),
+                rpcs.iter()
+                    .enumerate()
+                    .map(|(index, r)| create_model_method(index, context, &key, r))
+                    .collect(),
+                create_model_annotations(&options),
+                model::Location::new(context.path, location.0),
+            );
+            model.insert_item(model::Item::Interface(interface));
+        }
+    });
+}
+
+// Examples:
+// - repeated Foo foos = 1; => Foo[] foos
+// - map<string, Foo> foos = 1; => map<string, Foo> foos
+// - optional int32 id = 1 [default = 42]; => @optional int32 id = 42
+fn create_model_member(
+    index: usize,
+    context: &Context,
+    scope: &model::Key,
+    field: &ast::Field,
+) -> model::Member {
+    let location = model::Location::new(context.path, field.location.0);
+
+    let (type_name, generic_args) = match field.field_type {
+        ast::FieldType::Named(ref name) => (context.resolve_name(name, scope), Vec::new()),
+        ast::FieldType::Map(ref key_type, ref value_type) => (
+            Scalar::Map.get_name().to_string(),
+            [key_type, value_type]
+                .iter()
+                .map(|t| {
+                    model::Arg::new(
+                        model::Direction::Unspecified,
+                        String::new(),
+                        Rc::new(create_model_type(context, &context.resolve_name(t, scope))),
+                        Vec::new(),
+                        Vec::new(),
+                        location.clone(),
+                    )
+                })
+                .collect(),
+        ),
+    };
+
+    let element_type = create_model_type(context, &type_name);
+    let member_type = match field.label {
+        ast::Label::Repeated => model::Type::Array(model::ArrayType::new(
+            Rc::new(element_type),
+            vec![model::ArrayDimension::Dynamic],
+        )),
+        _ => element_type,
+    };
+
+    let mut annotations = Vec::new();
+    match field.label {
+        ast::Label::Optional => annotations.push(model::Annotation::new("optional", Vec::new())),
+        ast::Label::Required => annotations.push(model::Annotation::new("required", Vec::new())),
+        ast::Label::Unspecified | ast::Label::Repeated => (),
+    }
+    if let Some(ref oneof) = field.oneof {
+        annotations.push(model::Annotation::new(
+            "oneof",
+            vec![model::AnnotationParam::new(
+                "value",
+                model::Expression::Literal(model::Literal::String(oneof.clone())),
+            )],
+        ));
+    }
+
+    // Proto2 default values (enum values are named in the scope of their enum)
+    let (defaults, options): (Vec<&ast::ProtoOption>, Vec<&ast::ProtoOption>) =
+        field.options.iter().partition(|o| o.name == "default");
+    let default_value = defaults.first().map(|o| match o.value {
+        ast::Constant::Identifier(ref name) if context.keys.contains(&type_name) => {
+            model::Expression::Reference(format!("{}.{}", type_name, name))
+        }
+        _ => create_model_constant(&o.value),
+    });
+    annotations.extend(options.into_iter().filter_map(create_model_annotation));
+
+    model::Member::new(
+        model::Arg::new(
+            model::Direction::Unspecified,
+            field.name.clone(),
+            Rc::new(member_type),
+            generic_args,
+            Vec::new(),
+            location.clone(),
+        ),
+        default_value,
+        None,
+        index,
+        model::Javadoc::parse(&field.docu),
+        annotations,
+        location,
+    )
+}
+
+fn create_model_enum_element(
+    index: usize,
+    context: &Context,
+    value: &ast::EnumValue,
+) -> model::EnumElement {
+    // Enum elements have no annotations: a deprecated value is deprecated by
+    // its docu
+    let mut docu = model::Javadoc::parse(&value.docu);
+    let is_deprecated = value
+        .options
+        .iter()
+        .any(|o| o.name == "deprecated" && o.value == ast::Constant::Bool(true));
+    if is_deprecated && docu.deprecated.is_none() {
+        docu.deprecated = Some(model::Text::new());
+    }
+
+    model::EnumElement {
+        name: value.name.clone(),
+        value: Some(create_model_number(&value.number, model::Literal::Int)),
+        computed_value: None,
+        index,
+        docu,
+        location: model::Location::new(context.path, value.location.0),
+    }
+}
+
+// Example:
+// - rpc Watch(WatchRequest) returns (stream Event);
+//   => @stream Event Watch(in WatchRequest request)
+fn create_model_method(
+    index: usize,
+    context: &Context,
+    scope: &model::Key,
+    rpc: &ast::Rpc,
+) -> model::Method {
+    let location = model::Location::new(context.path, rpc.location.0);
+
+    let create_arg = |direction, name: &str, type_name: &str, is_stream| {
+        model::Arg::new(
+            direction,
+            name.to_string(),
+            Rc::new(create_model_type(
+                context,
+                &context.resolve_name(type_name, scope),
+            )),
+            Vec::new(),
+            if is_stream {
+                vec![model::Annotation::new("stream", Vec::new())]
+            } else {
+                Vec::new()
+            },
+            location.clone(),
+        )
+    };
+
+    model::Method::new(
+        false,
+        &rpc.name,
+        create_arg(
+            model::Direction::Unspecified,
+            "",
+            &rpc.response,
+            rpc.response_stream,
+        ),
+        vec![create_arg(
+            model::Direction::In,
+            "request",
+            &rpc.request,
+            rpc.request_stream,
+        )],
+        model::TransactionCode::Implicit(index as u32),
+        index,
+        model::Javadoc::parse(&rpc.docu),
+        create_model_annotations(&rpc.options),
+        location,
+    )
+}
+
+fn create_model_annotations(options: &[ast::ProtoOption]) -> Vec<model::Annotation> {
+    options.iter().filter_map(create_model_annotation).collect()
+}
+
+// Examples:
+// - deprecated = true => @deprecated (and nothing when false)
+// - json_name = "id" => @json_name("id")
+fn create_model_annotation(option: &ast::ProtoOption) -> Option<model::Annotation> {
+    match (option.name.as_str(), &option.value) {
+        ("deprecated", ast::Constant::Bool(true)) => {
+            Some(model::Annotation::new("deprecated", Vec::new()))
+        }
+        ("deprecated", ast::Constant::Bool(false)) => None,
+        (name, value) => Some(model::Annotation::new(
+            name,
+            vec![model::AnnotationParam::new(
+                "value",
+                create_model_constant(value),
+            )],
+        )),
+    }
+}
+
+fn create_model_constant(constant: &ast::Constant) -> model::Expression {
+    match constant {
+        ast::Constant::Identifier(name) => model::Expression::Reference(name.clone()),
+        ast::Constant::Int(i) => create_model_number(i, model::Literal::Int),
+        ast::Constant::Float(f) => create_model_number(f, model::Literal::Float),
+        ast::Constant::String(s) | ast::Constant::Aggregate(s) => {
+            model::Expression::Literal(model::Literal::String(s.clone()))
+        }
+        ast::Constant::Bool(b) => model::Expression::Literal(model::Literal::Bool(*b)),
+    }
+}
+
+// The sign is an operator of the expression (e.g.: -1 => Minus 1)
+fn create_model_number(
+    number: &str,
+    create_literal: fn(String) -> model::Literal,
+) -> model::Expression {
+    match number.strip_prefix('-') {
+        Some(digits) => model::Expression::Unary(
+            model::UnaryOp::Minus,
+            Box::new(model::Expression::Literal(create_literal(
+                digits.to_string(),
+            ))),
+        ),
+        None => {
+            model::Expression::Literal(create_literal(number.trim_start_matches('+').to_string()))
+        }
+    }
+}
+
+fn create_model_type(context: &Context, type_name: &str) -> model::Type {
+    model::Type::Unresolved(model::UnresolvedType::new(context.pkg.clone(), type_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_member_type_keys(model: &Model, key: &str) -> Vec<Option<model::Key>> {
+        model.items[key]
+            .get_members()
+            .iter()
+            .map(|m| match *m.arg.arg_type {
+                model::Type::Item(ref item) => Some(item.get_key().clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_scopes() -> Result<(), Box<dyn std::error::Error>> {
+        let files = vec![
+            parse("syntax = \"proto3\";\nmessage Root {}\n")?,
+            parse("syntax = \"proto3\";\npackage a;\nmessage Foo {}\nmessage Bar {}\n")?,
+            parse(
+                "syntax = \"proto3\";\npackage a.b;\n\
+                 message Bar {}\n\
+                 message Outer {\n\
+                   message Foo {}\n\
+                   message Inner { Foo foo = 1; Bar bar = 2; }\n\
+                   .a.Foo qualified = 1;\n\
+                   a.Bar relative = 2;\n\
+                   Root root = 3;\n\
+                   int32 scalar = 4;\n\
+                 }\n",
+            )?,
+        ];
+        let mut model = create_model(files);
+        assert!(model.resolve_types().is_empty());
+
+        let key = |k: &str| Some(k.to_string());
+
+        // Innermost scopes first (a.b.Outer.Inner, a.b.Outer, then a.b)
+        assert_eq!(
+            get_member_type_keys(&model, "a.b.Outer.Inner"),
+            vec![key("a.b.Outer.Foo"), key("a.b.Bar")]
+        );
+
+        // Fully qualified names, names relative to an outer package and items
+        // of files without package
+        assert_eq!(
+            get_member_type_keys(&model, "a.b.Outer"),
+            vec![key("a.Foo"), key("a.Bar"), key(".Root"), None]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,150 @@
+use crate::parser::NodeRange;
+
+#[derive(Debug)]
+pub struct File {
+    // Path of the parsed file, when known
+    pub path: Option<String>,
+    // e.g.: proto3 (proto2 when not given)
+    pub syntax: Option<String>,
+    pub package: String,
+    pub imports: Vec<String>,
+    pub options: Vec<ProtoOption>,
+    pub items: Vec<Item>,
+}
+
+pub enum FileElement {
+    Package(String),
+    Import(String),
+    Option(ProtoOption),
+    Item(Item),
+    // e.g.: extend Foo { ... }
+    Ignored,
+}
+
+pub enum MessageElement {
+    // The fields of a oneof are fields of the message
+    Fields(Vec<Field>),
+    // The field of a group and the message it declares
+    Group(Box<Field>, Item),
+    Item(Item),
+    Option(ProtoOption),
+    // e.g.: reserved 2, 15; or extensions 100 to 199;
+    Ignored,
+}
+
+pub enum ServiceElement {
+    Rpc(Rpc),
+    Option(ProtoOption),
+    Ignored,
+}
+
+pub enum EnumElement {
+    Value(EnumValue),
+    Option(ProtoOption),
+    Ignored,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Item {
+    Message {
+        name: String,
+        docu: String,
+        fields: Vec<Field>,
+        nested_items: Vec<Item>,
+        options: Vec<ProtoOption>,
+        location: NodeRange,
+    },
+    Enum {
+        name: String,
+        docu: String,
+        values: Vec<EnumValue>,
+        options: Vec<ProtoOption>,
+        location: NodeRange,
+    },
+    Service {
+        name: String,
+        docu: String,
+        rpcs: Vec<Rpc>,
+        options: Vec<ProtoOption>,
+        location: NodeRange,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Label {
+    Unspecified,
+    Optional,
+    Required,
+    Repeated,
+}
+
+// Names are kept as written (e.g.: .a.b.Foo or Foo.Bar), they are resolved
+// when creating the model
+#[derive(Debug, PartialEq)]
+pub enum FieldType {
+    Named(String),
+    Map(String, String),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Field {
+    pub name: String,
+    pub docu: String,
+    pub label: Label,
+    pub field_type: FieldType,
+    pub number: String,
+    // Name of the oneof containing the field
+    pub oneof: Option<String>,
+    pub options: Vec<ProtoOption>,
+    pub location: NodeRange,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct EnumValue {
+    pub name: String,
+    pub docu: String,
+    pub number: String,
+    pub options: Vec<ProtoOption>,
+    pub location: NodeRange,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Rpc {
+    pub name: String,
+    pub docu: String,
+    pub request: String,
+    pub request_stream: bool,
+    pub response: String,
+    pub response_stream: bool,
+    pub options: Vec<ProtoOption>,
+    pub location: NodeRange,
+}
+
+// Examples:
+// - java_package = "a.b"
+// - (my.option).field = 42
+#[derive(Debug, PartialEq)]
+pub struct ProtoOption {
+    pub name: String,
+    pub value: Constant,
+}
+
+impl ProtoOption {
+    pub fn new<S: Into<String>>(name: S, value: Constant) -> Self {
+        ProtoOption {
+            name: name.into(),
+            value,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Constant {
+    Identifier(String),
+    Int(String),
+    Float(String),
+    String(String),
+    Bool(bool),
+    // Text of a message literal (e.g.: { a: 1 b: "x" }), braces excluded
+    Aggregate(String),
+}
@@ -0,0 +1,1167 @@
+use nom::{
+    branch::alt,
+    bytes::complete::{is_not, tag, tag_no_case, take_until, take_while1},
+    character::complete::{
+        alpha1, alphanumeric1, anychar, char, digit0, digit1, hex_digit1, multispace0, multispace1,
+        not_line_ending, one_of, space0,
+    },
+    combinator::{all_consuming, cut, eof, map, not, opt, recognize, value},
+    error::context,
+    multi::{many0, separated_list1},
+    sequence::{delimited, pair, preceded, terminated, tuple},
+};
+use nom_locate::LocatedSpan;
+
+use crate::diagnostic::{Diagnostic, SourcePosition, SourceRange};
+use crate::parser::error::{SyntaxError, SyntaxErrorKind};
+use crate::parser::protobuf::ast::{
+    Constant, EnumElement, EnumValue, Field, FieldType, File, FileElement, Item, Label,
+    MessageElement, ProtoOption, Rpc, ServiceElement,
+};
+use crate::parser::NodeRange;
+
+pub type Span<'a> = LocatedSpan<&'a str>;
+
+type IResult<I, O, E = SyntaxError<I>> = nom::IResult<I, O, E>;
+
+pub fn parse(source: &str) -> Result<File, Box<Diagnostic>> {
+    let input = Span::new(source);
+
+    match all_consuming(parse_proto)(input) {
+        Ok((_, file)) => Ok(file),
+        Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) => Err(Box::new(
+            e.map_input(|i| i.location_offset()).to_diagnostic(source),
+        )),
+        Err(nom::Err::Incomplete(_)) => unreachable!(),
+    }
+}
+
+// Proto file:
+// - [<syntax>]
+// - 0+ <import>, <package>, <option> or <item> (in any order)
+fn parse_proto(input: Span) -> IResult<Span, File> {
+    let (input, syntax) = opt(context("syntax declaration", parse_syntax))(input)?;
+    let (input, elements) = many0(preceded(not(pair(sp, eof)), cut(parse_file_element)))(input)?;
+    let (input, _) = sp(input)?;
+
+    let mut file = File {
+        path: None,
+        syntax,
+        package: String::new(),
+        imports: Vec::new(),
+        options: Vec::new(),
+        items: Vec::new(),
+    };
+
+    elements.into_iter().for_each(|e| match e {
+        FileElement::Package(package) => file.package = package,
+        FileElement::Import(import) => file.imports.push(import),
+        FileElement::Option(option) => file.options.push(option),
+        FileElement::Item(item) => file.items.push(item),
+        FileElement::Ignored => (),
+    });
+
+    Ok((input, file))
+}
+
+fn parse_file_element(input: Span) -> IResult<Span, FileElement> {
+    alt((
+        map(context("import", parse_import), FileElement::Import),
+        map(
+            context("package declaration", parse_package),
+            FileElement::Package,
+        ),
+        map(
+            context("option", parse_option_statement),
+            FileElement::Option,
+        ),
+        map(
+            context("message declaration", parse_message),
+            FileElement::Item,
+        ),
+        map(context("enum declaration", parse_enum), FileElement::Item),
+        map(
+            context("service declaration", parse_service),
+            FileElement::Item,
+        ),
+        map(context("extend declaration", parse_extend), |_| {
+            FileElement::Ignored
+        }),
+        map(parse_empty_statement, |_| FileElement::Ignored),
+        parse_invalid_element,
+    ))(input)
+}
+
+// Examples:
+// - syntax = "proto3";
+// - edition = "2023";
+fn parse_syntax(input: Span) -> IResult<Span, String> {
+    let (input, _) = extract_docu(input)?;
+    let (input, _) = alt((keyword("syntax"), keyword("edition")))(input)?;
+    let (input, (_, syntax, _)) =
+        cut(tuple((ws(char('=')), ws(string_literal), ws(char(';')))))(input)?;
+
+    Ok((input, syntax))
+}
+
+// Examples:
+// - import "a/b/foo.proto";
+// - import public "a/b/foo.proto";
+fn parse_import(input: Span) -> IResult<Span, String> {
+    let (input, _) = ws(keyword("import"))(input)?;
+    let (input, (_, path, _)) = cut(tuple((
+        opt(ws(alt((keyword("weak"), keyword("public"))))),
+        ws(string_literal),
+        ws(char(';')),
+    )))(input)?;
+
+    Ok((input, path))
+}
+
+// Examples:
+// - package x;
+// - package x.y.z;
+fn parse_package(input: Span) -> IResult<Span, String> {
+    let (input, _) = ws(keyword("package"))(input)?;
+    let (input, package) = cut(terminated(ws(full_identifier), ws(char(';'))))(input)?;
+
+    Ok((input, package.to_string()))
+}
+
+// Example:
+// - option java_package = "a.b";
+fn parse_option_statement(input: Span) -> IResult<Span, ProtoOption> {
+    preceded(
+        ws(keyword("option")),
+        cut(terminated(parse_option, ws(char(';')))),
+    )(input)
+}
+
+// Examples:
+// - deprecated = true
+// - (my.option).field = 42
+fn parse_option(input: Span) -> IResult<Span, ProtoOption> {
+    let (input, (name, _, value)) =
+        tuple((ws(option_name), ws(char('=')), ws(parse_constant)))(input)?;
+
+    Ok((input, ProtoOption::new(*name, value)))
+}
+
+fn option_name(input: Span) -> IResult<Span, Span> {
+    recognize(pair(
+        alt((identifier, delimited(char('('), type_name, char(')')))),
+        many0(pair(char('.'), identifier)),
+    ))(input)
+}
+
+// Example:
+// - [deprecated = true, json_name = "id"]
+fn parse_field_options(input: Span) -> IResult<Span, Vec<ProtoOption>> {
+    map(
+        opt(delimited(
+            ws(char('[')),
+            separated_list1(ws(char(',')), parse_option),
+            ws(char(']')),
+        )),
+        Option::unwrap_or_default,
+    )(input)
+}
+
+// Examples:
+// - true
+// - FOO
+// - -42
+// - 1.5e3
+// - "text"
+// - { a: 1 b: "x" }
+fn parse_constant(input: Span) -> IResult<Span, Constant> {
+    alt((
+        map(string_literal, Constant::String),
+        map(parse_aggregate, Constant::Aggregate),
+        map(parse_float, |f| Constant::Float(f.to_string())),
+        map(parse_int, |i| Constant::Int(i.to_string())),
+        map(full_identifier, |i| match *i.fragment() {
+            "true" => Constant::Bool(true),
+            "false" => Constant::Bool(false),
+            _ => Constant::Identifier(i.to_string()),
+        }),
+    ))(input)
+}
+
+// Examples:
+// - 42
+// - -0x1F
+// - 017 (octal)
+fn parse_int(input: Span) -> IResult<Span, Span> {
+    recognize(pair(
+        opt(one_of("+-")),
+        alt((recognize(pair(tag_no_case("0x"), hex_digit1)), digit1)),
+    ))(input)
+}
+
+// Examples:
+// - 1.5
+// - -.5e-3
+// - 1e10
+// - -inf
+fn parse_float(input: Span) -> IResult<Span, Span> {
+    let exponent = || tuple((one_of("eE"), opt(one_of("+-")), digit1));
+
+    recognize(pair(
+        opt(one_of("+-")),
+        alt((
+            recognize(tuple((digit1, char('.'), digit0, opt(exponent())))),
+            recognize(tuple((char('.'), digit1, opt(exponent())))),
+            recognize(pair(digit1, exponent())),
+            terminated(alt((tag("inf"), tag("nan"))), not(identifier_char)),
+        )),
+    ))(input)
+}
+
+// Message literal of custom options, braces excluded
+fn parse_aggregate(input: Span) -> IResult<Span, String> {
+    fn aggregate_content(input: Span) -> IResult<Span, Span> {
+        recognize(many0(alt((
+            recognize(string_literal),
+            recognize(delimited(char('{'), aggregate_content, char('}'))),
+            is_not("{}\"'"),
+        ))))(input)
+    }
+
+    map(
+        delimited(char('{'), aggregate_content, cut(char('}'))),
+        |content| content.trim().to_string(),
+    )(input)
+}
+
+// Examples:
+// - "text"
+// - 'text'
+// - "text" "continued"
+//
+// The escape sequences are kept
+fn string_literal(input: Span) -> IResult<Span, String> {
+    fn quoted(input: Span) -> IResult<Span, Span> {
+        let (input, quote) = one_of("\"'")(input)?;
+        terminated(
+            recognize(many0(alt((
+                recognize(pair(char('\\'), anychar)),
+                take_while1(move |c| c != quote && c != '\\' && c != '\n'),
+            )))),
+            cut(char(quote)),
+        )(input)
+    }
+
+    let (input, first) = quoted(input)?;
+    let (input, others) = many0(preceded(sp, quoted))(input)?;
+
+    Ok((
+        input,
+        others
+            .iter()
+            .fold(first.to_string(), |s, o| s + o.fragment()),
+    ))
+}
+
+// Examples:
+// - message MessageName { <field> <message> <enum> <oneof> ... }
+fn parse_message(input: Span) -> IResult<Span, Item> {
+    let (input, docu) = extract_docu(input)?;
+    let start = input;
+
+    let (input, _) = keyword("message")(input)?;
+    let (input, (name, elements)) = cut(pair(ws(identifier), parse_message_body))(input)?;
+
+    Ok((
+        input,
+        create_message(name.to_string(), docu, elements, location(start, input)),
+    ))
+}
+
+fn parse_message_body(input: Span) -> IResult<Span, Vec<MessageElement>> {
+    delimited(ws(char('{')), many0(parse_message_element), ws(char('}')))(input)
+}
+
+fn create_message(
+    name: String,
+    docu: String,
+    elements: Vec<MessageElement>,
+    location: NodeRange,
+) -> Item {
+    let mut fields = Vec::new();
+    let mut nested_items = Vec::new();
+    let mut options = Vec::new();
+
+    elements.into_iter().for_each(|e| match e {
+        MessageElement::Fields(mut f) => fields.append(&mut f),
+        MessageElement::Group(field, item) => {
+            fields.push(*field);
+            nested_items.push(item);
+        }
+        MessageElement::Item(item) => nested_items.push(item),
+        MessageElement::Option(option) => options.push(option),
+        MessageElement::Ignored => (),
+    });
+
+    Item::Message {
+        name,
+        docu,
+        fields,
+        nested_items,
+        options,
+        location,
+    }
+}
+
+fn parse_message_element(input: Span) -> IResult<Span, MessageElement> {
+    alt((
+        map(context("field declaration", parse_map_field), |f| {
+            MessageElement::Fields(vec![f])
+        }),
+        map(
+            context("oneof declaration", parse_oneof),
+            MessageElement::Fields,
+        ),
+        map(context("group declaration", parse_group), |(f, i)| {
+            MessageElement::Group(Box::new(f), i)
+        }),
+        map(
+            context("option", parse_option_statement),
+            MessageElement::Option,
+        ),
+        map(
+            context("message declaration", parse_message),
+            MessageElement::Item,
+        ),
+        map(
+            context("enum declaration", parse_enum),
+            MessageElement::Item,
+        ),
+        map(context("extend declaration", parse_extend), |_| {
+            MessageElement::Ignored
+        }),
+        map(context("reserved declaration", parse_reserved), |_| {
+            MessageElement::Ignored
+        }),
+        map(parse_empty_statement, |_| MessageElement::Ignored),
+        map(context("field declaration", parse_field), |f| {
+            MessageElement::Fields(vec![f])
+        }),
+        parse_invalid_element,
+    ))(input)
+}
+
+// Examples:
+// - int32 id = 1;
+// - repeated Foo foos = 2 [deprecated = true];
+// - optional .a.b.Bar bar = 3; // trailing docu
+fn parse_field(input: Span) -> IResult<Span, Field> {
+    let (input, docu) = extract_docu(input)?;
+    let start = input;
+
+    let (input, label) = parse_label(input)?;
+    let (input, (field_type, name)) = pair(ws(type_name), ws(identifier))(input)?;
+    let (input, (_, number, options, _)) = cut(tuple((
+        ws(char('=')),
+        ws(parse_int),
+        parse_field_options,
+        ws(char(';')),
+    )))(input)?;
+    let end = input;
+    let (input, trailing_docu) = trailing_comment(input)?;
+
+    Ok((
+        input,
+        Field {
+            name: name.to_string(),
+            docu: or_trailing(docu, trailing_docu),
+            label,
+            field_type: FieldType::Named(field_type.to_string()),
+            number: number.to_string(),
+            oneof: None,
+            options,
+            location: location(start, end),
+        },
+    ))
+}
+
+fn parse_label(input: Span) -> IResult<Span, Label> {
+    map(
+        opt(alt((
+            value(Label::Optional, keyword("optional")),
+            value(Label::Required, keyword("required")),
+            value(Label::Repeated, keyword("repeated")),
+        ))),
+        |label| label.unwrap_or(Label::Unspecified),
+    )(input)
+}
+
+// Example:
+// - map<string, Foo> foos = 1;
+fn parse_map_field(input: Span) -> IResult<Span, Field> {
+    let (input, docu) = extract_docu(input)?;
+    let start = input;
+
+    let (input, _) = pair(keyword("map"), ws(char('<')))(input)?;
+    let (input, (key_type, _, value_type, _, name, _, number, options, _)) = cut(tuple((
+        ws(type_name),
+        ws(char(',')),
+        ws(type_name),
+        ws(char('>')),
+        ws(identifier),
+        ws(char('=')),
+        ws(parse_int),
+        parse_field_options,
+        ws(char(';')),
+    )))(input)?;
+    let end = input;
+    let (input, trailing_docu) = trailing_comment(input)?;
+
+    Ok((
+        input,
+        Field {
+            name: name.to_string(),
+            docu: or_trailing(docu, trailing_docu),
+            label: Label::Unspecified,
+            field_type: FieldType::Map(key_type.to_string(), value_type.to_string()),
+            number: number.to_string(),
+            oneof: None,
+            options,
+            location: location(start, end),
+        },
+    ))
+}
+
+// Example:
+// - oneof value { string name = 1; int32 id = 2; }
+fn parse_oneof(input: Span) -> IResult<Span, Vec<Field>> {
+    let (input, _) = ws(keyword("oneof"))(input)?;
+    let (input, (name, fields)) = cut(pair(
+        ws(identifier),
+        delimited(
+            ws(char('{')),
+            many0(alt((
+                map(context("option", parse_option_statement), |_| None),
+                map(parse_empty_statement, |_| None),
+                map(context("field declaration", parse_field), Some),
+                parse_invalid_element,
+            ))),
+            ws(char('}')),
+        ),
+    ))(input)?;
+
+    Ok((
+        input,
+        fields
+            .into_iter()
+            .flatten()
+            .map(|f| Field {
+                oneof: Some(name.to_string()),
+                ..f
+            })
+            .collect(),
+    ))
+}
+
+// Proto2 field declaring its message type (named after the field)
+// Example:
+// - repeated group Result = 1 { required string url = 2; }
+fn parse_group(input: Span) -> IResult<Span, (Field, Item)> {
+    let (input, docu) = extract_docu(input)?;
+    let start = input;
+
+    let (input, label) = parse_label(input)?;
+    let (input, _) = ws(keyword("group"))(input)?;
+    let (input, (name, _, number, options)) = cut(tuple((
+        ws(identifier),
+        ws(char('=')),
+        ws(parse_int),
+        parse_field_options,
+    )))(input)?;
+    let end = input;
+    let (input, elements) = cut(parse_message_body)(input)?;
+
+    let field = Field {
+        name: name.to_lowercase(),
+        docu: String::new(),
+        label,
+        field_type: FieldType::Named(name.to_string()),
+        number: number.to_string(),
+        oneof: None,
+        options,
+        location: location(start, end),
+    };
+    let message = create_message(name.to_string(), docu, elements, location(start, input));
+
+    Ok((input, (field, message)))
+}
+
+// Examples:
+// - reserved 2, 15, 9 to 11;
+// - reserved "foo", "bar";
+// - extensions 100 to max;
+fn parse_reserved(input: Span) -> IResult<Span, ()> {
+    let (input, _) = ws(alt((keyword("reserved"), keyword("extensions"))))(input)?;
+    let (input, _) = cut(pair(is_not(";"), char(';')))(input)?;
+
+    Ok((input, ()))
+}
+
+// Example:
+// - extend Foo { optional int32 bar = 126; }
+fn parse_extend(input: Span) -> IResult<Span, ()> {
+    let (input, _) = ws(keyword("extend"))(input)?;
+    let (input, _) = cut(pair(ws(type_name), parse_message_body))(input)?;
+
+    Ok((input, ()))
+}
+
+// Examples:
+// - enum EnumName { FOO = 0; BAR = 1 [deprecated = true]; }
+fn parse_enum(input: Span) -> IResult<Span, Item> {
+    let (input, docu) = extract_docu(input)?;
+    let start = input;
+
+    let (input, _) = keyword("enum")(input)?;
+    let (input, (name, elements)) = cut(pair(
+        ws(identifier),
+        delimited(
+            ws(char('{')),
+            many0(alt((
+                map(
+                    context("option", parse_option_statement),
+                    EnumElement::Option,
+                ),
+                map(context("reserved declaration", parse_reserved), |_| {
+                    EnumElement::Ignored
+                }),
+                map(parse_empty_statement, |_| EnumElement::Ignored),
+                map(context("enum value", parse_enum_value), EnumElement::Value),
+                parse_invalid_element,
+            ))),
+            ws(char('}')),
+        ),
+    ))(input)?;
+
+    let mut values = Vec::new();
+    let mut options = Vec::new();
+    elements.into_iter().for_each(|e| match e {
+        EnumElement::Value(v) => values.push(v),
+        EnumElement::Option(o) => options.push(o),
+        EnumElement::Ignored => (),
+    });
+
+    Ok((
+        input,
+        Item::Enum {
+            name: name.to_string(),
+            docu,
+            values,
+            options,
+            location: location(start, input),
+        },
+    ))
+}
+
+// Examples:
+// - FOO = 1;
+// - BAR = -1 [deprecated = true];
+fn parse_enum_value(input: Span) -> IResult<Span, EnumValue> {
+    let (input, docu) = extract_docu(input)?;
+    let start = input;
+
+    let (input, name) = identifier(input)?;
+    let (input, (_, number, options, _)) = cut(tuple((
+        ws(char('=')),
+        ws(parse_int),
+        parse_field_options,
+        ws(char(';')),
+    )))(input)?;
+    let end = input;
+    let (input, trailing_docu) = trailing_comment(input)?;
+
+    Ok((
+        input,
+        EnumValue {
+            name: name.to_string(),
+            docu: or_trailing(docu, trailing_docu),
+            number: number.to_string(),
+            options,
+            location: location(start, end),
+        },
+    ))
+}
+
+// Examples:
+// - service ServiceName { <rpc> <rpc> }
+fn parse_service(input: Span) -> IResult<Span, Item> {
+    let (input, docu) = extract_docu(input)?;
+    let start = input;
+
+    let (input, _) = keyword("service")(input)?;
+    let (input, (name, elements)) = cut(pair(
+        ws(identifier),
+        delimited(
+            ws(char('{')),
+            many0(alt((
+                map(
+                    context("option", parse_option_statement),
+                    ServiceElement::Option,
+                ),
+                map(parse_empty_statement, |_| ServiceElement::Ignored),
+                map(context("rpc declaration", parse_rpc), ServiceElement::Rpc),
+                parse_invalid_element,
+            ))),
+            ws(char('}')),
+        ),
+    ))(input)?;
+
+    let mut rpcs = Vec::new();
+    let mut options = Vec::new();
+    elements.into_iter().for_each(|e| match e {
+        ServiceElement::Rpc(r) => rpcs.push(r),
+        ServiceElement::Option(o) => options.push(o),
+        ServiceElement::Ignored => (),
+    });
+
+    Ok((
+        input,
+        Item::Service {
+            name: name.to_string(),
+            docu,
+            rpcs,
+            options,
+            location: location(start, input),
+        },
+    ))
+}
+
+// Examples:
+// - rpc Get(GetRequest) returns (GetResponse);
+// - rpc Watch(WatchRequest) returns (stream Event) { option deprecated = true; }
+fn parse_rpc(input: Span) -> IResult<Span, Rpc> {
+    let (input, docu) = extract_docu(input)?;
+    let start = input;
+
+    let (input, _) = keyword("rpc")(input)?;
+    let (input, (name, request, _, response)) = cut(tuple((
+        ws(identifier),
+        parse_rpc_type,
+        ws(keyword("returns")),
+        parse_rpc_type,
+    )))(input)?;
+    let (input, options) = cut(alt((
+        map(ws(char(';')), |_| Vec::new()),
+        delimited(
+            ws(char('{')),
+            map(
+                many0(alt((
+                    map(context("option", parse_option_statement), Some),
+                    map(parse_empty_statement, |_| None),
+                ))),
+                |options| options.into_iter().flatten().collect(),
+            ),
+            pair(ws(char('}')), opt(ws(char(';')))),
+        ),
+    )))(input)?;
+    let end = input;
+    let (input, trailing_docu) = trailing_comment(input)?;
+
+    Ok((
+        input,
+        Rpc {
+            name: name.to_string(),
+            docu: or_trailing(docu, trailing_docu),
+            request: request.1.to_string(),
+            request_stream: request.0,
+            response: response.1.to_string(),
+            response_stream: response.0,
+            options,
+            location: location(start, end),
+        },
+    ))
+}
+
+// Examples:
+// - (Foo)
+// - (stream Foo)
+fn parse_rpc_type(input: Span) -> IResult<Span, (bool, Span)> {
+    delimited(
+        ws(char('(')),
+        pair(
+            map(opt(ws(keyword("stream"))), |s| s.is_some()),
+            ws(type_name),
+        ),
+        ws(char(')')),
+    )(input)
+}
+
+fn parse_empty_statement(input: Span) -> IResult<Span, ()> {
+    value((), ws(char(';')))(input)
+}
+
+// Anything else than the end of the parent ('}') => failure
+fn parse_invalid_element<O>(input: Span) -> IResult<Span, O> {
+    let (input, _) = sp(input)?;
+
+    // Invalid input (up to the next ';' or '}') => failure
+    let (_, input) = is_not("};")(input)?;
+    Err(nom::Err::Failure(SyntaxError::new(
+        input,
+        SyntaxErrorKind::UnexpectedInput,
+    )))
+}
+
+// Examples:
+// // ...
+// /* ... */
+fn parse_comment(input: Span) -> IResult<Span, Span> {
+    alt((
+        preceded(tag("//"), not_line_ending),
+        delimited(tag("/*"), take_until("*/"), tag("*/")),
+    ))(input)
+}
+
+// Comments right before a declaration (i.e. not detached by a blank line)
+fn extract_docu(mut input: Span) -> IResult<Span, String> {
+    let mut comments = Vec::new();
+
+    loop {
+        let (after_spaces, spaces) = multispace0(input)?;
+        if spaces.fragment().matches('\n').count() > 1 {
+            comments.clear();
+        }
+
+        match parse_comment(after_spaces) {
+            Ok((rest, comment)) => {
+                comments.push(comment);
+                input = rest;
+            }
+            Err(_) => return Ok((after_spaces, docu_text(&comments))),
+        }
+    }
+}
+
+// Comment at the end of the line of a declaration
+// Example:
+// - int32 id = 1; // the id
+fn trailing_comment(input: Span) -> IResult<Span, Option<String>> {
+    opt(map(
+        preceded(space0, preceded(tag("//"), not_line_ending)),
+        |comment| docu_text(&[comment]),
+    ))(input)
+}
+
+fn or_trailing(docu: String, trailing_docu: Option<String>) -> String {
+    match trailing_docu {
+        Some(trailing_docu) if docu.is_empty() => trailing_docu,
+        _ => docu,
+    }
+}
+
+// The lines are kept (e.g.: for paragraphs), without their leading '*' or '/'
+// (of /** ... */ or /// ...)
+fn docu_text(comments: &[Span]) -> String {
+    let lines: Vec<&str> = comments
+        .iter()
+        .flat_map(|c| c.fragment().lines())
+        .map(|line| {
+            let line = line.trim_start();
+            let line = line.strip_prefix(&['*', '/'][..]).unwrap_or(line);
+            line.strip_prefix(' ').unwrap_or(line).trim_end()
+        })
+        .collect();
+
+    lines.join("\n").trim().to_string()
+}
+
+// Location of the input consumed from start to end, trailing whitespace excluded
+fn location(start: Span, end: Span) -> NodeRange {
+    let consumed = &start.fragment()[..end.location_offset() - start.location_offset()];
+    let start_position =
+        SourcePosition::new(start.location_line() as usize, start.get_utf8_column());
+
+    NodeRange(SourceRange::new(
+        start_position,
+        start_position.advanced_by(consumed.trim_end()),
+    ))
+}
+
+// Examples:
+// - Foo
+// - a.b.Foo
+fn full_identifier(input: Span) -> IResult<Span, Span> {
+    recognize(separated_list1(char('.'), identifier))(input)
+}
+
+// Examples:
+// - Foo
+// - Foo.Bar
+// - .a.b.Foo (fully qualified)
+fn type_name(input: Span) -> IResult<Span, Span> {
+    recognize(pair(opt(char('.')), full_identifier))(input)
+}
+
+// Valid identifier
+fn identifier(input: Span) -> IResult<Span, Span> {
+    recognize(pair(alt((alpha1, tag("_"))), many0(identifier_char)))(input).map_err(|e| {
+        e.map(|_: SyntaxError<Span>| SyntaxError::new(input, SyntaxErrorKind::ExpectedIdentifier))
+    })
+}
+
+fn identifier_char(input: Span) -> IResult<Span, Span> {
+    alt((alphanumeric1, tag("_")))(input)
+}
+
+// Keyword not followed by other identifier chars (e.g.: not the start of
+// messageName for message)
+fn keyword<'a>(word: &'static str) -> impl FnMut(Span<'a>) -> IResult<Span<'a>, Span<'a>> {
+    terminated(tag(word), not(identifier_char))
+}
+
+// Spaces and comments
+fn sp(input: Span) -> IResult<Span, ()> {
+    value(
+        (),
+        many0(alt((value((), multispace1), value((), parse_comment)))),
+    )(input)
+}
+
+// Ignore leading spaces and comments (the trailing ones document the next
+// declaration, see extract_docu)
+fn ws<'a, O, F>(f: F) -> impl FnMut(Span<'a>) -> IResult<Span<'a>, O>
+where
+    F: FnMut(Span<'a>) -> IResult<Span<'a>, O>,
+{
+    preceded(sp, f)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(name: &str, label: Label, field_type: FieldType, number: &str) -> Field {
+        Field {
+            name: name.to_string(),
+            docu: String::new(),
+            label,
+            field_type,
+            number: number.to_string(),
+            oneof: None,
+            options: Vec::new(),
+            location: NodeRange::default(),
+        }
+    }
+
+    #[test]
+    fn test_file() -> Result<(), Box<dyn std::error::Error>> {
+        let file = parse(
+            r#"
+            // License
+            syntax = "proto3";
+
+            package a.b;
+
+            import "c/d.proto";
+            import public "e.proto";
+            option java_package = "com.a.b";
+
+            message Foo {}
+            ;
+            enum Bar { BAR_UNSPECIFIED = 0; }
+            extend Foo { int32 ext = 100; }
+            "#,
+        )?;
+
+        assert_eq!(file.syntax, Some("proto3".to_string()));
+        assert_eq!(file.package, "a.b");
+        assert_eq!(file.imports, vec!["c/d.proto", "e.proto"]);
+        assert_eq!(
+            file.options,
+            vec![ProtoOption::new(
+                "java_package",
+                Constant::String("com.a.b".to_string())
+            )]
+        );
+        assert_eq!(file.items.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_message() -> Result<(), Box<dyn std::error::Error>> {
+        let input = Span::new(
+            r#"// Docu of Foo
+            message Foo {
+                // id docu
+                int32 id = 1;
+                repeated .a.Bar bars = 2 [deprecated = true];
+                map<string, Foo.Inner> inners = 3; // inners docu
+
+                oneof value {
+                    string name = 4;
+                    int64 number = 5;
+                }
+                reserved 6, 9 to 11;
+                reserved "old";
+
+                message Inner {}
+            }rest"#,
+        );
+        let (input, message) = parse_message(input)?;
+
+        assert_eq!(
+            message,
+            Item::Message {
+                name: "Foo".to_string(),
+                docu: "Docu of Foo".to_string(),
+                fields: vec![
+                    Field {
+                        docu: "id docu".to_string(),
+                        ..field(
+                            "id",
+                            Label::Unspecified,
+                            FieldType::Named("int32".to_string()),
+                            "1"
+                        )
+                    },
+                    Field {
+                        options: vec![ProtoOption::new("deprecated", Constant::Bool(true))],
+                        ..field(
+                            "bars",
+                            Label::Repeated,
+                            FieldType::Named(".a.Bar".to_string()),
+                            "2"
+                        )
+                    },
+                    Field {
+                        docu: "inners docu".to_string(),
+                        ..field(
+                            "inners",
+                            Label::Unspecified,
+                            FieldType::Map("string".to_string(), "Foo.Inner".to_string()),
+                            "3"
+                        )
+                    },
+                    Field {
+                        oneof: Some("value".to_string()),
+                        ..field(
+                            "name",
+                            Label::Unspecified,
+                            FieldType::Named("string".to_string()),
+                            "4"
+                        )
+                    },
+                    Field {
+                        oneof: Some("value".to_string()),
+                        ..field(
+                            "number",
+                            Label::Unspecified,
+                            FieldType::Named("int64".to_string()),
+                            "5"
+                        )
+                    },
+                ],
+                nested_items: vec![Item::Message {
+                    name: "Inner".to_string(),
+                    docu: String::new(),
+                    fields: Vec::new(),
+                    nested_items: Vec::new(),
+                    options: Vec::new(),
+                    location: NodeRange::default(),
+                }],
+                options: Vec::new(),
+                location: NodeRange::default(),
+            }
+        );
+        assert_eq!(*input, "rest");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_group() -> Result<(), Box<dyn std::error::Error>> {
+        let input = Span::new("repeated group Result = 1 { required string url = 2; }rest");
+        let (input, (group_field, message)) = parse_group(input)?;
+
+        assert_eq!(
+            group_field,
+            field(
+                "result",
+                Label::Repeated,
+                FieldType::Named("Result".to_string()),
+                "1"
+            )
+        );
+        assert_eq!(
+            message,
+            Item::Message {
+                name: "Result".to_string(),
+                docu: String::new(),
+                fields: vec![field(
+                    "url",
+                    Label::Required,
+                    FieldType::Named("string".to_string()),
+                    "2"
+                )],
+                nested_items: Vec::new(),
+                options: Vec::new(),
+                location: NodeRange::default(),
+            }
+        );
+        assert_eq!(*input, "rest");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_enum() -> Result<(), Box<dyn std::error::Error>> {
+        let input = Span::new(
+            r#"/**
+             * Docu of Kind
+             */
+            enum Kind {
+                option allow_alias = true;
+                KIND_UNSPECIFIED = 0;
+                KIND_A = 0x1; // A
+                KIND_B = -1 [deprecated = true];
+                reserved 2;
+            }rest"#,
+        );
+        let (input, enumeration) = parse_enum(input)?;
+
+        let value = |name: &str, number: &str| EnumValue {
+            name: name.to_string(),
+            docu: String::new(),
+            number: number.to_string(),
+            options: Vec::new(),
+            location: NodeRange::default(),
+        };
+        assert_eq!(
+            enumeration,
+            Item::Enum {
+                name: "Kind".to_string(),
+                docu: "Docu of Kind".to_string(),
+                values: vec![
+                    value("KIND_UNSPECIFIED", "0"),
+                    EnumValue {
+                        docu: "A".to_string(),
+                        ..value("KIND_A", "0x1")
+                    },
+                    EnumValue {
+                        options: vec![ProtoOption::new("deprecated", Constant::Bool(true))],
+                        ..value("KIND_B", "-1")
+                    },
+                ],
+                options: vec![ProtoOption::new("allow_alias", Constant::Bool(true))],
+                location: NodeRange::default(),
+            }
+        );
+        assert_eq!(*input, "rest");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_service() -> Result<(), Box<dyn std::error::Error>> {
+        let input = Span::new(
+            r#"service FooService {
+                // Gets a foo
+                rpc Get(GetRequest) returns (a.Foo);
+                rpc Watch(stream Foo) returns (stream Event) {
+                    option deprecated = true;
+                }
+            }rest"#,
+        );
+        let (input, service) = parse_service(input)?;
+
+        assert_eq!(
+            service,
+            Item::Service {
+                name: "FooService".to_string(),
+                docu: String::new(),
+                rpcs: vec![
+                    Rpc {
+                        name: "Get".to_string(),
+                        docu: "Gets a foo".to_string(),
+                        request: "GetRequest".to_string(),
+                        request_stream: false,
+                        response: "a.Foo".to_string(),
+                        response_stream: false,
+                        options: Vec::new(),
+                        location: NodeRange::default(),
+                    },
+                    Rpc {
+                        name: "Watch".to_string(),
+                        docu: String::new(),
+                        request: "Foo".to_string(),
+                        request_stream: true,
+                        response: "Event".to_string(),
+                        response_stream: true,
+                        options: vec![ProtoOption::new("deprecated", Constant::Bool(true))],
+                        location: NodeRange::default(),
+                    },
+                ],
+                options: Vec::new(),
+                location: NodeRange::default(),
+            }
+        );
+        assert_eq!(*input, "rest");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_options() -> Result<(), Box<dyn std::error::Error>> {
+        let (_, options) = parse_field_options(Span::new(
+            r#"[default = -1.5e3, (my.opt).x = { a: 1 b: "}" }, json_name = "a" 'b', c = FOO]"#,
+        ))?;
+
+        assert_eq!(
+            options,
+            vec![
+                ProtoOption::new("default", Constant::Float("-1.5e3".to_string())),
+                ProtoOption::new(
+                    "(my.opt).x",
+                    Constant::Aggregate(r#"a: 1 b: "}""#.to_string())
+                ),
+                ProtoOption::new("json_name", Constant::String("ab".to_string())),
+                ProtoOption::new("c", Constant::Identifier("FOO".to_string())),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_diagnostics() -> Result<(), Box<dyn std::error::Error>> {
+        let diagnostic = parse("package x;\nmessage A {\n  int32 a = 1\n}\n").unwrap_err();
+        assert_eq!(diagnostic.code, crate::parser::error::EXPECTED_TOKEN);
+        assert_eq!(diagnostic.message, "expected ';' after field declaration");
+        assert_eq!(diagnostic.range.map(|r| r.start.line), Some(3));
+
+        let diagnostic = parse("package x;\nfoo bar;\n").unwrap_err();
+        assert_eq!(diagnostic.code, crate::parser::error::UNEXPECTED_INPUT);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_locations() -> Result<(), Box<dyn std::error::Error>> {
+        let file = parse("package x;\n\n// Foo\nmessage Foo {\n  int32 id = 1; // id\n}\n")?;
+
+        match file.items.first() {
+            Some(Item::Message {
+                fields, location, ..
+            }) => {
+                assert_eq!(location.start, SourcePosition::new(4, 1));
+                assert_eq!(location.end, SourcePosition::new(6, 2));
+                assert_eq!(fields[0].location.start, SourcePosition::new(5, 3));
+                assert_eq!(fields[0].location.end, SourcePosition::new(5, 16));
+            }
+            _ => panic!("expected a message"),
+        }
+
+        Ok(())
+    }
+}
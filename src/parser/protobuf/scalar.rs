@@ -0,0 +1,62 @@
+use strum_macros::EnumIter;
+
+use crate::model::TypeCategory;
+
+// Scalar value types, and maps (map<K, V>)
+#[derive(Debug, EnumIter)]
+pub enum Scalar {
+    Double,
+    Float,
+    Int32,
+    Int64,
+    Uint32,
+    Uint64,
+    Sint32,
+    Sint64,
+    Fixed32,
+    Fixed64,
+    Sfixed32,
+    Sfixed64,
+    Bool,
+    String,
+    Bytes,
+    Map,
+}
+
+impl Scalar {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Scalar::Double => "double",
+            Scalar::Float => "float",
+            Scalar::Int32 => "int32",
+            Scalar::Int64 => "int64",
+            Scalar::Uint32 => "uint32",
+            Scalar::Uint64 => "uint64",
+            Scalar::Sint32 => "sint32",
+            Scalar::Sint64 => "sint64",
+            Scalar::Fixed32 => "fixed32",
+            Scalar::Fixed64 => "fixed64",
+            Scalar::Sfixed32 => "sfixed32",
+            Scalar::Sfixed64 => "sfixed64",
+            Scalar::Bool => "bool",
+            Scalar::String => "string",
+            Scalar::Bytes => "bytes",
+            Scalar::Map => "map",
+        }
+    }
+
+    pub fn get_category(&self) -> TypeCategory {
+        match self {
+            Scalar::String | Scalar::Bytes => TypeCategory::String,
+            Scalar::Map => TypeCategory::Container,
+            _ => TypeCategory::Primitive,
+        }
+    }
+
+    pub fn get_generic_arity(&self) -> usize {
+        match self {
+            Scalar::Map => 2,
+            _ => 0,
+        }
+    }
+}
@@ -6,8 +6,9 @@ use std::path::PathBuf;
 
 use crate::{
     create_ui_controller,
-    ui_controller::{UiController, UiListener},
-    ui_state::{UiItem, UiSelection},
+    model::DataFlow,
+    ui_controller::{UiController, UiFilter, UiListener},
+    ui_state::{diagnostics_json, UiItem, UiSelection},
     TEST_AIDL,
 };
 
@@ -23,6 +24,7 @@ qrc!(vuk_resource,
         "src/ui/qml/Navigation.qml",
         "src/ui/qml/ItemSymbol.qml",
         "src/ui/qml/OpenFileDialog.qml",
+        "src/ui/qml/Problems.qml",
         "src/ui/qml/Selection.qml",
         "src/ui/qml/main.qml",
         "src/ui/qml/style/qmldir",
@@ -64,6 +66,7 @@ struct QuickVuk {
             match create_ui_controller(TEST_AIDL) {
                 Ok(mut controller) => {
                     controller.add_listener(Box::new(listener));
+                    controller.filter = self.filter;
 
                     self.selection = serde_json::to_string(&controller.selection).unwrap();
                     self.controller = Some(controller);
@@ -71,7 +74,8 @@ struct QuickVuk {
                     self.selection_changed();
                 }
                 Err(e) => {
-                    self.error(e.to_string());
+                    eprintln!("{}", e);
+                    self.error(diagnostics_json(e.as_ref()));
                 }
             }
         }
@@ -87,6 +91,7 @@ struct QuickVuk {
             match UiController::open(&path) {
                 Ok(mut controller) => {
                     controller.add_listener(Box::new(listener));
+                    controller.filter = self.filter;
 
                     self.selection = serde_json::to_string(&controller.selection).unwrap();
                     self.controller = Some(controller);
@@ -94,7 +99,8 @@ struct QuickVuk {
                     self.selection_changed();
                 }
                 Err(e) => {
-                    self.error(e.to_string());
+                    eprintln!("{}", e);
+                    self.error(diagnostics_json(e.as_ref()));
                 }
             }
         }
@@ -116,7 +122,8 @@ struct QuickVuk {
                 match controller.get_dependencies(&key.into()) {
                     Ok(edges) => serde_json::to_string(&edges).unwrap(),
                     Err(e) => {
-                        self.error(e.to_string());
+                        eprintln!("{}", e);
+                        self.error(diagnostics_json(e.as_ref()));
                         "null".to_string()
                     }
                 }
@@ -132,7 +139,8 @@ struct QuickVuk {
                 match controller.get_references(&key.into()) {
                     Ok(edges) => serde_json::to_string(&edges).unwrap(),
                     Err(e) => {
-                        self.error(e.to_string());
+                        eprintln!("{}", e);
+                        self.error(diagnostics_json(e.as_ref()));
                         "null".to_string()
                     }
                 }
@@ -142,8 +150,56 @@ struct QuickVuk {
         }
     ),
 
+    // Hide the hidden and/or deprecated elements from the graphs, and only
+    // follow the method args of the given data flow ("All", "In" or "Out")
+    set_filter: qt_method!(
+        fn set_filter(&mut self, hide_hidden: bool, hide_deprecated: bool, data_flow: QString) {
+            self.filter = UiFilter {
+                hide_hidden,
+                hide_deprecated,
+                data_flow: match data_flow.to_string().as_str() {
+                    "In" => DataFlow::In,
+                    "Out" => DataFlow::Out,
+                    _ => DataFlow::All,
+                },
+            };
+            if let Some(controller) = &mut self.controller {
+                controller.filter = self.filter;
+            }
+        }
+    ),
+
+    // Open a file at the given line with the editor of the environment
+    // ($VISUAL or $EDITOR, e.g.: "vim" => vim +42 a/IFoo.aidl), false if
+    // there is none
+    open_in_editor: qt_method!(
+        fn open_in_editor(&self, path: QString, line: usize) -> bool {
+            let editor = std::env::var("VISUAL").or_else(|_| std::env::var("EDITOR"));
+            let editor = match editor {
+                Ok(editor) if !editor.trim().is_empty() => editor,
+                _ => return false,
+            };
+
+            let mut words = editor.split_whitespace();
+            let mut command = std::process::Command::new(words.next().unwrap());
+            command
+                .args(words)
+                .arg(format!("+{}", line))
+                .arg(path.to_string());
+
+            match command.spawn() {
+                Ok(_) => true,
+                Err(e) => {
+                    eprintln!("Cannot run {}: {}", editor, e);
+                    false
+                }
+            }
+        }
+    ),
+
     // Internal
     controller: Option<UiController>,
+    filter: UiFilter,
 }
 
 struct Listener;
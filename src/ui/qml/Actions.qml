@@ -40,6 +40,48 @@ Item {
         onTriggered: Style.increaseFontSize()
     }
 
+    readonly property Action hideHidden: Action {
+        text: "Hide hidden elements"
+        checkable: true
+        onToggled: applyFilter()
+    }
+
+    readonly property Action hideDeprecated: Action {
+        text: "Hide deprecated elements"
+        checkable: true
+        onToggled: applyFilter()
+    }
+
+    // Only follow the method args read (or written) by the callee
+    readonly property Action followInArgs: Action {
+        text: "Follow in arguments only"
+        checkable: true
+        onToggled: {
+            if (checked) {
+                followOutArgs.checked = false;
+            }
+            applyFilter();
+        }
+    }
+
+    readonly property Action followOutArgs: Action {
+        text: "Follow out arguments only"
+        checkable: true
+        onToggled: {
+            if (checked) {
+                followInArgs.checked = false;
+            }
+            applyFilter();
+        }
+    }
+
+    // Filter of the dependencies and references of the diagram
+    function applyFilter() {
+        var dataFlow = followInArgs.checked ? "In" : followOutArgs.checked ? "Out" : "All";
+        vuk.set_filter(hideHidden.checked, hideDeprecated.checked, dataFlow);
+        diagram.refresh();
+    }
+
     readonly property Action goBackward: Action {
         text: "Back"
         property string iconCode: "\uE72B"
@@ -6,6 +6,9 @@ Item {
     property Item itemFrom
     property Item itemTo
     property var indicesFrom: []
+    property string kind: "Reference"
+
+    readonly property color lineColor: kind === "Inheritance" ? Style.inheritanceConnectorColor : Style.connectorColor
 
     property Item leftItem: itemTo.x > itemFrom.x ? itemFrom : itemTo
     property Item rightItem: leftItem === itemFrom ? itemTo : itemFrom
@@ -28,7 +31,7 @@ Item {
         y: parent.leftItem === parent.bottomItem ? parent.height : 0
         height: Style.connectorWidth
         width: parent.width / 2
-        color: Style.connectorColor
+        color: parent.lineColor
     }
 
     //    |
@@ -37,7 +40,7 @@ Item {
         y: 0
         height: parent.height
         width: Style.connectorWidth
-        color: Style.connectorColor
+        color: parent.lineColor
     }
 
     //     ---
@@ -46,7 +49,7 @@ Item {
         y: parent.rightItem === parent.bottomItem ? parent.height : 0
         height: Style.connectorWidth
         width: parent.width / 2
-        color: Style.connectorColor
+        color: parent.lineColor
     }
     
     // Arrow
@@ -55,7 +58,7 @@ Item {
         y: itemTo === parent.topItem ? 0 : parent.height
         width: Style.connectorArrowLength
         height: Style.connectorWidth
-        color: Style.connectorColor
+        color: parent.lineColor
         rotation: itemTo === parent.rightItem ? -145 : 35
         transformOrigin: Item.Left
     }
@@ -65,7 +68,7 @@ Item {
         y: itemTo === parent.topItem ? 0 : parent.height
         width: Style.connectorArrowLength
         height: Style.connectorWidth
-        color: Style.connectorColor
+        color: parent.lineColor
         rotation: itemTo === parent.rightItem ? -215 : -35
         transformOrigin: Item.Left
     }
@@ -103,7 +103,7 @@ Rectangle {
         
         diagram.leftItems = edges.map(edge => {
             const subItem = diagram.createItem(edge.to, { position: 0 });
-            subItem.createConnector(subItem, mainItem);
+            subItem.createConnector(subItem, mainItem, edge.kind);
             subItem.memberFilter = edge.from_indices;
             return subItem;
         });
@@ -117,7 +117,7 @@ Rectangle {
         
         diagram.rightItems = edges.map(edge => {
             const subItem = diagram.createItem(edge.to, { position: 2 });
-            const connector = subItem.createConnector(mainItem, subItem);
+            const connector = subItem.createConnector(mainItem, subItem, edge.kind);
             /*subItem*/connector.opacity = Qt.binding(() => {
                 if (mainItem.highlightedMemberIndex < 0) return 1.0;
                 if (edge.from_indices.includes(mainItem.highlightedMemberIndex)) return 1.0;
@@ -127,6 +127,16 @@ Rectangle {
         });
     }
 
+    // Expand the main item again (e.g.: once the filter changed)
+    function refresh() {
+        if (!mainItem) return;
+
+        expandLeft();
+        expandRight();
+        adjustItemStateForAvailableSpace();
+        layout();
+    }
+
     function createItem(node, params) {
         return itemComponent.createObject(diagram, Object.assign({}, params, { node }));
     }
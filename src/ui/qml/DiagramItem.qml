@@ -56,8 +56,9 @@ Rectangle {
     
     Component.onDestruction: clearConnectors()
     
-    function createConnector(itemFrom, itemTo) {
-        const connector = connectorComponent.createObject(diagram, { itemFrom, itemTo });
+    // kind: "Reference" or "Inheritance" (see UiEdgeKind)
+    function createConnector(itemFrom, itemTo, kind) {
+        const connector = connectorComponent.createObject(diagram, { itemFrom, itemTo, kind });
         connectors.push(connector);
         return connector;
     }
@@ -103,6 +104,18 @@ Rectangle {
                     Layout.fillWidth: true
                 }
 
+                Label {
+                    Layout.alignment: Qt.AlignVCenter
+                    visible: text.length > 0
+
+                    color: titleLabel.color
+                    text: diagramItem.node.item.annotations.join(" ")
+                    font.family: Style.mainFontFamily
+                    font.pointSize: Style.itemAnnotationFontSize
+                    font.italic: true
+                    elide: Text.ElideRight
+                }
+
                 ItemSymbol {
                     color: titleLabel.color
                     border.color: titleBackgroundRect.color
@@ -117,9 +130,11 @@ Rectangle {
                         diagramItem.containsMouse ? Qt.darker(diagramItem.foregroundColor) :
                         diagramItem.foregroundColor
                     text: diagramItem.node.item.name
+                    opacity: diagramItem.node.item.is_hidden ? Style.hiddenOpacity : 1
                     font.family: Style.mainFontFamily
                     font.pointSize: Style.itemTitleFontSize
                     font.bold: true
+                    font.strikeout: diagramItem.node.item.is_deprecated
                     verticalAlignment: Qt.AlignVCenter
                     elide: Text.ElideRight
                 }
@@ -187,15 +202,19 @@ Rectangle {
 
                     anchors { left: parent.left; right: parent.right }
                     height: shouldBeShown ? memberTextMetrics.boundingRect.height : 0
-                    opacity: !expanded ? 0 :
+                    opacity: (modelData.is_hidden ? Style.hiddenOpacity : 1) * (
+                        !expanded ? 0 :
                         !memberFilter ? 1 :
-                        passFilter ? 1 : 0.35
-                    text: modelData.text
+                        passFilter ? 1 : 0.35)
+                    text: modelData.annotations.concat([modelData.text]).join(" ")
                     color: memberMouseArea.containsMouse ? Qt.darker(diagramItem.foregroundColor) /*Style.diagramItemHighlightColor*/ : diagramItem.foregroundColor
                     //color: Style.diagramItemMemberColor
                     maximumLineCount: 1
                     wrapMode: Text.Wrap
-                    font: memberTextMetrics.font
+                    font.family: memberTextMetrics.font.family
+                    font.pointSize: memberTextMetrics.font.pointSize
+                    font.strikeout: modelData.is_deprecated
+                    font.italic: !!modelData.inherited_from
                     elide: Text.ElideRight
                     
                     Behavior on height {
@@ -7,20 +7,116 @@ import "./style"
 Label {
     anchors { left: parent.left; right: parent.right }
     wrapMode: Text.Wrap
+    textFormat: Text.RichText
+
+    // e.g.: declared at IFoo.aidl:42 (opens the file at this line, see
+    // onLinkActivated)
+    function declaredAt(location) {
+        if (!location || !location.url) {
+            return "";
+        }
+        const href = "location:" + encodeURIComponent(JSON.stringify(location));
+        return "<br/><br/>declared at <a href=\"" + href + "\">" + location.text + "</a>";
+    }
+
+    // e.g.: inherited from a.b.IBar (the super-interface of a method)
+    function inheritedFrom(member) {
+        if (!member.inherited_from) {
+            return "";
+        }
+        return "<p><i>Inherited from <a href=\"item:" + member.inherited_from + "\">" + member.inherited_from + "</a></i></p>";
+    }
+
+    // e.g.: interface IFoo (AIDL)
+    function itemTitle(item) {
+        return item.item_type + " <strong>" + item.name + "</strong> <i>(" + item.idl + ")</i>";
+    }
+
+    // e.g.: Stable interface, version 2, with getInterfaceVersion () -> int = 16777215
+    function stabilityHtml(item) {
+        if (!item.is_stable) {
+            return "";
+        }
+        const version = item.frozen_version !== null ? ", version " + item.frozen_version : "";
+        return "<p><i>Stable interface" + version + "</i><br/>" + item.meta_transactions.join("<br/>") + "</p>";
+    }
+
+    // e.g.: transaction code 4
+    function transactionCode(member) {
+        if (member.transaction_code === null || member.transaction_code === undefined) {
+            return "";
+        }
+        return "<p><i>Transaction code " + member.transaction_code + "</i></p>";
+    }
+
+    // e.g.: Parameters: name - the name
+    function tagsHtml(title, tags) {
+        if (!tags.length) {
+            return "";
+        }
+        return "<p><b>" + title + ":</b><br/>" + tags.map(function(tag) {
+            return "<i>" + tag.name + "</i> - " + tag.text;
+        }).join("<br/>") + "</p>";
+    }
+
+    // Texts of the docu are already HTML (see UiDocu)
+    function docuHtml(docu) {
+        const blocks = docu.blocks.map(function(block) {
+            if (block.Paragraph !== undefined) {
+                return "<p>" + block.Paragraph + "</p>";
+            } else if (block.List !== undefined) {
+                return "<ul>" + block.List.map(function(entry) { return "<li>" + entry + "</li>"; }).join("") + "</ul>";
+            } else {
+                return "<pre>" + block.Code + "</pre>";
+            }
+        }).join("");
+
+        const deprecated = docu.deprecated !== null ? "<p><b>Deprecated.</b> <i>" + docu.deprecated + "</i></p>" : "";
+        const returns = docu.returns !== null ? "<p><b>Returns:</b> " + docu.returns + "</p>" : "";
+        const see = docu.see.length ? "<p><b>See also:</b> " + docu.see.join(", ") + "</p>" : "";
+        const others = docu.other_tags.map(function(tag) {
+            return "<p><b>" + tag.name + ":</b> " + tag.text + "</p>";
+        }).join("");
+
+        return deprecated + blocks + tagsHtml("Parameters", docu.params) + returns +
+                tagsHtml("Throws", docu.throws) + see + others;
+    }
+
+    // Links to items (e.g.: item:a.b.IFoo) navigate to them, locations (see
+    // declaredAt) are opened in the editor if any, others are opened
+    onLinkActivated: {
+        if (link.startsWith("item:")) {
+            navigation.push(JSON.parse(vuk.get_root_node(link.substring("item:".length))));
+        } else if (link.startsWith("location:")) {
+            const location = JSON.parse(decodeURIComponent(link.substring("location:".length)));
+            if (!vuk.open_in_editor(location.path, location.line)) {
+                Qt.openUrlExternally(location.url);
+            }
+        } else {
+            Qt.openUrlExternally(link);
+        }
+    }
 
     text: {
         if (diagram.highlightedMember) {
             // Member docu
-            diagram.mainItem.node.item.name + "::<strong>" + diagram.highlightedMember.text + "</strong><br/><br/>" +
-                    "<i>" + diagram.highlightedMember.docu + "</i>";
+            diagram.mainItem.node.item.name + "::<strong>" + diagram.highlightedMember.text + "</strong>" +
+                    inheritedFrom(diagram.highlightedMember) +
+                    transactionCode(diagram.highlightedMember) +
+                    docuHtml(diagram.highlightedMember.docu) +
+                    declaredAt(diagram.highlightedMember.location);
         } else if (diagram.highlightedItem) {
             // Item docu
-            diagram.highlightedItem.item_type + " <strong>" + diagram.highlightedItem.name + "</strong>" + "<br/><br/>" +
-                    "<i>" + diagram.highlightedItem.docu + "</i>";
+            itemTitle(diagram.highlightedItem) +
+                    stabilityHtml(diagram.highlightedItem) +
+                    docuHtml(diagram.highlightedItem.docu) +
+                    declaredAt(diagram.highlightedItem.location);
         } else if (vuk.currentItem) {
             // Item docu
-            vuk.currentItem.item_type + " <strong>" + vuk.currentItem.name + "</strong>" + "<br/><br/>" +
-                    "<i>" + vuk.currentItem.docu + "</i>";
+            itemTitle(vuk.currentItem) +
+                    stabilityHtml(vuk.currentItem) +
+                    docuHtml(vuk.currentItem.docu) +
+                    declaredAt(vuk.currentItem.location);
         } else {
             // Nothing
             "";
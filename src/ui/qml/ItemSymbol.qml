@@ -18,6 +18,8 @@ Rectangle {
             case "Interface": "i"; break;
             case "Enum": "E"; break;
             case "Struct": "P"; break;
+            case "Union": "U"; break;
+            case "OpaqueStruct": "O"; break;
             default: "";
         }
         color: parent.border.color
@@ -0,0 +1,54 @@
+import QtQuick 2.9
+import QtQuick.Controls 2.15
+import QtQuick.Layouts 1.15
+import "./style"
+
+// Problems found in the model (e.g.: unresolved types)
+ListView {
+    id: problemsView
+    visible: count > 0
+
+    model: vuk.selectionState && vuk.selectionState.problems
+    clip: true
+
+    header: Rectangle {
+        width: problemsView.width
+        height: 30
+        color: "lightGray"
+
+        Label {
+            anchors.centerIn: parent
+            text: "Problems (" + problemsView.count + ")"
+            color: "black"
+        }
+    }
+
+    delegate: ItemDelegate {
+        width: parent ? parent.width : 0
+        font.family: Style.mainFontFamily
+        font.pointSize: Style.listViewItemFontSize
+
+        text: {
+            const severityColor = modelData.diagnostic.severity === "Error" ? Style.problemErrorColor
+                : Style.problemWarningColor
+
+            "<font color=\"" + severityColor + "\">" + modelData.diagnostic.severity.toLowerCase() + "</font> " +
+                modelData.item_name + ": " + modelData.diagnostic.message
+        }
+
+        ToolTip.visible: hovered
+        ToolTip.text: {
+            const diagnostic = modelData.diagnostic;
+            const range = diagnostic.range ? ":" + diagnostic.range.start.line + ":" + diagnostic.range.start.column : "";
+
+            "[" + diagnostic.code + "] " + (diagnostic.path || modelData.item_name) + range +
+                diagnostic.notes.map(function(note) { return "\n" + note }).join("")
+        }
+
+        onClicked: {
+            navigation.push(JSON.parse(vuk.get_root_node(modelData.key)));
+        }
+    }
+
+    ScrollIndicator.vertical: ScrollIndicator {}
+}
@@ -86,7 +86,10 @@ ColumnLayout {
                     leftPadding: 4
                     rightPadding: 4
                     text: delegate.text
-                    font: delegate.font
+                    font.family: delegate.font.family
+                    font.pointSize: delegate.font.pointSize
+                    font.strikeout: modelData.is_deprecated
+                    opacity: modelData.is_hidden ? Style.hiddenOpacity : 1
                     color: delegate.highlighted ? Style.listViewItemHighlightedTextColor : Style.listViewItemTextColor
                     elide: Text.ElideRight
                     verticalAlignment: Text.AlignVCenter
@@ -102,7 +105,7 @@ ColumnLayout {
                 const pkgColor = highlighted ? Style.listViewItemHighlightedAlternativeTextColor
                     : Style.listViewItemAlternativeTextColor
                 
-                modelData.name + "<font color=\"" + pkgColor + "\" size=\"2\"> (" + modelData.pkg_path + ")</font>"
+                modelData.name + "<font color=\"" + pkgColor + "\" size=\"2\"> (" + modelData.pkg_path + ", " + modelData.idl + ")</font>"
             }
             
             states: State {
@@ -119,4 +122,42 @@ ColumnLayout {
         
         ScrollIndicator.vertical: ScrollIndicator {}
     }
+
+    // Files which could not be (completely) parsed
+    ListView {
+        id: brokenFilesView
+        Layout.fillWidth: true
+        Layout.preferredHeight: Math.min(contentHeight, selection.height / 4)
+        visible: count > 0
+
+        model: vuk.selectionState && vuk.selectionState.broken_files
+        clip: true
+
+        header: Rectangle {
+            width: brokenFilesView.width
+            height: 30
+            color: "lightGray"
+
+            Label {
+                anchors.centerIn: parent
+                text: "Broken files (" + brokenFilesView.count + ")"
+                color: "black"
+            }
+        }
+
+        delegate: ItemDelegate {
+            id: brokenFileDelegate
+            width: parent ? parent.width : 0
+            font.family: Style.mainFontFamily
+            font.pointSize: Style.listViewItemFontSize
+            text: modelData.path.split("/").pop()
+
+            ToolTip.visible: hovered
+            ToolTip.text: modelData.path + "\n\n" + modelData.diagnostics.map(function(d) {
+                return (d.range ? d.range.start.line + ":" + d.range.start.column + ": " : "") + d.message
+            }).join("\n")
+        }
+
+        ScrollIndicator.vertical: ScrollIndicator {}
+    }
 }
@@ -34,6 +34,12 @@ ApplicationWindow {
             title: "&View"
             MenuItem { action: actions.decreaseFontSize }
             MenuItem { action: actions.increaseFontSize }
+            MenuSeparator {}
+            MenuItem { action: actions.hideHidden }
+            MenuItem { action: actions.hideDeprecated }
+            MenuSeparator {}
+            MenuItem { action: actions.followInArgs }
+            MenuItem { action: actions.followOutArgs }
         }
 
         Menu {
@@ -118,7 +124,9 @@ ApplicationWindow {
         }
         
         onError: {
-            console.log("QUICK says: there is an error:", error);
+            JSON.parse(error).forEach(function(d) {
+                console.log("QUICK says: there is an error:", d.code, d.path, d.range ? d.range.start.line : "", d.message);
+            });
         }
     }
     
@@ -172,7 +180,7 @@ ApplicationWindow {
 
             Flickable {
                 id: diagramScrollView
-                anchors.fill: parent
+                anchors { left: parent.left; right: parent.right; top: parent.top; bottom: problemsView.top }
 
                 //ScrollBar.vertical.policy: ScrollBar.AutoHide
                 //ScrollBar.horizontal.policy: ScrollBar.AutoHide
@@ -197,6 +205,12 @@ ApplicationWindow {
                 }
             }
             
+            Problems {
+                id: problemsView
+                anchors { left: parent.left; right: parent.right; bottom: parent.bottom }
+                height: visible ? Math.min(contentHeight, parent.height / 4) : 0
+            }
+
             //MouseArea {
             //    anchors.fill: parent
             //    acceptedButtons: Qt.NoButton
@@ -20,6 +20,7 @@ QtObject {
 
     readonly property real itemTitleFontSize: 10 * fontFactor
     readonly property real itemMemberFontSize: 9 * fontFactor
+    readonly property real itemAnnotationFontSize: 8 * fontFactor
     readonly property real listViewItemFontSize: 10
 
     readonly property string toolFontFamily: toolFont.name
@@ -44,6 +45,13 @@ QtObject {
     readonly property real connectorWidth: 2
     readonly property real connectorArrowLength: 12
     readonly property color connectorColor: "#787878"
+    readonly property color inheritanceConnectorColor: "#2b88d8"
+
+    // Hidden elements (@hide) are dimmed
+    readonly property real hiddenOpacity: 0.5
+
+    readonly property color problemErrorColor: "#d13438"
+    readonly property color problemWarningColor: "#ca5010"
     
     readonly property FontLoader mainFont: FontLoader {
         source: "qrc:/vuk_fonts/src/ui/fonts/segoeui.ttf"
@@ -59,6 +67,8 @@ QtObject {
             case "Interface": return "#ec3c13";
             case "Enum": return "#71b32b";
             case "Struct": return "#1b91db";
+            case "Union": return "#8e44ad";
+            case "OpaqueStruct": return "#7f8c8d";
             default: return "#EEEEEE";
         }
     }
@@ -1,9 +1,17 @@
-use std::{cmp::Ordering, collections::HashMap, fs::File, io::Read, path::PathBuf, rc::Rc};
-use walkdir::WalkDir;
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    fs::File,
+    io::Read,
+    path::PathBuf,
+    rc::Rc,
+};
+use walkdir::{DirEntry, WalkDir};
 
-use crate::parser::{aidl, error::ParseContentError};
-use crate::parser::{aidl::ast, error::ParseFileError};
-use crate::ui_state::{UiEdge, UiItem, UiNode, UiNodeState, UiSelection};
+use crate::parser::{error::ParseFileError, front_end::Registry};
+use crate::ui_state::{
+    UiBrokenFile, UiEdge, UiEdgeKind, UiItem, UiNode, UiNodeState, UiProblem, UiSelection,
+};
 use crate::{model, model::Model, ui_state::UiSelectionItem};
 
 pub trait UiListener {
@@ -15,59 +23,118 @@ pub trait UiListener {
 pub struct UiController {
     pub model: Model,
     pub selection: UiSelection,
+    pub broken_files: Vec<ParseFileError>,
+    pub problems: Vec<model::ItemDiagnostic>,
+    pub filter: UiFilter,
     listeners: Vec<Box<dyn UiListener>>,
 }
 
+// Elements left out of the dependency and reference graphs (e.g.: only
+// following the out args of the methods with DataFlow::Out)
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UiFilter {
+    pub hide_hidden: bool,
+    pub hide_deprecated: bool,
+    pub data_flow: model::DataFlow,
+}
+
+impl UiFilter {
+    fn excludes(&self, status: model::Status) -> bool {
+        (self.hide_hidden && status.is_hidden) || (self.hide_deprecated && status.is_deprecated)
+    }
+}
+
 impl UiController {
     pub fn new(model: Model) -> Self {
         // Create selection items
         let selection = UiSelection {
             items: create_selection_items(&model.items),
+            broken_files: Vec::new(),
+            problems: Vec::new(),
             current: -1,
         };
 
         UiController {
             model,
             selection,
+            broken_files: Vec::new(),
+            problems: Vec::new(),
+            filter: UiFilter::default(),
             listeners: Vec::new(),
         }
     }
 
+    // The files which could not be (completely) parsed are listed in the
+    // selection and their valid items are still part of the model
+    pub fn new_with_broken_files(model: Model, broken_files: Vec<ParseFileError>) -> Self {
+        Self::new_with_problems(model, broken_files, Vec::new())
+    }
+
+    // Same as new_with_broken_files, also listing the problems found while
+    // resolving the model
+    pub fn new_with_problems(
+        model: Model,
+        broken_files: Vec<ParseFileError>,
+        problems: Vec<model::ItemDiagnostic>,
+    ) -> Self {
+        let mut controller = Self::new(model);
+
+        controller.selection.broken_files = broken_files
+            .iter()
+            .map(UiBrokenFile::new_with_parse_file_error)
+            .collect();
+        controller.broken_files = broken_files;
+
+        controller.selection.problems = problems
+            .iter()
+            .map(|p| UiProblem::new_with_item_diagnostic(p, &controller.model.items))
+            .collect();
+        controller.problems = problems;
+
+        controller
+    }
+
     pub fn open(path: &PathBuf) -> Result<Self, Box<dyn std::error::Error>> {
-        let mut aidl_file_entries = WalkDir::new(path)
+        // A single walk loads the files of all the IDLs
+        let mut front_ends = Registry::default();
+        let idl_file_entries: Vec<DirEntry> = WalkDir::new(path)
             .min_depth(1)
             .into_iter()
             .filter_map(|e| e.ok())
             .filter(|e| e.file_type().is_file())
-            .filter(|e| {
-                e.path()
-                    .extension()
-                    .and_then(|ext| ext.to_str())
-                    .and_then(|ext| Some(ext == "aidl"))
-                    .unwrap_or(false)
-            });
+            .filter(|e| front_ends.matches(e.path()))
+            .collect();
 
-        let mut ast_files = Vec::<ast::File>::new();
+        let mut broken_files = Vec::<ParseFileError>::new();
 
-        aidl_file_entries.try_for_each(|e| {
+        idl_file_entries.iter().try_for_each(|e| {
             let mut file = File::open(e.path())?;
 
             let mut buffer = String::new();
             file.read_to_string(&mut buffer)?;
 
-            let ast_file = aidl::parse(&buffer).map_err(|content_error: ParseContentError| {
-                ParseFileError {
+            let mut diagnostics = front_ends.parse(e.path(), &buffer);
+
+            if !diagnostics.is_empty() {
+                let path_string = e.path().to_string_lossy().to_string();
+                diagnostics
+                    .iter_mut()
+                    .for_each(|d| d.path = Some(path_string.clone()));
+
+                broken_files.push(ParseFileError {
                     path: e.path().into(),
-                    content_error,
-                }
-            })?;
+                    diagnostics,
+                });
+            }
 
-            ast_files.push(ast_file);
             Ok(()) as Result<_, Box<dyn std::error::Error>>
         })?;
 
-        let model = aidl::create_model(ast_files);
-        Ok(Self::new(model))
+        let mut model = front_ends.create_model();
+        let mut problems = model.resolve_types();
+        problems.append(&mut model.validate());
+
+        Ok(Self::new_with_problems(model, broken_files, problems))
     }
 
     pub fn add_listener(&mut self, listener: Box<dyn UiListener>) {
@@ -78,7 +145,7 @@ impl UiController {
         let model_item = self.model.items.get(key);
 
         if let Some(model_item) = model_item {
-            let root_item = UiItem::new_for_model_item(model_item);
+            let root_item = UiItem::new_for_model_item(model_item, &self.model);
             let root_node = UiNode {
                 item: root_item,
                 state: UiNodeState::Collapsed,
@@ -101,13 +168,29 @@ impl UiController {
             .ok_or_else(|| model::Error::NotFound(key.into()))?;
 
         // Look for sub-nodes
-        let model_sub_items = self.model.find_dependencies(model_item);
+        let model_sub_items = self
+            .model
+            .find_dependencies_with_data_flow(model_item, self.filter.data_flow);
 
+        // Inheritance edges are kept without members (e.g.: a super-interface
+        // without methods)
         let edges = model_sub_items
             .into_iter()
-            .map(|(indices, item)| (indices, UiItem::new_for_model_item(&item)))
-            .map(|(indices, item)| {
+            .filter(|(_, _, item)| !self.filter.excludes(item.get_status()))
+            .map(|(kind, indices, item)| (kind, self.filtered_indices(model_item, indices), item))
+            .filter(|(kind, indices, _)| {
+                *kind == model::DependencyKind::Inheritance || !indices.is_empty()
+            })
+            .map(|(kind, indices, item)| {
+                (
+                    kind,
+                    indices,
+                    UiItem::new_for_model_item(&item, &self.model),
+                )
+            })
+            .map(|(kind, indices, item)| {
                 (
+                    kind,
                     indices,
                     UiNode {
                         item,
@@ -115,9 +198,10 @@ impl UiController {
                     },
                 )
             })
-            .map(|(indices, node)| UiEdge {
+            .map(|(kind, indices, node)| UiEdge {
                 to: node,
                 from_indices: indices,
+                kind: UiEdgeKind::of_dependency_kind(kind),
             })
             .collect();
 
@@ -134,13 +218,27 @@ impl UiController {
             .get(key)
             .ok_or_else(|| model::Error::NotFound(key.into()))?;
 
-        let references = self.model.find_references(model_item);
+        let references = self
+            .model
+            .find_references_with_data_flow(model_item, self.filter.data_flow);
 
         let edges = references
             .into_iter()
-            .map(|(indices, item)| (indices, UiItem::new_for_model_item(&item)))
-            .map(|(indices, item)| {
+            .filter(|(_, _, item)| !self.filter.excludes(item.get_status()))
+            .map(|(kind, indices, item)| (kind, self.filtered_indices(&item, indices), item))
+            .filter(|(kind, indices, _)| {
+                *kind == model::DependencyKind::Inheritance || !indices.is_empty()
+            })
+            .map(|(kind, indices, item)| {
                 (
+                    kind,
+                    indices,
+                    UiItem::new_for_model_item(&item, &self.model),
+                )
+            })
+            .map(|(kind, indices, item)| {
+                (
+                    kind,
                     UiNode {
                         item,
                         state: UiNodeState::Collapsed,
@@ -148,14 +246,38 @@ impl UiController {
                     indices,
                 )
             })
-            .map(|(node, indices)| UiEdge {
+            .map(|(kind, node, indices)| UiEdge {
                 to: node,
                 from_indices: indices,
+                kind: UiEdgeKind::of_dependency_kind(kind),
             })
             .collect();
 
         Ok(edges)
     }
+
+    // Indices of the members of the item which are not excluded by the filter,
+    // including the methods inherited by an interface (numbered after its own
+    // members)
+    fn filtered_indices(&self, item: &model::Item, indices: HashSet<usize>) -> HashSet<usize> {
+        let inherited_methods = match item {
+            model::Item::Interface(i) => self.model.find_inherited_methods(i),
+            _ => Vec::new(),
+        };
+
+        indices
+            .into_iter()
+            .filter(|index| {
+                let status = item.get_member_status(*index).or_else(|| {
+                    inherited_methods
+                        .iter()
+                        .find(|m| m.index == *index)
+                        .map(|m| m.get_method().get_status())
+                });
+                !status.is_some_and(|status| self.filter.excludes(status))
+            })
+            .collect()
+    }
 }
 
 fn create_selection_items(items: &HashMap<model::Key, Rc<model::Item>>) -> Vec<UiSelectionItem> {
@@ -176,3 +298,30 @@ fn create_selection_items(items: &HashMap<model::Key, Rc<model::Item>>) -> Vec<U
     });
     ret
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::hidl;
+
+    #[test]
+    fn test_filtered_indices() {
+        let files = [
+            "package a@1.0;\ninterface IBase { /** @deprecated */ old(); ping(); };\n",
+            "package a@1.0;\ninterface IFoo extends IBase { /** @deprecated */ foo(); bar(); };\n",
+        ]
+        .iter()
+        .map(|s| hidl::parse(s).unwrap())
+        .collect();
+        let mut model = hidl::create_model(files);
+        model.resolve_types();
+
+        let mut controller = UiController::new(model);
+        controller.filter.hide_deprecated = true;
+
+        // foo and bar, then the inherited old and ping
+        let item = controller.model.items["a@1.0.IFoo"].clone();
+        let indices = controller.filtered_indices(&item, HashSet::from([0, 1, 2, 3]));
+        assert_eq!(indices, HashSet::from([1, 3]));
+    }
+}
@@ -1,47 +1,117 @@
 use serde::Serialize;
 use std::{
     collections::{HashMap, HashSet},
+    path::Path,
     rc::Rc,
 };
 
+use crate::diagnostic::{Diagnostic, Severity};
 use crate::model;
+use crate::parser::error::ParseFileError;
 
 #[derive(Clone, Debug, Default, Serialize)]
 pub struct UiSelection {
     pub items: Vec<UiSelectionItem>,
+    pub broken_files: Vec<UiBrokenFile>,
+    pub problems: Vec<UiProblem>,
     pub current: i32,
 }
 
 impl UiSelection {
     pub fn new_with_model_items(model_items: &HashMap<model::Key, Rc<model::Item>>) -> Self {
         let mut items: Vec<UiSelectionItem> = model_items
-                .iter()
-                .map(|(_, i)| i)
-                .map(UiSelectionItem::new_with_model_item)
-                .collect();
+            .iter()
+            .map(|(_, i)| i)
+            .map(UiSelectionItem::new_with_model_item)
+            .collect();
         items.sort_by_cached_key(|i| i.pkg_path.clone());
         UiSelection {
             items,
+            broken_files: Vec::new(),
+            problems: Vec::new(),
             current: -1,
         }
     }
 }
 
+// Convert the error to a JSON array of diagnostics for the QML error signal
+pub fn diagnostics_json(error: &(dyn std::error::Error + 'static)) -> String {
+    let diagnostics = if let Some(diagnostic) = error.downcast_ref::<Diagnostic>() {
+        vec![diagnostic.clone()]
+    } else if let Some(parse_file_error) = error.downcast_ref::<ParseFileError>() {
+        parse_file_error.diagnostics.clone()
+    } else {
+        vec![Diagnostic::new(Severity::Error, "E000", error.to_string())]
+    };
+
+    serde_json::to_string(&diagnostics).unwrap()
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct UiBrokenFile {
+    pub path: String,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl UiBrokenFile {
+    pub fn new_with_parse_file_error(error: &ParseFileError) -> Self {
+        UiBrokenFile {
+            path: error.path.to_string_lossy().to_string(),
+            diagnostics: error.diagnostics.clone(),
+        }
+    }
+}
+
+// Problem found in the model (e.g.: unresolved type)
+#[derive(Clone, Debug, Serialize)]
+pub struct UiProblem {
+    pub key: model::Key,
+    pub item_name: String,
+    pub member_index: Option<usize>,
+    pub diagnostic: Diagnostic,
+}
+
+impl UiProblem {
+    pub fn new_with_item_diagnostic(
+        item_diagnostic: &model::ItemDiagnostic,
+        model_items: &HashMap<model::Key, Rc<model::Item>>,
+    ) -> Self {
+        UiProblem {
+            key: item_diagnostic.key.clone(),
+            item_name: model_items
+                .get(&item_diagnostic.key)
+                .map(|i| i.get_qualified_name().to_string())
+                .unwrap_or_else(|| item_diagnostic.key.clone()),
+            member_index: item_diagnostic.member_index,
+            diagnostic: item_diagnostic.diagnostic.clone(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize)]
 pub struct UiSelectionItem {
     pub item_type: ItemType,
     pub name: String,
     pub pkg_path: String,
     pub key: model::Key,
+    // e.g.: AIDL
+    pub idl: String,
+    pub is_deprecated: bool,
+    pub is_hidden: bool,
 }
 
 impl UiSelectionItem {
     pub fn new_with_model_item(model_item: &Rc<model::Item>) -> Self {
+        let status = model_item.get_status();
+
         UiSelectionItem {
             item_type: ItemType::of_model_item(model_item),
-            name: model_item.get_name().to_string(),
+            name: model_item.get_qualified_name().to_string(),
             pkg_path: model_item.get_pkg().path.to_string(),
             key: model_item.get_key().clone(),
+            idl: model_item.get_idl().to_string(),
+            is_deprecated: status.is_deprecated,
+            is_hidden: status.is_hidden,
         }
     }
 }
@@ -51,6 +121,8 @@ pub enum ItemType {
     Interface = 0,
     Struct = 1,
     Enum = 2,
+    Union = 3,
+    OpaqueStruct = 4,
 }
 
 impl ItemType {
@@ -59,6 +131,8 @@ impl ItemType {
             model::Item::Interface(_) => ItemType::Interface,
             model::Item::Struct(_) => ItemType::Struct,
             model::Item::Enum(_) => ItemType::Enum,
+            model::Item::Union(_) => ItemType::Union,
+            model::Item::OpaqueStruct(_) => ItemType::OpaqueStruct,
         }
     }
 }
@@ -74,25 +148,61 @@ pub struct UiItem {
     pub key: model::Key,
     pub item_type: ItemType,
     pub name: String,
-    pub docu: String,
+    pub docu: UiDocu,
+    pub annotations: Vec<String>,
+    pub location: UiLocation,
+    // e.g.: AIDL
+    pub idl: String,
     pub members: Vec<UiMember>,
+    pub is_deprecated: bool,
+    pub is_hidden: bool,
+    pub is_stable: bool,
+    // Version of an interface frozen in an AIDL API dump
+    pub frozen_version: Option<u32>,
+    // e.g.: getInterfaceVersion () -> int = 16777215
+    pub meta_transactions: Vec<String>,
 }
 
 impl UiItem {
-    pub fn new_for_model_item(model_item: &model::Item) -> Self {
+    // The model is needed for the members inherited from other items (e.g.:
+    // the methods of the super-interfaces)
+    pub fn new_for_model_item(model_item: &model::Item, model: &model::Model) -> Self {
+        let status = model_item.get_status();
+
         UiItem {
             key: model_item.get_key().clone(),
             item_type: ItemType::of_model_item(model_item),
-            name: model_item.get_name().to_string(),
-            docu: model_item.get_docu().to_string(),
+            name: match model_item {
+                model::Item::Struct(s) if !s.type_params.is_empty() => format!(
+                    "{}<{}>",
+                    model_item.get_qualified_name(),
+                    s.type_params.join(", ")
+                ),
+                _ => model_item.get_qualified_name().to_string(),
+            },
+            docu: UiDocu::new_from_javadoc(model_item.get_docu()),
+            annotations: annotation_strings(model_item.get_annotations()),
+            location: UiLocation::new_from_model_location(model_item.get_location()),
+            idl: model_item.get_idl().to_string(),
             members: match model_item {
                 model::Item::Interface(i) => {
                     let const_members = i.consts.iter().map(|c| UiMember::new_from_model_const(c));
 
-                    let method_members =
-                        i.methods.iter().map(|m| UiMember::new_from_model_method(m));
+                    let method_members = i
+                        .methods
+                        .iter()
+                        .map(|m| UiMember::new_from_model_method(m, i.pkg.idl));
+
+                    let inherited_members = model
+                        .find_inherited_methods(i)
+                        .iter()
+                        .map(UiMember::new_from_inherited_method)
+                        .collect::<Vec<UiMember>>();
 
-                    const_members.chain(method_members).collect()
+                    const_members
+                        .chain(method_members)
+                        .chain(inherited_members)
+                        .collect()
                 }
                 model::Item::Struct(s) => s
                     .members
@@ -104,6 +214,41 @@ impl UiItem {
                     .iter()
                     .map(UiMember::new_from_enum_element)
                     .collect(),
+                model::Item::Union(u) => u
+                    .members
+                    .iter()
+                    .map(UiMember::new_from_model_member)
+                    .collect(),
+                model::Item::OpaqueStruct(o) => o
+                    .native_types
+                    .iter()
+                    .enumerate()
+                    .map(|(index, native_type)| {
+                        UiMember::new_from_native_type(index, native_type, &o.location)
+                    })
+                    .collect(),
+            },
+            is_deprecated: status.is_deprecated,
+            is_hidden: status.is_hidden,
+            is_stable: matches!(model_item, model::Item::Interface(i) if i.is_stable()),
+            frozen_version: match model_item {
+                model::Item::Interface(i) => i.get_frozen_version(),
+                _ => None,
+            },
+            meta_transactions: match model_item {
+                model::Item::Interface(i) => i
+                    .get_meta_transactions()
+                    .iter()
+                    .map(|t| {
+                        format!(
+                            "{} () -> {} = {}",
+                            t.get_name(),
+                            t.get_return_type_name(),
+                            t.get_value()
+                        )
+                    })
+                    .collect(),
+                _ => Vec::new(),
             },
         }
     }
@@ -112,8 +257,17 @@ impl UiItem {
 #[derive(Clone, Debug, Serialize)]
 pub struct UiMember {
     text: String,
-    docu: String,
+    docu: UiDocu,
+    annotations: Vec<String>,
+    location: UiLocation,
     index: usize,
+    is_deprecated: bool,
+    is_hidden: bool,
+    // Key of the item declaring an inherited member (e.g.: the
+    // super-interface of an inherited method)
+    inherited_from: Option<model::Key>,
+    // Code of the binder transaction of an AIDL method
+    transaction_code: Option<u32>,
 }
 
 impl UiMember {
@@ -122,60 +276,307 @@ impl UiMember {
             text: format!(
                 "const {}: {} = {}",
                 model_const.name,
-                model_const.const_type.get_name(),
-                model_const.value
+                model_const.const_type,
+                format_value(&model_const.value, &model_const.computed_value)
             ),
-            docu: model_const.docu.clone(),
+            docu: UiDocu::new_from_javadoc(&model_const.docu),
+            annotations: annotation_strings(&model_const.annotations),
+            location: UiLocation::new_from_model_location(&model_const.location),
             index: model_const.index,
+            is_deprecated: model_const.get_status().is_deprecated,
+            is_hidden: model_const.get_status().is_hidden,
+            inherited_from: None,
+            transaction_code: None,
         }
     }
 
     pub fn new_from_model_member(model_member: &model::Member) -> Self {
+        let text = format!(
+            "{}: {}",
+            model_member.arg.name,
+            model_member.arg.get_full_type_name(),
+        );
+
         UiMember {
-            text: format!(
-                "{}: {}",
-                model_member.arg.name,
-                model_member.arg.arg_type.get_name(), // TODO: incl. generic
-            ),
-            docu: model_member.docu.clone(),
+            text: match &model_member.default_value {
+                Some(value) => format!(
+                    "{} = {}",
+                    text,
+                    format_value(value, &model_member.computed_default_value)
+                ),
+                None => text,
+            },
+            docu: UiDocu::new_from_javadoc(&model_member.docu),
+            annotations: annotation_strings(&model_member.annotations),
+            location: UiLocation::new_from_model_location(&model_member.location),
             index: model_member.index,
+            is_deprecated: model_member.get_status().is_deprecated,
+            is_hidden: model_member.get_status().is_hidden,
+            inherited_from: None,
+            transaction_code: None,
         }
     }
 
-    pub fn new_from_model_method(model_method: &model::Method) -> Self {
+    pub fn new_from_model_method(model_method: &model::Method, idl: model::Idl) -> Self {
         let arg_string = model_method
             .args
             .iter()
             .map(|arg| {
-                //let arg_name = if arg.name.is_empty() { "_" } else { &arg.name };
-                //format!("{}: {}", arg_name, arg.arg_type.get_name())
-                arg.arg_type.get_name().to_string()
+                // e.g.: @nullable out result: List<Foo>
+                let mut arg_parts = annotation_strings(&arg.annotations);
+                if arg.direction != model::Direction::Unspecified {
+                    arg_parts.push(arg.direction.to_string());
+                }
+                if arg.name.is_empty() {
+                    arg_parts.push(arg.get_full_type_name());
+                } else {
+                    arg_parts.push(format!("{}: {}", arg.name, arg.get_full_type_name()));
+                }
+
+                arg_parts.join(" ")
             })
             .collect::<Vec<String>>()
             .join(", ");
 
+        let one_way_prefix = if model_method.is_one_way {
+            "oneway "
+        } else {
+            ""
+        };
+
         UiMember {
             text: if model_method.return_arg.arg_type.is_void() {
-                format!("{} ({})", model_method.name, arg_string)
+                format!("{}{} ({})", one_way_prefix, model_method.name, arg_string)
             } else {
                 format!(
-                    "{} ({}) -> {}",
+                    "{}{} ({}) -> {}",
+                    one_way_prefix,
                     model_method.name,
                     arg_string,
-                    model_method.return_arg.arg_type.get_name()
+                    model_method.return_arg.get_full_type_name()
                 )
             },
-            docu: model_method.docu.clone(),
+            docu: UiDocu::new_from_javadoc(&model_method.docu),
+            annotations: annotation_strings(&model_method.annotations),
+            location: UiLocation::new_from_model_location(&model_method.location),
             index: model_method.index,
+            is_deprecated: model_method.get_status().is_deprecated,
+            is_hidden: model_method.get_status().is_hidden,
+            inherited_from: None,
+            // Only AIDL methods are binder transactions
+            transaction_code: match idl {
+                model::Idl::Aidl => model_method.transaction_code.get_value(),
+                _ => None,
+            },
+        }
+    }
+
+    // Same as the method in its super-interface, with the index of the
+    // inheriting interface
+    pub fn new_from_inherited_method(inherited_method: &model::InheritedMethod) -> Self {
+        UiMember {
+            index: inherited_method.index,
+            inherited_from: Some(inherited_method.from.get_key().clone()),
+            ..UiMember::new_from_model_method(
+                inherited_method.get_method(),
+                inherited_method.from.get_idl(),
+            )
+        }
+    }
+
+    // e.g.: cpp_header "a/b/Foo.h"
+    pub fn new_from_native_type(
+        index: usize,
+        native_type: &model::NativeType,
+        location: &model::Location,
+    ) -> Self {
+        UiMember {
+            text: native_type.to_string(),
+            index,
+            docu: UiDocu::default(),
+            annotations: Vec::new(),
+            location: UiLocation::new_from_model_location(location),
+            is_deprecated: false,
+            is_hidden: false,
+            inherited_from: None,
+            transaction_code: None,
         }
     }
 
     pub fn new_from_enum_element(element: &model::EnumElement) -> Self {
+        // e.g.: RED = 0, or FLAG_B = 1 << 1 (2)
+        let text = match (&element.value, &element.computed_value) {
+            (Some(value), computed_value) => {
+                format!("{} = {}", element.name, format_value(value, computed_value))
+            }
+            (None, Some(computed_value)) => format!("{} = {}", element.name, computed_value),
+            (None, None) => element.name.clone(),
+        };
+
         UiMember {
-            text: element.name.clone(),
+            text,
             index: element.index,
-            docu: element.docu.clone(),
+            docu: UiDocu::new_from_javadoc(&element.docu),
+            annotations: Vec::new(),
+            location: UiLocation::new_from_model_location(&element.location),
+            is_deprecated: element.get_status().is_deprecated,
+            is_hidden: element.get_status().is_hidden,
+            inherited_from: None,
+            transaction_code: None,
+        }
+    }
+}
+
+// Javadoc of an item or member, its texts are HTML for the documentation
+// panel, links to items using the "item:" scheme (e.g.:
+// <a href="item:a.b.IFoo">IFoo.bar</a>)
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct UiDocu {
+    pub blocks: Vec<UiDocuBlock>,
+    pub params: Vec<UiDocuTag>,
+    pub returns: Option<String>,
+    pub throws: Vec<UiDocuTag>,
+    pub deprecated: Option<String>,
+    pub is_hidden: bool,
+    pub see: Vec<String>,
+    pub other_tags: Vec<UiDocuTag>,
+}
+
+impl UiDocu {
+    pub fn new_from_javadoc(javadoc: &model::Javadoc) -> Self {
+        UiDocu {
+            blocks: javadoc
+                .blocks
+                .iter()
+                .map(|b| match b {
+                    model::Block::Paragraph(text) => UiDocuBlock::Paragraph(html_text(text)),
+                    model::Block::List(entries) => {
+                        UiDocuBlock::List(entries.iter().map(html_text).collect())
+                    }
+                    model::Block::Code(code) => UiDocuBlock::Code(escape_html(code)),
+                })
+                .collect(),
+            params: javadoc.params.iter().map(UiDocuTag::new_from_tag).collect(),
+            returns: javadoc.returns.as_ref().map(html_text),
+            throws: javadoc.throws.iter().map(UiDocuTag::new_from_tag).collect(),
+            deprecated: javadoc.deprecated.as_ref().map(html_text),
+            is_hidden: javadoc.is_hidden,
+            see: javadoc.see.iter().map(html_text).collect(),
+            other_tags: javadoc
+                .other_tags
+                .iter()
+                .map(UiDocuTag::new_from_tag)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub enum UiDocuBlock {
+    Paragraph(String),
+    List(Vec<String>),
+    Code(String),
+}
+
+// e.g.: the name and the text of a @param tag
+#[derive(Clone, Debug, Serialize)]
+pub struct UiDocuTag {
+    pub name: String,
+    pub text: String,
+}
+
+impl UiDocuTag {
+    fn new_from_tag(tag: &model::Tag) -> Self {
+        UiDocuTag {
+            name: escape_html(&tag.name),
+            text: html_text(&tag.text),
+        }
+    }
+}
+
+fn html_text(text: &model::Text) -> String {
+    text.iter()
+        .map(|inline| match inline {
+            model::Inline::Text(t) => escape_html(t),
+            model::Inline::Code(c) => format!("<code>{}</code>", escape_html(c)),
+            model::Inline::Link(link) => {
+                // e.g.: IFoo#bar is shown as IFoo.bar, and #bar as bar
+                let label = link
+                    .label
+                    .clone()
+                    .unwrap_or_else(|| link.target.trim_start_matches('#').replace('#', "."));
+                match link.key {
+                    Some(ref key) => {
+                        format!("<a href=\"item:{}\">{}</a>", key, escape_html(&label))
+                    }
+                    None => format!("<code>{}</code>", escape_html(&label)),
+                }
+            }
+        })
+        .collect()
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Where an item or member was declared (e.g.: "IFoo.aidl:42"), so that
+// the UI can show it and open the file
+#[derive(Clone, Debug, Serialize)]
+pub struct UiLocation {
+    pub text: String,
+    // Absolute path of the file, and its file URL (e.g.: file:///a/b/IFoo.aidl)
+    pub path: Option<String>,
+    pub url: Option<String>,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl UiLocation {
+    pub fn new_from_model_location(location: &model::Location) -> Self {
+        // Only show the file name
+        let file_name = location
+            .path
+            .as_ref()
+            .and_then(|path| Path::new(path).file_name())
+            .map(|file_name| file_name.to_string_lossy().to_string());
+
+        // The paths are relative to the working directory when opened from
+        // the command line
+        let path = location.path.as_ref().map(|path| {
+            std::env::current_dir()
+                .map(|dir| dir.join(path))
+                .unwrap_or_else(|_| path.into())
+                .to_string_lossy()
+                .to_string()
+        });
+
+        UiLocation {
+            text: model::Location::new(file_name.as_deref(), location.range).to_string(),
+            url: path.as_ref().map(|path| file_url(path)),
+            path,
+            line: location.range.start.line,
+            column: location.range.start.column,
+        }
+    }
+}
+
+fn annotation_strings(annotations: &[model::Annotation]) -> Vec<String> {
+    annotations.iter().map(|a| a.to_string()).collect()
+}
+
+// Show the computed value next to the expression when they differ (e.g.: 1 << 2 (4))
+fn format_value(expression: &model::Expression, computed_value: &Option<model::Value>) -> String {
+    let expression_text = expression.to_string();
+
+    match computed_value {
+        Some(value) if value.to_string() != expression_text => {
+            format!("{} ({})", expression_text, value)
         }
+        _ => expression_text,
     }
 }
 
@@ -189,4 +590,99 @@ pub enum UiNodeState {
 pub struct UiEdge {
     pub to: UiNode,
     pub from_indices: HashSet<usize>,
+    pub kind: UiEdgeKind,
+}
+
+// Drawn differently (e.g.: an inheritance edge has its own color)
+#[derive(Clone, Debug, Serialize)]
+pub enum UiEdgeKind {
+    Reference,
+    Inheritance,
+}
+
+impl UiEdgeKind {
+    pub fn of_dependency_kind(kind: model::DependencyKind) -> Self {
+        match kind {
+            model::DependencyKind::Reference => UiEdgeKind::Reference,
+            model::DependencyKind::Inheritance => UiEdgeKind::Inheritance,
+        }
+    }
+}
+
+// e.g.: file:///C:/a/IFoo.aidl for C:\\a\\IFoo.aidl
+fn file_url(absolute_path: &str) -> String {
+    let path = absolute_path.replace('\\', "/");
+    if path.starts_with('/') {
+        format!("file://{}", path)
+    } else {
+        format!("file:///{}", path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::aidl;
+
+    #[test]
+    fn test_diagnostics_json() {
+        let diagnostic = aidl::parse("package a;\ninterface IFoo { void f() }\n").unwrap_err();
+        let json: serde_json::Value =
+            serde_json::from_str(&diagnostics_json(diagnostic.as_ref())).unwrap();
+        assert_eq!(json[0]["code"], "P001");
+        assert_eq!(json[0]["severity"], "Error");
+        assert_eq!(json[0]["range"]["start"]["line"], 2);
+        assert_eq!(json[0]["source_line"], "interface IFoo { void f() }");
+
+        let parse_file_error = ParseFileError {
+            path: "IFoo.aidl".into(),
+            diagnostics: vec![*diagnostic.clone(), *diagnostic],
+        };
+        let json: serde_json::Value =
+            serde_json::from_str(&diagnostics_json(&parse_file_error)).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), 2);
+
+        let io_error = std::io::Error::new(std::io::ErrorKind::NotFound, "no such file");
+        let json: serde_json::Value = serde_json::from_str(&diagnostics_json(&io_error)).unwrap();
+        assert_eq!(json[0]["code"], "E000");
+        assert_eq!(json[0]["message"], "no such file");
+        assert_eq!(json[0]["range"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn test_location() {
+        let range = crate::diagnostic::SourceRange::new(
+            crate::diagnostic::SourcePosition::new(4, 5),
+            crate::diagnostic::SourcePosition::new(4, 9),
+        );
+        let location =
+            UiLocation::new_from_model_location(&model::Location::new(Some("a/IFoo.aidl"), range));
+        let absolute_path = std::env::current_dir().unwrap().join("a").join("IFoo.aidl");
+        assert_eq!(location.text, "IFoo.aidl:4");
+        assert_eq!(location.path.as_deref(), absolute_path.to_str());
+        assert!(location.url.unwrap().ends_with("/a/IFoo.aidl"));
+        assert_eq!((location.line, location.column), (4, 5));
+
+        assert_eq!(file_url("/a/IFoo.aidl"), "file:///a/IFoo.aidl");
+        assert_eq!(file_url("C:\\a\\IFoo.aidl"), "file:///C:/a/IFoo.aidl");
+    }
+
+    #[test]
+    fn test_transaction_code() {
+        let file =
+            aidl::parse("package a; interface IFoo { void f() = 4294967295; void g() = 2; }")
+                .unwrap();
+        let model = aidl::create_model(vec![file]);
+        let methods = match *model.items["a.IFoo"] {
+            model::Item::Interface(ref i) => &i.methods,
+            _ => unreachable!(),
+        };
+
+        // Too large for a transaction code (see validate_transaction_codes)
+        let transaction_codes: Vec<Option<u32>> = methods
+            .iter()
+            .map(|m| UiMember::new_from_model_method(m, model::Idl::Aidl).transaction_code)
+            .collect();
+        assert_eq!(transaction_codes, vec![None, Some(3)]);
+    }
 }